@@ -1,6 +1,7 @@
 import { assertEquals, assert } from "jsr:@std/assert";
 import { FrameDecoder, FrameHeader, LenBE } from "../src/setu/frame.ts";
-import { encodeAsLastFrame } from "../src/setu/frame.writer.ts";
+import { encodeAsLastFrame, encodeCompressed } from "../src/setu/frame.writer.ts";
+import { ENCODINGS } from "../src/setu/compression.ts";
 import { Status } from "../src/status.ts";
 import { Stream } from "../src/utils/stream.ts";
 
@@ -33,3 +34,19 @@ Deno.test("last frame", async () => {
     assert(data.status == Status.Ok);
     assertEquals([...data.bytes], [54, 55]);
 });
+
+Deno.test("compressed frame", async () => {
+    let msg = new Uint8Array(2048).fill(42);
+
+    for (let encoding of ENCODINGS) {
+        let frame = await encodeCompressed(encoding, e => e.push(msg), Status.Ok);
+        assert(FrameHeader.parse(frame[0]).isCompressed);
+        assert(frame.length < msg.length);
+
+        let reader = new FrameDecoder(new Stream(ReadableStream.from([frame]).getReader()), encoding);
+        let { data } = await reader.parseFrame();
+
+        assert(data.type == "trailer");
+        assertEquals([...data.bytes], [...msg]);
+    }
+});
@@ -9,9 +9,17 @@ mod data {
     include!("../tests/data.rs");
 }
 
+#[path = "utils/counting_alloc.rs"]
+mod counting_alloc;
+
 use crate::data::Types;
 use criterion::{Criterion, criterion_group, criterion_main};
 use lipi::*;
+fn allocations(f: impl FnOnce()) -> usize {
+    let start = counting_alloc::count();
+    f();
+    counting_alloc::count() - start
+}
 
 type Data = (Types, Types, Types);
 
@@ -25,10 +33,37 @@ fn benchmark(c: &mut Criterion) {
     let raw = data.to_bytes().unwrap();
     assert_eq!(data, Data::decode(&mut &raw[..]).unwrap());
 
+    let growing = allocations(|| {
+        let mut buf = Vec::new();
+        data.encode(&mut buf).unwrap();
+    });
+    let pre_sized = allocations(|| {
+        data.to_bytes().unwrap();
+    });
+    println!("allocations per encode: {growing} (growing buffer), {pre_sized} (pre-sized)");
+
     run_bench(c, "encode", || {
         assert_eq!(data.to_bytes().unwrap().len(), raw.len());
     });
 
+    run_bench(c, "encode (growing buffer)", || {
+        let mut buf = Vec::new();
+        data.encode(&mut buf).unwrap();
+        assert_eq!(buf.len(), raw.len());
+    });
+
+    run_bench(c, "encoded_len", || {
+        assert_eq!(data.encoded_len(), raw.len());
+    });
+
+    let mut buf = Vec::with_capacity(raw.len());
+    c.bench_function("encode (reused buffer)", |b| {
+        b.iter(|| {
+            buf.clear();
+            data.encode(&mut buf).unwrap();
+        })
+    });
+
     run_bench(c, "decode", || {
         let new_data = Data::decode(&mut &raw[..]).unwrap();
         assert_eq!(new_data.2.bool, data.2.bool);
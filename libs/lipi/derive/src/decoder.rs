@@ -1,7 +1,7 @@
-use crate::utils::{data_ty, get_attr, get_attr_or_expr, get_numeric_ty};
+use crate::utils::{FieldInfo, data_ty, fields_info, get_attr_or_expr, get_numeric_ty, has_attr};
 use proc_macro2::{Span, TokenStream};
 use quote2::*;
-use syn::{punctuated::Punctuated, spanned::Spanned, *};
+use syn::{punctuated::Punctuated, *};
 
 pub fn expand(
     crate_path: &TokenStream,
@@ -17,90 +17,36 @@ pub fn expand(
         ..
     } = input;
 
-    let body = quote(|t| match data {
-        Data::Union(_) => unimplemented!(),
-        Data::Struct(DataStruct { fields, .. }) => {
-            let struct_fields: Vec<_> = fields
-                .iter()
-                .enumerate()
-                .map(|(idx, field)| {
-                    let (alias, name) = match &field.ident {
-                        Some(name) => (name.clone(), Member::Named(name.clone())),
-                        None => (
-                            Ident::new(&format!("_{}", idx), field.span()),
-                            Member::Unnamed(Index {
-                                index: idx as u32,
-                                span: field.span(),
-                            }),
-                        ),
-                    };
-                    (
-                        alias,
-                        get_attr(&field.attrs, key_attr).map(|key| {
-                            let name_str = match &field.ident {
-                                Some(name) => name.to_string(),
-                                None => idx.to_string(),
-                            };
-                            (key, name_str)
-                        }),
-                        get_attr_or_expr(&field.attrs, default_attr),
-                        name,
-                    )
-                })
-                .collect();
-
-            let field_decoder = quote(|t| {
-                for (alias, key, _, _) in &struct_fields {
-                    let Some((key, name_str)) = key else {
-                        continue;
-                    };
-                    quote!(t, {
-                        #key => #alias = __obj__.decode(__ty__, #name_str)?,
-                    });
-                }
+    let unknown_field = quote(|t| {
+        if has_attr(&input.attrs, "deny_unknown_fields") {
+            quote!(t, {
+                return Err(__crate::errors::__unknown_field(__key__, __ty__));
             });
-
-            let field_bind = quote(|t| {
-                for (alias, key, default, member) in &struct_fields {
-                    match default {
-                        Some(default) if default.is_empty() => {
-                            quote!(t, { #member: #alias.unwrap_or_else(::std::default::Default::default), });
-                        }
-                        Some(default) => {
-                            quote!(t, { #member: #alias.unwrap_or_else(|| #default), });
-                        }
-                        None => match key {
-                            Some((_, name_str)) => {
-                                quote!(t, { #member: __crate::decoder::Optional::convert(#alias, #name_str)?, });
-                            }
-                            None => {
-                                quote!(t, { #member: ::std::default::Default::default(), });
-                            }
-                        },
-                    }
-                }
+        } else {
+            quote!(t, {
+                __obj__.unknown_field(__key__, __ty__)?;
             });
+        }
+    });
 
-            for (alias, key, _, _) in &struct_fields {
-                if key.is_none() {
-                    continue;
-                }
-                quote!(t, {
-                    let mut #alias = ::std::option::Option::None;
-                });
-            }
-
+    let body = quote(|t| match data {
+        Data::Union(_) => unimplemented!(),
+        Data::Struct(_) => {
             quote!(t, {
+                let mut __b__ = <Self as __crate::decoder::DecodeFields<'decode>>::builder();
                 let mut __obj__ = __crate::decoder::FieldInfoDecoder::new(__r__);
 
                 while let Some((__key__, __ty__)) = __obj__.next_field_id_and_ty()? {
-                    match __key__ {
-                        #field_decoder
-                        _ => __obj__.skip_field(__key__, __ty__)?
+                    if !<Self as __crate::decoder::DecodeFields<'decode>>::decode_field(
+                        &mut __b__,
+                        __obj__.reader,
+                        __key__,
+                        __ty__,
+                    )? {
+                        #unknown_field
                     }
                 }
-
-                Ok(Self { #field_bind })
+                <Self as __crate::decoder::DecodeFields<'decode>>::finish(__b__)
             });
         }
         Data::Enum(DataEnum { variants, .. }) if let Some(ty) = get_numeric_ty(&input.attrs) => {
@@ -134,18 +80,44 @@ pub fn expand(
                     let name = &v.ident;
                     let (_, key) = v.discriminant.as_ref().unwrap();
                     match &v.fields {
-                        Fields::Named(_) => unimplemented!(),
                         Fields::Unit => {
                             quote!(t, {
                                 #key => { __obj__.skip_field_value(__ty__)?; Self::#name }
                             });
                         }
-                        Fields::Unnamed(_) => {
+                        Fields::Unnamed(FieldsUnnamed { unnamed, .. }) if unnamed.len() == 1 => {
                             let name_str = format!("{ident}::{name}");
                             quote!(t, {
                                 #key => Self::#name(__obj__.decode_field(__ty__, #name_str)?),
                             });
                         }
+                        Fields::Named(_) | Fields::Unnamed(_) => {
+                            // Fields of a tuple variant are keyed by their position.
+                            let key_attr = match v.fields {
+                                Fields::Named(_) => Some(key_attr),
+                                _ => None,
+                            };
+                            let fields = fields_info(&v.fields, key_attr);
+                            let builder = builder_init(&fields);
+                            let mut obj = TokenStream::new();
+                            quote!(obj, { __obj__ });
+                            let field_decoder = field_decoder(&fields, obj);
+                            let field_bind = field_bind(&fields, default_attr);
+                            quote!(t, {
+                                #key => {
+                                    __ty__.expected(__crate::DataType::Struct)?;
+                                    let mut __b__ = #builder;
+                                    let mut __obj__ = __crate::decoder::FieldInfoDecoder::new(&mut *__obj__.reader);
+
+                                    while let Some((__key__, __ty__)) = __obj__.next_field_id_and_ty()? {
+                                        if !#field_decoder {
+                                            #unknown_field
+                                        }
+                                    }
+                                    Self::#name { #field_bind }
+                                }
+                            });
+                        }
                     }
                 }
             });
@@ -171,6 +143,44 @@ pub fn expand(
     let (_, ty_generics, where_clause) = generics.split_for_impl();
     let mut params = generics.params.clone();
     let lifetime = add_decoder_trait_bounds(&mut params);
+    let reader_lifetime = &lifetime.lifetime;
+
+    let fields_impl = quote(|t| {
+        let Data::Struct(DataStruct { fields, .. }) = data else {
+            return;
+        };
+        let fields = fields_info(fields, Some(key_attr));
+        let builder_ty = builder_ty(&fields, default_attr);
+        let builder = builder_init(&fields);
+        let mut obj = TokenStream::new();
+        quote!(obj, { __crate::decoder::FieldInfoDecoder::new(__r__) });
+        let field_decoder = field_decoder(&fields, obj);
+        let field_bind = field_bind(&fields, default_attr);
+        let is_optional = is_optional(&fields);
+
+        quote!(t, {
+            impl <#lifetime, #params> __crate::decoder::DecodeFields<'decode> for #ident #ty_generics #where_clause {
+                type Builder = #builder_ty;
+
+                fn builder() -> Self::Builder {
+                    #builder
+                }
+
+                #[inline]
+                fn decode_field(__b__: &mut Self::Builder, __r__: &mut &#reader_lifetime [u8], __key__: u64, __ty__: __crate::DataType) -> __crate::Result<bool> {
+                    Ok(#field_decoder)
+                }
+
+                fn finish(__b__: Self::Builder) -> __crate::Result<Self> {
+                    Ok(Self { #field_bind })
+                }
+
+                fn is_optional(__key__: u64) -> bool {
+                    #is_optional
+                }
+            }
+        });
+    });
 
     let ty = data_ty(input);
     quote!(t, {
@@ -178,14 +188,220 @@ pub fn expand(
             use #crate_path as __crate;
             impl <#lifetime, #params> __crate::Decode<'decode> for #ident #ty_generics #where_clause {
                 const TY: __crate::DataType = #ty;
-                fn decode(__r__: &mut &#lifetime [u8]) -> __crate::Result<Self> {
+                fn decode(__r__: &mut &#reader_lifetime [u8]) -> __crate::Result<Self> {
                     #body
                 }
             }
+            #fields_impl
         };
     });
 }
 
+/// Fields are decoded into a tuple (`__b__`), skipped fields are left out.
+fn builder_fields<'a>(
+    fields: &'a [FieldInfo<'a>],
+) -> impl Iterator<Item = (Index, &'a FieldInfo<'a>)> {
+    fields
+        .iter()
+        .filter(|field| field.key.is_some() || field.attrs.flatten)
+        .enumerate()
+        .map(|(idx, field)| (Index::from(idx), field))
+}
+
+fn builder_ty<'a>(
+    fields: &'a [FieldInfo<'a>],
+    default_attr: &'a str,
+) -> QuoteFn<impl Fn(&mut TokenStream) + 'a> {
+    quote(move |t| {
+        let tys = quote(|t| {
+            for (_, FieldInfo { field, attrs, .. }) in builder_fields(fields) {
+                let ty = &field.ty;
+                if attrs.flatten {
+                    quote!(t, { <#ty as __crate::decoder::DecodeFields<'decode>>::Builder, });
+                } else if attrs.decode_with.is_some()
+                    || get_attr_or_expr(&field.attrs, default_attr).is_some()
+                {
+                    quote!(t, { ::std::option::Option<#ty>, });
+                } else {
+                    quote!(t, { ::std::option::Option<<#ty as __crate::decoder::Optional>::Value>, });
+                }
+            }
+        });
+        quote!(t, { (#tys) });
+    })
+}
+
+fn builder_init<'a>(fields: &'a [FieldInfo<'a>]) -> QuoteFn<impl Fn(&mut TokenStream) + 'a> {
+    quote(move |t| {
+        let values = quote(|t| {
+            for (_, FieldInfo { field, attrs, .. }) in builder_fields(fields) {
+                let ty = &field.ty;
+                if attrs.flatten {
+                    quote!(t, { <#ty as __crate::decoder::DecodeFields<'decode>>::builder(), });
+                } else {
+                    quote!(t, { ::std::option::Option::None, });
+                }
+            }
+        });
+        quote!(t, { (#values) });
+    })
+}
+
+/// Decodes field `__key__` into `__b__`, using `obj` decoder.
+///
+/// Evaluates to `false`, if the field is unknown.
+fn field_decoder<'a>(
+    fields: &'a [FieldInfo<'a>],
+    obj: TokenStream,
+) -> QuoteFn<impl Fn(&mut TokenStream) + 'a> {
+    quote(move |t| {
+        let arms = quote(|t| {
+            for (
+                idx,
+                FieldInfo {
+                    key, name, attrs, ..
+                },
+            ) in builder_fields(fields)
+            {
+                let Some(key) = key else {
+                    continue;
+                };
+                match &attrs.decode_with {
+                    Some(decode_with) => {
+                        quote!(t, {
+                            #key => { __b__.#idx = #obj.decode_with(__ty__, #name, #decode_with)?; true }
+                        });
+                    }
+                    None => {
+                        quote!(t, {
+                            #key => { __b__.#idx = #obj.decode(__ty__, #name)?; true }
+                        });
+                    }
+                }
+            }
+        });
+        let flatten = quote(|t| {
+            for (idx, FieldInfo { field, attrs, .. }) in builder_fields(fields) {
+                if !attrs.flatten {
+                    continue;
+                }
+                let ty = &field.ty;
+                quote!(t, {
+                    <#ty as __crate::decoder::DecodeFields<'decode>>::decode_field(&mut __b__.#idx, #obj.reader, __key__, __ty__)? ||
+                });
+            }
+        });
+        quote!(t, {
+            match __key__ {
+                #arms
+                _ => #flatten false
+            }
+        });
+    })
+}
+
+/// Evaluates to `true`, if the field `__key__` is an `Option<T>`.
+///
+/// Fields with `#[decode_with]` aren't, as the function accepts a (required) value.
+fn is_optional<'a>(fields: &'a [FieldInfo<'a>]) -> QuoteFn<impl Fn(&mut TokenStream) + 'a> {
+    quote(move |t| {
+        let arms = quote(|t| {
+            for (
+                _,
+                FieldInfo {
+                    field, key, attrs, ..
+                },
+            ) in builder_fields(fields)
+            {
+                let Some(key) = key else {
+                    continue;
+                };
+                let ty = &field.ty;
+                if attrs.decode_with.is_some() {
+                    quote!(t, { #key => false, });
+                } else {
+                    quote!(t, { #key => <#ty as __crate::decoder::Optional>::IS_OPTION, });
+                }
+            }
+        });
+        let flatten = quote(|t| {
+            for (_, FieldInfo { field, attrs, .. }) in builder_fields(fields) {
+                if attrs.flatten {
+                    let ty = &field.ty;
+                    quote!(t, {
+                        <#ty as __crate::decoder::DecodeFields<'decode>>::is_optional(__key__) ||
+                    });
+                }
+            }
+        });
+        quote!(t, {
+            match __key__ {
+                #arms
+                _ => #flatten false
+            }
+        });
+    })
+}
+
+fn field_bind<'a>(
+    fields: &'a [FieldInfo<'a>],
+    default_attr: &'a str,
+) -> QuoteFn<impl Fn(&mut TokenStream) + 'a> {
+    quote(move |t| {
+        let mut builder = builder_fields(fields).peekable();
+        for field in fields {
+            let FieldInfo {
+                field: Field { attrs, ty, .. },
+                member,
+                name,
+                ..
+            } = field;
+            let default = get_attr_or_expr(attrs, default_attr);
+
+            let idx = match builder.peek() {
+                Some((idx, f)) if std::ptr::eq(*f, field) => {
+                    let idx = idx.clone();
+                    builder.next();
+                    idx
+                }
+                // Skipped
+                _ => {
+                    match default {
+                        Some(default) if !default.is_empty() => {
+                            quote!(t, { #member: #default, });
+                        }
+                        _ => {
+                            quote!(t, { #member: ::std::default::Default::default(), });
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            if field.attrs.flatten {
+                quote!(t, {
+                    #member: <#ty as __crate::decoder::DecodeFields<'decode>>::finish(__b__.#idx)?,
+                });
+                continue;
+            }
+            match default {
+                Some(default) if default.is_empty() => {
+                    quote!(t, { #member: __b__.#idx.unwrap_or_else(::std::default::Default::default), });
+                }
+                Some(default) => {
+                    quote!(t, { #member: __b__.#idx.unwrap_or_else(|| #default), });
+                }
+                None if field.attrs.decode_with.is_some() => {
+                    quote!(t, { #member: __b__.#idx.ok_or(__crate::errors::RequiredField { name: #name })?, });
+                }
+                None => {
+                    quote!(t, { #member: __crate::decoder::Optional::convert(__b__.#idx, #name)?, });
+                }
+            }
+        }
+    })
+}
+
 fn fallback(
     default_attr: &str,
     variants: &Punctuated<Variant, token::Comma>,
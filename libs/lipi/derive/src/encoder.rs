@@ -1,7 +1,7 @@
-use crate::utils::{data_ty, get_attr, get_numeric_ty};
+use crate::utils::{FieldInfo, data_ty, fields_info, get_numeric_ty};
 use proc_macro2::{Punct, Spacing, TokenStream};
 use quote2::{Quote, ToTokens, quote};
-use syn::{spanned::Spanned, *};
+use syn::*;
 
 pub fn expand(crate_path: &TokenStream, input: &DeriveInput, t: &mut TokenStream, key_attr: &str) {
     let DeriveInput {
@@ -11,36 +11,14 @@ pub fn expand(crate_path: &TokenStream, input: &DeriveInput, t: &mut TokenStream
         ..
     } = input;
 
+    let generics = add_encode_trait_bounds(generics.clone());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
     let body = quote(|t| match data {
         Data::Union(_) => unimplemented!(),
-        Data::Struct(DataStruct { fields, .. }) => {
-            match fields {
-                Fields::Named(FieldsNamed { named, .. }) => {
-                    for Field {
-                        attrs, ty, ident, ..
-                    } in named
-                    {
-                        let Some(key) = get_attr(attrs, key_attr) else {
-                            continue;
-                        };
-                        encode_field(t, ty, ident, key);
-                    }
-                }
-                Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
-                    for (idx, f) in unnamed.iter().enumerate() {
-                        let Some(key) = get_attr(&f.attrs, key_attr) else {
-                            continue;
-                        };
-                        let idx = Index {
-                            index: idx as u32,
-                            span: f.span(),
-                        };
-                        encode_field(t, &f.ty, idx, key);
-                    }
-                }
-                Fields::Unit => {}
-            }
+        Data::Struct(_) => {
             quote!(t, {
+                __crate::encoder::EncodeFields::encode_fields(self, w)?;
                 ::std::io::Write::write_all(w, &[__crate::DataType::StructEnd.code()])
             });
         }
@@ -57,11 +35,34 @@ pub fn expand(crate_path: &TokenStream, input: &DeriveInput, t: &mut TokenStream
                     let (_, key) = v.discriminant.as_ref().unwrap();
 
                     match &v.fields {
-                        Fields::Named(_) => unimplemented!(),
-                        Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
-                            assert_eq!(unnamed.len(), 1);
+                        Fields::Unnamed(FieldsUnnamed { unnamed, .. }) if unnamed.len() == 1 => {
                             quote!(t, { Self::#name(val) => __crate::encoder::Field::encode(val, w, #key), });
                         }
+                        Fields::Named(_) | Fields::Unnamed(_) => {
+                            // Fields of a tuple variant are keyed by their position.
+                            let key_attr = match v.fields {
+                                Fields::Named(_) => Some(key_attr),
+                                _ => None,
+                            };
+                            let fields = fields_info(&v.fields, key_attr);
+                            let bindings = quote(|t| {
+                                for FieldInfo { member, alias, .. } in &fields {
+                                    quote!(t, { #member: #alias, });
+                                }
+                            });
+                            let encode_fields = quote(|t| {
+                                for field in &fields {
+                                    encode_field(t, field, field.alias.to_token_stream());
+                                }
+                            });
+                            quote!(t, {
+                                Self::#name { #bindings .. } => {
+                                    __crate::encoder::encode_field_id_and_ty(w, #key, __crate::DataType::Struct)?;
+                                    #encode_fields
+                                    ::std::io::Write::write_all(w, &[__crate::DataType::StructEnd.code()])
+                                }
+                            });
+                        }
                         Fields::Unit => {
                             quote!(t, { Self::#name => __crate::encoder::Field::encode(&false, w, #key), });
                         }
@@ -76,10 +77,133 @@ pub fn expand(crate_path: &TokenStream, input: &DeriveInput, t: &mut TokenStream
         }
     });
 
+    let len = quote(|t| match data {
+        Data::Union(_) => unimplemented!(),
+        Data::Struct(_) => {
+            quote!(t, {
+                __crate::encoder::EncodeFields::encoded_fields_len(self) + 1
+            });
+        }
+        Data::Enum(_) if let Some(ty) = get_numeric_ty(&input.attrs) => {
+            quote!(t, {
+               let tag = unsafe { *(self as *const Self).cast::<#ty>() };
+               <#ty as __crate::Encode>::encoded_len(&tag)
+            });
+        }
+        Data::Enum(DataEnum { variants, .. }) => {
+            let body = quote(|t| {
+                for v in variants {
+                    let name = &v.ident;
+                    let (_, key) = v.discriminant.as_ref().unwrap();
+
+                    match &v.fields {
+                        Fields::Unnamed(FieldsUnnamed { unnamed, .. }) if unnamed.len() == 1 => {
+                            quote!(t, { Self::#name(val) => __crate::encoder::Field::encoded_len(val, #key), });
+                        }
+                        Fields::Named(_) | Fields::Unnamed(_) => {
+                            let key_attr = match v.fields {
+                                Fields::Named(_) => Some(key_attr),
+                                _ => None,
+                            };
+                            let fields = fields_info(&v.fields, key_attr);
+                            let bindings = quote(|t| {
+                                for FieldInfo { member, alias, .. } in &fields {
+                                    quote!(t, { #member: #alias, });
+                                }
+                            });
+                            let fields_len = quote(|t| {
+                                for field in &fields {
+                                    encoded_field_len(t, field, field.alias.to_token_stream());
+                                }
+                            });
+                            quote!(t, {
+                                Self::#name { #bindings .. } => {
+                                    __crate::encoder::field_id_and_ty_len(#key) #fields_len + 1
+                                }
+                            });
+                        }
+                        Fields::Unit => {
+                            quote!(t, { Self::#name => __crate::encoder::Field::encoded_len(&false, #key), });
+                        }
+                    }
+                }
+            });
+            quote!(t, {
+                match self {
+                    #body
+                }
+            });
+        }
+    });
+
+    let fields_impl = quote(|t| {
+        let Data::Struct(DataStruct { fields, .. }) = data else {
+            return;
+        };
+        let fields = fields_info(fields, Some(key_attr));
+        let encode_fields = quote(|t| {
+            for field in &fields {
+                let member = &field.member;
+                let mut value = TokenStream::new();
+                quote!(value, { &self.#member });
+                encode_field(t, field, value);
+            }
+        });
+        let fields_len = quote(|t| {
+            for field in &fields {
+                let member = &field.member;
+                let mut value = TokenStream::new();
+                quote!(value, { &self.#member });
+                encoded_field_len(t, field, value);
+            }
+        });
+        let columns = quote(|t| {
+            for FieldInfo {
+                field, key, attrs, ..
+            } in &fields
+            {
+                let ty = &field.ty;
+                if attrs.flatten {
+                    quote!(t, { + <#ty as __crate::encoder::EncodeFields>::COLUMNS });
+                } else if key.is_some() {
+                    quote!(t, { + 1 });
+                }
+            }
+        });
+        let encode_columns = quote(|t| {
+            for field in &fields {
+                encode_column(t, field);
+            }
+        });
+        quote!(t, {
+            impl #impl_generics __crate::encoder::EncodeFields for #ident #ty_generics #where_clause {
+                const COLUMNS: usize = 0 #columns;
+
+                fn encode_fields(&self, w: &mut (impl ::std::io::Write + ?::std::marker::Sized)) -> ::std::io::Result<()> {
+                    #encode_fields
+                    Ok(())
+                }
+
+                fn encoded_fields_len(&self) -> usize {
+                    0 #fields_len
+                }
+
+                fn encode_columns<'rows>(
+                    w: &mut (impl ::std::io::Write + ?::std::marker::Sized),
+                    rows: impl ::std::iter::Iterator<Item = &'rows Self> + ::std::clone::Clone,
+                ) -> ::std::io::Result<()>
+                where
+                    Self: 'rows,
+                {
+                    #encode_columns
+                    Ok(())
+                }
+            }
+        });
+    });
+
     let ty = data_ty(input);
 
-    let generics = add_encode_trait_bounds(generics.clone());
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     quote!(t, {
         const _: () = {
             use #crate_path as __crate;
@@ -88,18 +212,97 @@ pub fn expand(crate_path: &TokenStream, input: &DeriveInput, t: &mut TokenStream
                 fn encode(&self, w: &mut (impl ::std::io::Write + ?::std::marker::Sized)) -> ::std::io::Result<()> {
                     #body
                 }
+
+                fn encoded_len(&self) -> usize {
+                    #len
+                }
             }
+            #fields_impl
         };
     });
 }
 
-fn encode_field(t: &mut TokenStream, ty: &Type, ident: impl ToTokens, key: &Expr) {
-    let ref_symbol = match ty {
-        Type::Reference(_) => None,
-        _ => Some(Punct::new('&', Spacing::Alone)),
+/// `value` is a reference to the field.
+fn encode_field(t: &mut TokenStream, field: &FieldInfo, value: TokenStream) {
+    let FieldInfo { key, attrs, .. } = field;
+    if attrs.flatten {
+        quote!(t, {
+            __crate::encoder::EncodeFields::encode_fields(#value, w)?;
+        });
+        return;
+    }
+    let Some(key) = key else {
+        return;
+    };
+    if let Some(encode_with) = &attrs.encode_with {
+        quote!(t, {
+            __crate::encoder::OptionalField::encode(&#encode_with(#value), w, #key)?;
+        });
+        return;
+    }
+    let deref = match field.field.ty {
+        Type::Reference(_) => Some(Punct::new('*', Spacing::Alone)),
+        _ => None,
+    };
+    quote!(t, {
+        __crate::encoder::OptionalField::encode(#deref #value, w, #key)?;
+    });
+}
+
+/// Adds the length of [encode_field] to the sum, `value` is a reference to the field.
+fn encoded_field_len(t: &mut TokenStream, field: &FieldInfo, value: TokenStream) {
+    let FieldInfo { key, attrs, .. } = field;
+    if attrs.flatten {
+        quote!(t, {
+            + __crate::encoder::EncodeFields::encoded_fields_len(#value)
+        });
+        return;
+    }
+    let Some(key) = key else {
+        return;
     };
+    if let Some(encode_with) = &attrs.encode_with {
+        quote!(t, {
+            + __crate::encoder::OptionalField::encoded_len(&#encode_with(#value), #key)
+        });
+        return;
+    }
+    let deref = match field.field.ty {
+        Type::Reference(_) => Some(Punct::new('*', Spacing::Alone)),
+        _ => None,
+    };
+    quote!(t, {
+        + __crate::encoder::OptionalField::encoded_len(#deref #value, #key)
+    });
+}
+
+/// Encodes the field of every row (`rows`) as a column.
+fn encode_column(t: &mut TokenStream, field: &FieldInfo) {
+    let FieldInfo {
+        field: Field { ty, .. },
+        member,
+        key,
+        attrs,
+        ..
+    } = field;
+    if attrs.flatten {
+        quote!(t, {
+            <#ty as __crate::encoder::EncodeFields>::encode_columns(w, rows.clone().map(|row| &row.#member))?;
+        });
+        return;
+    }
+    let Some(key) = key else {
+        return;
+    };
+    if let Some(encode_with) = &attrs.encode_with {
+        quote!(t, {
+            let values: ::std::vec::Vec<_> = rows.clone().map(|row| #encode_with(&row.#member)).collect();
+            __crate::encoder::encode_column(w, #key, values.iter())?;
+        });
+        return;
+    }
     quote!(t, {
-        __crate::encoder::OptionalField::encode(#ref_symbol self.#ident, w, #key)?;
+        __crate::encoder::encode_column(w, #key, rows.clone().map(|row| &row.#member))?;
     });
 }
 
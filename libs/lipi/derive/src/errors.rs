@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::utils::{self, add_compile_error, get_repr, is_numeric};
+use crate::utils::{self, FieldAttrs, add_compile_error, get_repr, is_numeric};
 use proc_macro2::TokenStream;
 use quote2::ToTokens;
 use std::format as fmt;
@@ -14,37 +14,7 @@ pub fn verify(input: &DeriveInput, key_attr: &str) -> VerifyResult {
 
     match data {
         Data::Union(_) => unimplemented!(),
-        Data::Struct(DataStruct { fields, .. }) => {
-            let mut seen = HashSet::new();
-
-            for field in fields {
-                let Some(key) = utils::get_attr(&field.attrs, key_attr) else {
-                    continue;
-                };
-
-                let Some(key_0) = seen.get(key) else {
-                    seen.insert(key);
-                    continue;
-                };
-
-                let loc = key.span().start();
-
-                add_compile_error(
-                    &mut err,
-                    key_0.span(),
-                    &fmt!("duplicate key at line {}", loc.line),
-                );
-
-                add_compile_error(
-                    &mut err,
-                    key.span(),
-                    &fmt!(
-                        "duplicate key `{}` later defined here",
-                        key_0.to_token_stream()
-                    ),
-                );
-            }
-        }
+        Data::Struct(DataStruct { fields, .. }) => verify_fields(&mut err, fields, key_attr),
         Data::Enum(DataEnum { variants, .. }) if is_numeric(&input.attrs) => {
             if get_repr(&input.attrs).is_none() {
                 add_compile_error(
@@ -88,18 +58,6 @@ pub fn verify(input: &DeriveInput, key_attr: &str) -> VerifyResult {
                 }
 
                 match &v.fields {
-                    Fields::Named(fields) => {
-                        let first_ty = match fields.named.first() {
-                            Some(field) => field.ty.to_token_stream().to_string(),
-                            None => "T".into(),
-                        };
-
-                        add_compile_error(
-                            &mut err,
-                            fields.span(),
-                            &fmt!("unsupported {{ .. }}; use `{ident}({first_ty})` instead"),
-                        );
-                    }
                     Fields::Unnamed(FieldsUnnamed {
                         unnamed,
                         paren_token,
@@ -109,25 +67,19 @@ pub fn verify(input: &DeriveInput, key_attr: &str) -> VerifyResult {
                         add_compile_error(&mut err, span, "remove `()`")
                     }
 
-                    Fields::Unnamed(FieldsUnnamed { unnamed, .. }) if unnamed.len() != 1 => {
-                        let count = unnamed.len();
-                        let span = if count == 2 {
-                            unnamed.last().span()
-                        } else {
-                            let mut spans = unnamed.iter();
-                            spans.next(); // skip
-                            let start = spans.next().span();
-                            let end = spans.next_back().span();
-                            start.join(end).unwrap_or_else(|| unnamed.last().span())
-                        };
-
-                        add_compile_error(
-                            &mut err,
-                            span,
-                            &fmt!("remove extra fields; only one field is allowed (found {count})"),
-                        )
+                    Fields::Named(_) => verify_fields(&mut err, &v.fields, key_attr),
+                    Fields::Unnamed(_) => {
+                        for field in &v.fields {
+                            if utils::has_attr(&field.attrs, "flatten") {
+                                add_compile_error(
+                                    &mut err,
+                                    field.span(),
+                                    "`#[flatten]` is not supported in tuple variant",
+                                );
+                            }
+                        }
                     }
-                    _ => {}
+                    Fields::Unit => {}
                 }
             }
         }
@@ -135,3 +87,72 @@ pub fn verify(input: &DeriveInput, key_attr: &str) -> VerifyResult {
 
     if err.is_empty() { Ok(()) } else { Err(err) }
 }
+
+fn verify_fields(err: &mut TokenStream, fields: &Fields, key_attr: &str) {
+    let mut seen = HashSet::new();
+
+    for field in fields {
+        let attrs = FieldAttrs::new(&field.attrs);
+        let key = utils::get_attr(&field.attrs, key_attr);
+
+        if let Some(rename) = attrs.rename
+            && !matches!(
+                rename,
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(_),
+                    ..
+                })
+            )
+        {
+            add_compile_error(err, rename.span(), "expected string literal");
+        }
+        if attrs.flatten && (attrs.encode_with.is_some() || attrs.decode_with.is_some()) {
+            add_compile_error(
+                err,
+                field.span(),
+                "`#[flatten]` can't be used with `#[with]`",
+            );
+        }
+
+        let Some(key) = key else {
+            if !attrs.skip && !attrs.flatten {
+                add_compile_error(
+                    err,
+                    field.span(),
+                    &fmt!("missing `#[{key_attr} = N]`, use `#[skip]` to ignore this field"),
+                );
+            }
+            continue;
+        };
+        if attrs.skip || attrs.flatten {
+            add_compile_error(
+                err,
+                key.span(),
+                "`#[skip]` or `#[flatten]` field can't have a key",
+            );
+            continue;
+        }
+
+        let Some(key_0) = seen.get(key) else {
+            seen.insert(key);
+            continue;
+        };
+
+        let loc = key.span().start();
+
+        add_compile_error(
+            err,
+            key_0.span(),
+            &fmt!("duplicate key at line {}", loc.line),
+        );
+
+        add_compile_error(
+            err,
+            key.span(),
+            &fmt!(
+                "duplicate key `{}` later defined here",
+                key_0.to_token_stream()
+            ),
+        );
+    }
+}
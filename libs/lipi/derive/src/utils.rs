@@ -1,6 +1,7 @@
-use proc_macro2::{Literal, TokenStream};
+use proc_macro2::{Literal, Span, TokenStream};
 use quote2::*;
-use syn::*;
+use std::borrow::Cow;
+use syn::{spanned::Spanned, *};
 
 pub fn get_attr<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Expr> {
     attrs.iter().find_map(|attr| match &attr.meta {
@@ -17,10 +18,124 @@ pub fn get_attr_or_expr(attrs: &[Attribute], name: &str) -> Option<TokenStream>
     })
 }
 
-pub fn is_numeric(attrs: &[Attribute]) -> bool {
+/// Field of a struct, or a variant.
+pub struct FieldInfo<'a> {
+    pub field: &'a Field,
+    pub member: Member,
+    /// Name of the local binding.
+    pub alias: Ident,
+    /// `None`, if the field is skipped or flattened.
+    pub key: Option<Cow<'a, Expr>>,
+    pub name: String,
+    pub attrs: FieldAttrs<'a>,
+}
+
+/// Fields are keyed by `key_attr` (fields without it are skipped), or by their position if `None`.
+pub fn fields_info<'a>(fields: &'a Fields, key_attr: Option<&str>) -> Vec<FieldInfo<'a>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let attrs = FieldAttrs::new(&field.attrs);
+            let key = match key_attr {
+                _ if attrs.skip || attrs.flatten => None,
+                Some(key_attr) => get_attr(&field.attrs, key_attr).map(Cow::Borrowed),
+                None => Some(Cow::Owned(Expr::Lit(ExprLit {
+                    attrs: Vec::new(),
+                    lit: Lit::Int(LitInt::new(&idx.to_string(), field.span())),
+                }))),
+            };
+            let member = match &field.ident {
+                Some(name) => Member::Named(name.clone()),
+                None => Member::Unnamed(Index {
+                    index: idx as u32,
+                    span: field.span(),
+                }),
+            };
+            FieldInfo {
+                field,
+                member,
+                alias: Ident::new(&format!("__{idx}"), Span::call_site()),
+                key,
+                name: attrs.name(field, idx),
+                attrs,
+            }
+        })
+        .collect()
+}
+
+/// Field attributes, other than `#[key]` and `#[default]`.
+pub struct FieldAttrs<'a> {
+    pub skip: bool,
+    pub flatten: bool,
+    pub rename: Option<&'a Expr>,
+    /// `#[encode_with = "path"]` (`fn(&T) -> U`), or `path::encode` of `#[with = "path"]`.
+    pub encode_with: Option<TokenStream>,
+    /// `#[decode_with = "path"]` (`fn(U) -> Result<T, E>`), or `path::decode` of `#[with = "path"]`.
+    ///
+    /// The field is required, unless it has `#[default]`.
+    pub decode_with: Option<TokenStream>,
+}
+
+impl<'a> FieldAttrs<'a> {
+    pub fn new(attrs: &'a [Attribute]) -> Self {
+        let with = |name| {
+            get_path_attr(attrs, "with").map(|path| {
+                let mut t = TokenStream::new();
+                let name = Ident::new(name, Span::call_site());
+                quote!(t, { #path::#name });
+                t
+            })
+        };
+        Self {
+            skip: has_attr(attrs, "skip"),
+            flatten: has_attr(attrs, "flatten"),
+            rename: get_attr(attrs, "rename"),
+            encode_with: get_path_attr(attrs, "encode_with").or_else(|| with("encode")),
+            decode_with: get_path_attr(attrs, "decode_with").or_else(|| with("decode")),
+        }
+    }
+
+    /// Name of the field, as seen by the clients.
+    pub fn name(&self, field: &Field, idx: usize) -> String {
+        if let Some(Expr::Lit(ExprLit {
+            lit: Lit::Str(name),
+            ..
+        })) = self.rename
+        {
+            return name.value();
+        }
+        match &field.ident {
+            Some(name) => name.to_string(),
+            None => idx.to_string(),
+        }
+    }
+}
+
+/// Path of `#[name = "path"]` attribute.
+pub fn get_path_attr(attrs: &[Attribute], name: &str) -> Option<TokenStream> {
+    let value = get_attr(attrs, name)?;
+    let path = match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(path),
+            ..
+        }) => path.parse::<Path>(),
+        _ => Err(Error::new(value.span(), "expected path as string literal")),
+    };
+    Some(match path {
+        Ok(path) => path.to_token_stream(),
+        Err(err) => err.to_compile_error(),
+    })
+}
+
+pub fn has_attr(attrs: &[Attribute], name: &str) -> bool {
     attrs
         .iter()
-        .any(|attr| matches!(&attr.meta, Meta::Path(path) if path.is_ident("numeric")))
+        .any(|attr| matches!(&attr.meta, Meta::Path(path) if path.is_ident(name)))
+}
+
+pub fn is_numeric(attrs: &[Attribute]) -> bool {
+    has_attr(attrs, "numeric")
 }
 
 pub fn get_repr(attrs: &[Attribute]) -> Option<&TokenStream> {
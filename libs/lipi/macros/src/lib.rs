@@ -4,7 +4,10 @@ use lipi_derive::{quote2, syn};
 use proc_macro::TokenStream;
 use quote2::*;
 
-#[proc_macro_derive(Encode, attributes(numeric, key))]
+#[proc_macro_derive(
+    Encode,
+    attributes(numeric, key, skip, rename, with, encode_with, decode_with, flatten)
+)]
 pub fn encoder(input: TokenStream) -> TokenStream {
     let Ok(input) = syn::parse(input) else {
         return TokenStream::new();
@@ -20,7 +23,21 @@ pub fn encoder(input: TokenStream) -> TokenStream {
     t.into()
 }
 
-#[proc_macro_derive(Decode, attributes(numeric, key, default))]
+#[proc_macro_derive(
+    Decode,
+    attributes(
+        numeric,
+        deny_unknown_fields,
+        key,
+        default,
+        skip,
+        rename,
+        with,
+        encode_with,
+        decode_with,
+        flatten
+    )
+)]
 pub fn decoder(input: TokenStream) -> TokenStream {
     let Ok(input) = syn::parse(input) else {
         return TokenStream::new();
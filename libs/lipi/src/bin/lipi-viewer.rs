@@ -1,6 +1,10 @@
+use lipi::{
+    BitSet, DataType, Decode,
+    decoder::{decode_bytes, decode_field_id_and_ty, decode_len, decode_list_len_and_ty},
+};
 use std::env;
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
 use std::path::Path;
 use std::process;
 
@@ -12,6 +16,9 @@ DESCRIPTION:
     Parses raw binary data in lipi format and prints
     a structured representation.
 
+    Every line shows byte offset, field id, type, value
+    and hex span of the entry.
+
 USAGE:
     lipi-viewer [FILE]
     cat FILE | lipi-viewer
@@ -45,20 +52,288 @@ fn main() -> Result {
     Ok(())
 }
 
-fn parse_and_print_entries(_data: Vec<u8>) {
-    // let mut buf = &data[..];
-    // match lipi::Entries::parse(&mut buf) {
-    //     Ok(entries) => println!("{entries}"),
-    //     Err(error) => {
-    //         let remaining = buf.len();
-    //         let offset = data.len() - remaining;
-
-    //         eprintln!(
-    //             "\nfailed to parse; offset: {offset} (0x{offset:X}); remaining bytes: {remaining}"
-    //         );
-    //         eprintln!("{error:#?}");
-    //         process::exit(1);
-    //     }
-    // }
-    todo!()
+fn parse_and_print_entries(data: Vec<u8>) {
+    let mut viewer = Viewer::new(&data, io::stdout().lock());
+    if let Err(error) = viewer.entries() {
+        let remaining = viewer.reader.len();
+        let offset = data.len() - remaining;
+
+        eprintln!(
+            "\nfailed to parse; offset: {offset} (0x{offset:X}); remaining bytes: {remaining}"
+        );
+        eprintln!("{error:#?}");
+        process::exit(1);
+    }
+}
+
+/// Maximum bytes of a hex span, longer spans are truncated.
+const HEX_SPAN: usize = 16;
+/// Column of hex spans.
+const HEX_COLUMN: usize = 56;
+
+/// Walks a lipi buffer without schema, and prints an indented tree of entries.
+struct Viewer<'de, W> {
+    data: &'de [u8],
+    reader: &'de [u8],
+    depth: usize,
+    out: W,
+}
+
+impl<'de, W: Write> Viewer<'de, W> {
+    fn new(data: &'de [u8], out: W) -> Self {
+        Self {
+            data,
+            reader: data,
+            depth: 0,
+            out,
+        }
+    }
+
+    fn offset(&self) -> usize {
+        self.data.len() - self.reader.len()
+    }
+
+    /// Prints an entry, that starts at `start` and ends at current offset.
+    fn line(&mut self, start: usize, entry: impl std::fmt::Display) -> Result {
+        let text = format!(
+            "{start:06X}  {:indent$}{entry}",
+            "",
+            indent = self.depth * 2
+        );
+        let span = &self.data[start..self.offset()];
+
+        let mut hex = String::new();
+        for byte in span.iter().take(HEX_SPAN) {
+            hex.push_str(&format!("{byte:02x} "));
+        }
+        if span.len() > HEX_SPAN {
+            hex.push_str("..");
+        }
+        writeln!(self.out, "{text:<HEX_COLUMN$} | {}", hex.trim_end())?;
+        Ok(())
+    }
+
+    fn nested(&mut self, f: impl FnOnce(&mut Self) -> Result) -> Result {
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Top level entries are struct fields, every struct end is followed by the next message, if any.
+    fn entries(&mut self) -> Result {
+        while !self.reader.is_empty() {
+            self.fields()?;
+        }
+        Ok(())
+    }
+
+    fn fields(&mut self) -> Result {
+        while !self.reader.is_empty() || self.depth > 0 {
+            let start = self.offset();
+            let (id, ty) = decode_field_id_and_ty(&mut self.reader)?;
+            if ty == DataType::StructEnd {
+                return self.line(start, "End");
+            }
+            self.value(start, format_args!("{id}: "), ty)?;
+        }
+        Ok(())
+    }
+
+    fn value(&mut self, start: usize, label: std::fmt::Arguments, ty: DataType) -> Result {
+        let reader = &mut self.reader;
+        match ty {
+            DataType::False => self.line(start, format_args!("{label}Bool = false")),
+            DataType::True => self.line(start, format_args!("{label}Bool = true")),
+            DataType::U8 => {
+                let val = u8::decode(reader)?;
+                self.line(start, format_args!("{label}U8 = {val}"))
+            }
+            DataType::I8 => {
+                let val = i8::decode(reader)?;
+                self.line(start, format_args!("{label}I8 = {val}"))
+            }
+            DataType::F32 => {
+                let val = f32::decode(reader)?;
+                self.line(start, format_args!("{label}F32 = {val}"))
+            }
+            DataType::F64 => {
+                let val = f64::decode(reader)?;
+                self.line(start, format_args!("{label}F64 = {val}"))
+            }
+            DataType::UInt => {
+                let val = u128::decode(reader)?;
+                self.line(start, format_args!("{label}UInt = {val}"))
+            }
+            DataType::Int => {
+                let val = i128::decode(reader)?;
+                self.line(start, format_args!("{label}Int = {val}"))
+            }
+            DataType::Str => {
+                let bytes = decode_bytes(reader)?;
+                match str::from_utf8(bytes) {
+                    Ok(val) => self.line(start, format_args!("{label}Str = {val:?}")),
+                    Err(_) => self.line(
+                        start,
+                        format_args!("{label}Str <invalid utf-8, {} bytes>", bytes.len()),
+                    ),
+                }
+            }
+            DataType::UnknownI | DataType::UnknownII => {
+                let len = decode_bytes(reader)?.len();
+                self.line(start, format_args!("{label}{ty:?} <{len} bytes>"))
+            }
+            DataType::StructEnd => Err("unexpected struct end".into()),
+            DataType::Struct => {
+                self.line(start, format_args!("{label}Struct"))?;
+                self.nested(Self::fields)
+            }
+            DataType::Union => {
+                self.line(start, format_args!("{label}Union"))?;
+                self.nested(|this| {
+                    let start = this.offset();
+                    let (id, ty) = decode_field_id_and_ty(&mut this.reader)?;
+                    this.value(start, format_args!("{id}: "), ty)
+                })
+            }
+            DataType::List => {
+                let (len, ty) = decode_list_len_and_ty(reader)?;
+                self.list(start, label, len, ty)
+            }
+            DataType::Table => {
+                let cols = decode_len(reader)?;
+                let rows = decode_len(reader)?;
+                self.line(start, format_args!("{label}Table [{cols} x {rows}]"))?;
+                self.nested(|this| {
+                    for _ in 0..cols {
+                        let start = this.offset();
+                        let (id, ty) = decode_field_id_and_ty(&mut this.reader)?;
+                        this.list(start, format_args!("{id}: "), rows, ty)?;
+                    }
+                    Ok(())
+                })
+            }
+        }
+    }
+
+    fn list(
+        &mut self,
+        start: usize,
+        label: std::fmt::Arguments,
+        len: usize,
+        ty: DataType,
+    ) -> Result {
+        let name = match ty {
+            DataType::True => "Bool",
+            _ => &format!("{ty:?}"),
+        };
+        match ty {
+            DataType::False => Err("unexpected bool packed in list".into()),
+            // Packed bools
+            DataType::True => {
+                let bits = read_bytes(&mut self.reader, len.div_ceil(8))?;
+                let bools: Vec<_> = (0..len).map(|i| bits.has(i)).collect();
+                self.line(
+                    start,
+                    format_args!("{label}List<{name}> [{len}] = {bools:?}"),
+                )
+            }
+            DataType::U8 => {
+                let bytes = read_bytes(&mut self.reader, len)?;
+                self.line(
+                    start,
+                    format_args!("{label}List<{name}> [{len}] = {bytes:?}"),
+                )
+            }
+            DataType::I8 => {
+                let bytes = read_bytes(&mut self.reader, len)?;
+                let vals: Vec<_> = bytes.iter().map(|&b| b as i8).collect();
+                self.line(
+                    start,
+                    format_args!("{label}List<{name}> [{len}] = {vals:?}"),
+                )
+            }
+            DataType::StructEnd => Err("unexpected list ty struct end".into()),
+            _ => {
+                self.line(start, format_args!("{label}List<{name}> [{len}]"))?;
+                self.nested(|this| {
+                    for i in 0..len {
+                        let start = this.offset();
+                        this.value(start, format_args!("[{i}] "), ty)?;
+                    }
+                    Ok(())
+                })
+            }
+        }
+    }
+}
+
+fn read_bytes<'de>(reader: &mut &'de [u8], len: usize) -> Result<&'de [u8]> {
+    if len > reader.len() {
+        return Err(format!("unexpected end of input, needed: {len} bytes").into());
+    }
+    let (bytes, rest) = reader.split_at(len);
+    *reader = rest;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lipi::Encode;
+
+    fn view(data: &[u8]) -> (String, Result) {
+        let mut out = Vec::new();
+        let result = Viewer::new(data, &mut out).entries();
+        (String::from_utf8(out).unwrap(), result)
+    }
+
+    #[derive(Encode)]
+    struct User {
+        #[key = 0]
+        name: String,
+        #[key = 1]
+        age: u8,
+        #[key = 2]
+        flags: Vec<bool>,
+    }
+
+    #[test]
+    fn test_view_struct() {
+        let user = User {
+            name: "Nur".into(),
+            age: 42,
+            flags: vec![true, false, true],
+        };
+        let (out, result) = view(&user.to_bytes().unwrap());
+        result.unwrap();
+
+        let lines: Vec<_> = out
+            .lines()
+            .map(|line| line.split(" | ").collect::<Vec<_>>())
+            .collect();
+        assert_eq!(lines[0][0].trim_end(), r#"000000  0: Str = "Nur""#);
+        assert_eq!(lines[0][1], "08 03 4e 75 72");
+        assert_eq!(lines[1][0].trim_end(), "000005  1: U8 = 42");
+        assert_eq!(
+            lines[2][0].trim_end(),
+            "000007  2: List<Bool> [3] = [true, false, true]"
+        );
+        assert_eq!(lines[3][0].trim_end(), "00000A  End");
+    }
+
+    #[test]
+    fn test_view_error() {
+        let data = User {
+            name: "Hello, World".into(),
+            age: 0,
+            flags: vec![],
+        }
+        .to_bytes()
+        .unwrap();
+
+        let (out, result) = view(&data[..6]);
+        assert!(result.is_err());
+        assert!(out.is_empty());
+    }
 }
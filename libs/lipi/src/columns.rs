@@ -0,0 +1,155 @@
+use crate::{
+    DataType, Decode, Encode, Result,
+    bit_set::BitSet,
+    decoder::{
+        DecodeFields, FieldInfoDecoder, decode_field_id_and_ty, decode_len, limits, options,
+    },
+    encoder::{EncodeFields, encode_len},
+    errors::UnknownField,
+    utils,
+};
+use std::{
+    io::{self, Write},
+    ops::{Deref, DerefMut},
+};
+
+/// List of structs, encoded as a [DataType::Table] with a column per field.
+///
+/// Field headers are written once per column, instead of once per item.
+/// Optional fields are wrapped in a struct (`{0: value}` or `{}`), as every row has a value,
+/// and bools are packed.
+///
+/// ```
+/// use lipi::{Columns, Decode, Encode};
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct Row {
+///     #[key = 0]
+///     id: u32,
+///     #[key = 1]
+///     label: Option<String>,
+/// }
+///
+/// let rows = Columns(vec![Row { id: 1, label: None }, Row { id: 2, label: Some("two".into()) }]);
+/// let bytes = rows.to_bytes().unwrap();
+/// assert_eq!(Columns::<Row>::decode(&mut &*bytes).unwrap(), rows);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Columns<T>(pub Vec<T>);
+
+impl<T> Deref for Columns<T> {
+    type Target = Vec<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Columns<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<Vec<T>> for Columns<T> {
+    fn from(rows: Vec<T>) -> Self {
+        Self(rows)
+    }
+}
+
+impl<T> From<Columns<T>> for Vec<T> {
+    fn from(Columns(rows): Columns<T>) -> Self {
+        rows
+    }
+}
+
+impl<T: EncodeFields> Encode for Columns<T> {
+    const TY: DataType = DataType::Table;
+
+    fn encode(&self, writer: &mut (impl Write + ?Sized)) -> io::Result<()> {
+        encode_len(writer, T::COLUMNS)?;
+        encode_len(writer, self.0.len())?;
+        T::encode_columns(writer, self.0.iter())
+    }
+}
+
+impl<'de, T: DecodeFields<'de>> Decode<'de> for Columns<T> {
+    const TY: DataType = DataType::Table;
+
+    fn decode(reader: &mut &'de [u8]) -> Result<Self> {
+        let _depth = limits::Depth::enter()?;
+        let cols = decode_len(reader)?;
+        let rows = decode_len(reader)?;
+        limits::check_len(rows)?;
+        limits::alloc::<T>(rows)?;
+
+        let mut builders = utils::try_collect(rows, || Ok::<_, crate::Error>(T::builder()))?;
+        for _ in 0..cols {
+            let (id, ty) = decode_field_id_and_ty(reader)?;
+            decode_column::<T>(&mut builders, reader, id, ty)?;
+        }
+        builders
+            .into_iter()
+            .map(T::finish)
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
+/// Decodes a column into the field `id` of every row.
+///
+/// Unknown columns are skipped, as unknown fields.
+fn decode_column<'de, T: DecodeFields<'de>>(
+    builders: &mut [T::Builder],
+    reader: &mut &'de [u8],
+    id: u64,
+    ty: DataType,
+) -> Result<()> {
+    // Type of an empty column isn't checked, same as an empty list.
+    if builders.is_empty() {
+        return Ok(());
+    }
+    if T::is_optional(id) {
+        ty.expected(DataType::Struct)?;
+        for builder in builders {
+            let mut obj = FieldInfoDecoder::new(reader);
+            while let Some((key, ty)) = obj.next_field_id_and_ty()? {
+                match key {
+                    0 => {
+                        T::decode_field(builder, obj.reader, id, ty)?;
+                    }
+                    _ => obj.skip_field(key, ty)?,
+                }
+            }
+        }
+        return Ok(());
+    }
+    if ty == DataType::True {
+        let bitvec = utils::read_bytes(reader, utils::bool_packed_len(builders.len()))?;
+        for (idx, builder) in builders.iter_mut().enumerate() {
+            let val = DataType::from(bitvec.has(idx));
+            if !T::decode_field(builder, &mut &[][..], id, val)? {
+                return options::unknown_field(UnknownField { id, ty });
+            }
+        }
+        return Ok(());
+    }
+    let len = builders.len();
+    for builder in builders {
+        // Unknown column is detected by the first row, before its value is consumed.
+        if !T::decode_field(builder, reader, id, ty)? {
+            options::unknown_field(UnknownField { id, ty })?;
+            return FieldInfoDecoder::new(reader).skip_list_values(len, ty);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "type-id")]
+impl<T: type_id::TypeId> type_id::TypeId for Columns<T> {
+    fn ty(r: &mut type_id::TypeRegistry) -> type_id::Type {
+        type_id::Type::List {
+            variant: type_id::ListVariant::Columns,
+            ty: Box::new(T::ty(r)),
+        }
+    }
+}
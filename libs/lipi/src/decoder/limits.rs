@@ -0,0 +1,122 @@
+use crate::{
+    Result,
+    errors::{Limit, LimitExceeded},
+};
+use std::cell::Cell;
+
+/// Resource limits of untrusted input, see [DecodeOptions::limits](super::DecodeOptions::limits).
+#[derive(Debug, Clone, Copy)]
+pub struct DecodeLimits {
+    /// Maximum nesting of structs, unions, lists and tables. (Default: 128)
+    pub max_depth: usize,
+    /// Maximum number of items in a list, or rows in a table. (Default: 1M)
+    pub max_len: usize,
+    /// Maximum length of a string or bytes. (Default: 16 MiB)
+    pub max_bytes: usize,
+    /// Maximum total size of allocated collections and strings, in bytes. (Default: 256 MiB)
+    pub max_alloc: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 128,
+            max_len: 1024 * 1024,
+            max_bytes: 16 * 1024 * 1024,
+            max_alloc: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// Usage of the limits, so far.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Budget {
+    limits: DecodeLimits,
+    depth: usize,
+    alloc: usize,
+}
+
+impl Budget {
+    pub(crate) fn new(limits: DecodeLimits) -> Self {
+        Self {
+            limits,
+            depth: 0,
+            alloc: 0,
+        }
+    }
+}
+
+thread_local! {
+    static BUDGET: Cell<Option<Budget>> = const { Cell::new(None) };
+}
+
+pub(crate) fn replace(budget: Option<Budget>) -> Option<Budget> {
+    BUDGET.replace(budget)
+}
+
+fn exceeded(limit: Limit, max: usize) -> crate::Error {
+    Box::new(LimitExceeded { limit, max })
+}
+
+/// Checks the number of items in a list, or rows in a table.
+pub(crate) fn check_len(len: usize) -> Result<()> {
+    match BUDGET.get() {
+        Some(Budget { limits, .. }) if len > limits.max_len => {
+            Err(exceeded(Limit::Len, limits.max_len))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks the length of a string or bytes.
+pub(crate) fn check_bytes(len: usize) -> Result<()> {
+    match BUDGET.get() {
+        Some(Budget { limits, .. }) if len > limits.max_bytes => {
+            Err(exceeded(Limit::Bytes, limits.max_bytes))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Accounts `len` items of `T`, before they are allocated.
+pub(crate) fn alloc<T>(len: usize) -> Result<()> {
+    let Some(mut budget) = BUDGET.get() else {
+        return Ok(());
+    };
+    let size = len.saturating_mul(size_of::<T>());
+    budget.alloc = budget.alloc.saturating_add(size);
+    if budget.alloc > budget.limits.max_alloc {
+        return Err(exceeded(Limit::Alloc, budget.limits.max_alloc));
+    }
+    BUDGET.set(Some(budget));
+    Ok(())
+}
+
+/// Nesting of a struct, union, list or table, left on drop.
+pub(crate) struct Depth(bool);
+
+impl Depth {
+    #[inline]
+    pub(crate) fn enter() -> Result<Self> {
+        let Some(mut budget) = BUDGET.get() else {
+            return Ok(Self(false));
+        };
+        if budget.depth >= budget.limits.max_depth {
+            return Err(exceeded(Limit::Depth, budget.limits.max_depth));
+        }
+        budget.depth += 1;
+        BUDGET.set(Some(budget));
+        Ok(Self(true))
+    }
+}
+
+impl Drop for Depth {
+    fn drop(&mut self) {
+        if self.0
+            && let Some(mut budget) = BUDGET.get()
+        {
+            budget.depth = budget.depth.saturating_sub(1);
+            BUDGET.set(Some(budget));
+        }
+    }
+}
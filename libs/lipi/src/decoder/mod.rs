@@ -1,13 +1,23 @@
+pub(crate) mod limits;
+pub(crate) mod options;
+mod read;
 mod skip;
+#[cfg(feature = "stream")]
+mod stream;
 
 use crate::assert_or_err;
 use crate::{DataType, Result, bit_set, errors, utils, varint, zig_zag};
 use bit_set::bitvec_to_bools;
+pub use limits::DecodeLimits;
+pub use options::{DecodeOptions, Decoded};
+pub use read::{Items, ReadDecoder};
 use std::{
     collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque},
     hash::Hash,
     mem,
 };
+#[cfg(feature = "stream")]
+pub use stream::{StreamDecoder, StreamItems};
 
 pub fn decode_field_id_and_ty(reader: &mut &[u8]) -> Result<(u64, DataType)> {
     let byte = utils::read_byte(reader)?;
@@ -28,6 +38,7 @@ pub fn decode_len(reader: &mut &[u8]) -> Result<usize> {
 
 pub fn decode_bytes<'de>(reader: &mut &'de [u8]) -> Result<&'de [u8]> {
     let len = decode_len(reader)?;
+    limits::check_bytes(len)?;
     Ok(utils::read_bytes(reader, len)?)
 }
 
@@ -40,6 +51,19 @@ pub fn decode_list_len_and_ty(reader: &mut &[u8]) -> Result<(usize, DataType)> {
     Ok((usize::try_from(len)?, ty))
 }
 
+/// Item type of an empty list (or table column) isn't checked,
+/// as it might be unknown to the encoder. (e.g. serde)
+fn expected_item_ty(
+    len: usize,
+    ty: DataType,
+    expected: DataType,
+) -> Result<(), errors::InvalidType> {
+    if len == 0 {
+        return Ok(());
+    }
+    ty.expected(expected)
+}
+
 // -----------------------------------------------------------------------------
 
 pub trait Decode<'de>: Sized {
@@ -47,8 +71,11 @@ pub trait Decode<'de>: Sized {
     fn decode(reader: &mut &'de [u8]) -> Result<Self>;
 
     fn decode_vec(reader: &mut &'de [u8]) -> Result<Vec<Self>> {
+        let _depth = limits::Depth::enter()?;
         let (len, ty) = decode_list_len_and_ty(reader)?;
-        ty.expected(Self::TY)?;
+        expected_item_ty(len, ty, Self::TY)?;
+        limits::check_len(len)?;
+        limits::alloc::<Self>(len)?;
 
         utils::try_collect(len, || Self::decode(reader))
     }
@@ -58,8 +85,11 @@ pub trait Decode<'de>: Sized {
         new: fn(len: usize) -> List,
         add: fn(list: &mut List, val: Self),
     ) -> Result<List> {
+        let _depth = limits::Depth::enter()?;
         let (len, ty) = decode_list_len_and_ty(reader)?;
-        ty.expected(Self::TY)?;
+        expected_item_ty(len, ty, Self::TY)?;
+        limits::check_len(len)?;
+        limits::alloc::<Self>(len)?;
 
         let mut list = new(len);
         for _ in 0..len {
@@ -99,7 +129,9 @@ decode_types! {
         }
 
         fn decode_vec(reader: &mut &[u8]) -> Result<Vec<Self>> {
-            <&[u8] as Decode>::decode(reader).map(|bytes| bytes.to_vec())
+            let bytes = <&[u8] as Decode>::decode(reader)?;
+            limits::alloc::<u8>(bytes.len())?;
+            Ok(bytes.to_vec())
         }
     }
 
@@ -109,7 +141,9 @@ decode_types! {
         }
 
         fn decode_vec(reader: &mut &[u8]) -> Result<Vec<Self>> {
-            <&[i8] as Decode>::decode(reader).map(|bytes| bytes.to_vec())
+            let bytes = <&[i8] as Decode>::decode(reader)?;
+            limits::alloc::<i8>(bytes.len())?;
+            Ok(bytes.to_vec())
         }
     }
 }
@@ -130,6 +164,13 @@ decode! {
         varint::read_u64(reader).map(zig_zag::zigzag_decode)
     }
 
+    u128 = UInt (reader) {
+        varint::read_u128(reader)
+    }
+    i128 = Int (reader) {
+        varint::read_u128(reader).map(zig_zag::zigzag_decode128)
+    }
+
     u16 = UInt (reader) {
         Ok(Self::try_from(u64::decode(reader)?)?)
     }
@@ -152,20 +193,24 @@ decode! {
     }
 
     String = Str (reader) {
-        decode_str(reader).map(Self::from)
+        let str = decode_str(reader)?;
+        limits::alloc::<u8>(str.len())?;
+        Ok(str.into())
     }
 
     // ==================== List ==========================
 
     &'de [u8] = List (reader) {
         let (len, ty) = decode_list_len_and_ty(reader)?;
-        ty.expected(DataType::U8)?;
+        expected_item_ty(len, ty, DataType::U8)?;
+        limits::check_bytes(len)?;
         Ok(utils::read_bytes(reader, len)?)
     }
 
     &'de [i8] = List (reader) {
        let (len, ty) = decode_list_len_and_ty(reader)?;
-       ty.expected(DataType::I8)?;
+       expected_item_ty(len, ty, DataType::I8)?;
+       limits::check_bytes(len)?;
        Ok(utils::read_bytes(reader, len).map(utils::i8_slice_from)?)
     }
 
@@ -180,7 +225,9 @@ decode! {
 
     Vec<bool> = List (reader) {
         let (len, ty) = decode_list_len_and_ty(reader)?;
-        ty.expected(DataType::True)?;
+        expected_item_ty(len, ty, DataType::True)?;
+        limits::check_len(len)?;
+        limits::alloc::<bool>(len)?;
 
         let bitvec = utils::read_bytes(reader, utils::bool_packed_len(len))?;
         Ok(bitvec_to_bools(bitvec, len))
@@ -246,12 +293,15 @@ where
         format!("invalid column count: expected `2`, found {col_count}")
     );
 
+    let _depth = limits::Depth::enter()?;
     let row_count = decode_len(reader)?;
+    limits::check_len(row_count)?;
+    limits::alloc::<(K, V)>(row_count)?;
 
     let (col_id, col_ty) = decode_field_id_and_ty(reader)?;
 
     if col_id == 0 {
-        col_ty.expected(K::TY)?;
+        expected_item_ty(row_count, col_ty, K::TY)?;
         let keys = utils::try_collect(row_count, || K::decode(reader))?;
 
         let (val_id, val_ty) = decode_field_id_and_ty(reader)?;
@@ -260,7 +310,7 @@ where
             format!("invalid column (value) id: expected `1`, found {val_id}")
         );
 
-        val_ty.expected(V::TY)?;
+        expected_item_ty(row_count, val_ty, V::TY)?;
 
         let mut map = new(row_count);
         for key in keys {
@@ -269,7 +319,7 @@ where
         return Ok(map);
     }
     if col_id == 1 {
-        col_ty.expected(V::TY)?;
+        expected_item_ty(row_count, col_ty, V::TY)?;
         let vals = utils::try_collect(row_count, || V::decode(reader))?;
 
         let (key_id, key_ty) = decode_field_id_and_ty(reader)?;
@@ -277,7 +327,7 @@ where
             key_id == 0,
             format!("invalid column (key) id: expected `0`, found {key_id}")
         );
-        key_ty.expected(K::TY)?;
+        expected_item_ty(row_count, key_ty, K::TY)?;
 
         let mut map = new(row_count);
         for val in vals {
@@ -307,7 +357,7 @@ macro_rules! tuples {
                 while let Some((key, _ty)) = fd.next_field_id_and_ty()? {
                     match key {
                         $($idx => $name = fd.decode(_ty, concat!("tuple ", $idx))?,)*
-                        _ => fd.skip_field(key, _ty)?,
+                        _ => fd.unknown_field(key, _ty)?,
                     }
                 }
 
@@ -336,6 +386,49 @@ tuples! { T0:0 T1:1 T2:2 T3:3 T4:4 T5:5 T6:6 T7:7 T8:8 T9:9 T10:10 T11:11 T12:12
 tuples! { T0:0 T1:1 T2:2 T3:3 T4:4 T5:5 T6:6 T7:7 T8:8 T9:9 T10:10 T11:11 T12:12 T13:13 }
 tuples! { T0:0 T1:1 T2:2 T3:3 T4:4 T5:5 T6:6 T7:7 T8:8 T9:9 T10:10 T11:11 T12:12 T13:13 T14:14 }
 
+// -------------------------------------- STD ----------------------------------------
+
+impl<'de, T: Decode<'de>> Decode<'de> for Box<T> {
+    const TY: DataType = T::TY;
+
+    #[inline]
+    fn decode(reader: &mut &'de [u8]) -> Result<Self> {
+        T::decode(reader).map(Box::new)
+    }
+}
+
+/// Struct with field `0` as `Ok`, or `1` as `Err`.
+impl<'de, T, E> Decode<'de> for std::result::Result<T, E>
+where
+    T: Optional,
+    T::Value: FieldDecoder<'de>,
+    E: FieldDecoder<'de>,
+{
+    const TY: DataType = DataType::Struct;
+
+    fn decode(reader: &mut &'de [u8]) -> Result<Self> {
+        let mut ok = None;
+        let mut err = None;
+
+        let mut fd = FieldInfoDecoder::new(reader);
+        while let Some((key, ty)) = fd.next_field_id_and_ty()? {
+            match key {
+                0 => ok = fd.decode(ty, "Ok")?,
+                1 => err = fd.decode(ty, "Err")?,
+                _ => fd.unknown_field(key, ty)?,
+            }
+        }
+
+        match err {
+            Some(err) => {
+                assert_or_err!(ok.is_none(), "result has both `Ok` and `Err`");
+                Ok(Err(err))
+            }
+            None => Ok(Ok(Optional::convert(ok, "Ok")?)),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------------
 
 pub trait FieldDecoder<'de>: Sized {
@@ -362,6 +455,29 @@ where
     }
 }
 
+/// Decodes fields of a struct, one at a time. (See [crate::encoder::EncodeFields])
+pub trait DecodeFields<'de>: Sized {
+    /// Decoded fields, so far.
+    type Builder;
+
+    fn builder() -> Self::Builder;
+
+    /// Returns `false`, if the field is unknown. (It's not consumed)
+    fn decode_field(
+        builder: &mut Self::Builder,
+        reader: &mut &'de [u8],
+        id: u64,
+        ty: DataType,
+    ) -> Result<bool>;
+
+    fn finish(builder: Self::Builder) -> Result<Self>;
+
+    /// Returns `true`, if the field is an `Option<T>`.
+    ///
+    /// Its values are wrapped in a struct, in a table column. (See [crate::Columns])
+    fn is_optional(id: u64) -> bool;
+}
+
 pub struct FieldInfoDecoder<'c, 'de> {
     pub reader: &'c mut &'de [u8],
 }
@@ -395,7 +511,14 @@ impl<'c, 'de> FieldInfoDecoder<'c, 'de> {
     where
         T: FieldDecoder<'de>,
     {
-        T::decode_field(self.reader, ty).map_err(|error| errors::FieldError { ty, name, error })
+        let field_error = |error| errors::FieldError { ty, name, error };
+        let _depth = match ty {
+            DataType::Struct | DataType::Union | DataType::List | DataType::Table => {
+                Some(limits::Depth::enter().map_err(field_error)?)
+            }
+            _ => None,
+        };
+        T::decode_field(self.reader, ty).map_err(field_error)
     }
 
     #[inline]
@@ -409,15 +532,40 @@ impl<'c, 'de> FieldInfoDecoder<'c, 'de> {
     {
         self.decode_field(ty, name).map(Some)
     }
+
+    /// Decodes the field as `U`, then converts it with `f`. (Used by `#[decode_with]`)
+    pub fn decode_with<U, T, E>(
+        &mut self,
+        ty: DataType,
+        name: &'static str,
+        f: impl FnOnce(U) -> Result<T, E>,
+    ) -> Result<Option<T>, errors::FieldError>
+    where
+        U: FieldDecoder<'de>,
+        E: Into<crate::Error>,
+    {
+        let val = self.decode_field(ty, name)?;
+        match f(val) {
+            Ok(val) => Ok(Some(val)),
+            Err(error) => Err(errors::FieldError {
+                ty,
+                name,
+                error: error.into(),
+            }),
+        }
+    }
 }
 
 pub trait Optional: Sized {
+    /// `true` for `Option<T>`.
+    const IS_OPTION: bool;
     type Value;
     type Error: std::error::Error + Send + Sync + 'static;
     fn convert(val: Option<Self::Value>, name: &'static str) -> Result<Self, Self::Error>;
 }
 
 impl<T> Optional for Option<T> {
+    const IS_OPTION: bool = true;
     type Value = T;
     type Error = std::convert::Infallible;
     #[inline]
@@ -430,6 +578,7 @@ impl<'de, T> Optional for T
 where
     T: FieldDecoder<'de>,
 {
+    const IS_OPTION: bool = false;
     type Value = T;
     type Error = errors::RequiredField;
     #[inline]
@@ -0,0 +1,107 @@
+use super::limits::{self, Budget, DecodeLimits};
+use crate::{Decode, Result, errors::UnknownField};
+use std::cell::RefCell;
+
+/// Options applied to every decoder, called by [DecodeOptions::decode].
+///
+/// Unknown fields are skipped by default, for forward compatibility, and there are no limits.
+#[derive(Debug, Default, Clone)]
+pub struct DecodeOptions {
+    deny_unknown_fields: bool,
+    limits: Option<DecodeLimits>,
+}
+
+/// Decoded value, with the unknown fields that were skipped.
+#[derive(Debug)]
+pub struct Decoded<T> {
+    pub value: T,
+    /// In encoded order, always empty if unknown fields are denied.
+    pub unknown_fields: Vec<UnknownField>,
+}
+
+struct State {
+    deny_unknown_fields: bool,
+    unknown_fields: Vec<UnknownField>,
+    /// Swapped with the budget of the outer scope.
+    budget: Option<Budget>,
+}
+
+thread_local! {
+    static STATE: RefCell<Option<State>> = const { RefCell::new(None) };
+}
+
+impl DecodeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rejects unknown fields with [UnknownField] error, instead of skipping them.
+    pub fn deny_unknown_fields(mut self, deny: bool) -> Self {
+        self.deny_unknown_fields = deny;
+        self
+    }
+
+    /// Rejects input that exceeds `limits`, with [LimitExceeded](crate::errors::LimitExceeded) error.
+    pub fn limits(mut self, limits: DecodeLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    pub fn decode<'de, T: Decode<'de>>(&self, reader: &mut &'de [u8]) -> Result<Decoded<T>> {
+        self.scope(|| T::decode(reader))
+    }
+
+    /// Applies the options to every decoder called by `f`.
+    pub fn scope<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<Decoded<T>> {
+        let scope = Scope::enter(State {
+            deny_unknown_fields: self.deny_unknown_fields,
+            unknown_fields: Vec::new(),
+            budget: self.limits.map(Budget::new),
+        });
+        let value = f()?;
+        let State { unknown_fields, .. } = scope.exit();
+        Ok(Decoded {
+            value,
+            unknown_fields,
+        })
+    }
+}
+
+/// Restores the outer state, on exit or unwind.
+struct Scope(Option<Option<State>>);
+
+impl Scope {
+    fn enter(mut state: State) -> Self {
+        state.budget = limits::replace(state.budget);
+        Self(Some(STATE.replace(Some(state))))
+    }
+
+    fn exit(mut self) -> State {
+        Self::restore(self.0.take().unwrap()).unwrap()
+    }
+
+    fn restore(outer: Option<State>) -> Option<State> {
+        let mut state = STATE.replace(outer)?;
+        state.budget = limits::replace(state.budget);
+        Some(state)
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        if let Some(outer) = self.0.take() {
+            Self::restore(outer);
+        }
+    }
+}
+
+pub(crate) fn unknown_field(field: UnknownField) -> Result<()> {
+    STATE.with_borrow_mut(|state| match state {
+        Some(state) if state.deny_unknown_fields => Err(field.into()),
+        Some(state) => {
+            state.unknown_fields.push(field);
+            Ok(())
+        }
+        None => Ok(()),
+    })
+}
@@ -0,0 +1,356 @@
+use super::*;
+use crate::DecodeOwned;
+use std::{
+    io::{self, Read},
+    marker::PhantomData,
+};
+
+/// Pull-based decoder over an [io::Read].
+///
+/// Each value (or struct field, or list item) is read into a reused buffer, then decoded from it.
+/// So only the largest of them has to be contiguous in memory, instead of the whole input.
+///
+/// ```
+/// use lipi::{Encode, decoder::ReadDecoder};
+///
+/// let bytes = vec!["a".to_string(), "b".to_string()].to_bytes().unwrap();
+/// let mut decoder = ReadDecoder::new(&*bytes);
+///
+/// let items: Vec<String> = decoder.decode_items().unwrap().collect::<lipi::Result<_>>().unwrap();
+/// assert_eq!(items, ["a", "b"]);
+/// ```
+#[derive(Debug)]
+pub struct ReadDecoder<R> {
+    reader: R,
+    buf: Vec<u8>,
+}
+
+impl<R: Read> ReadDecoder<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the next value of type `ty`, without decoding it.
+    pub fn read_value(&mut self, ty: DataType) -> Result<&[u8]> {
+        self.buf.clear();
+        Scan::new(&mut self.reader, &mut self.buf).value(ty)?;
+        Ok(&self.buf)
+    }
+
+    pub fn decode<T: DecodeOwned>(&mut self) -> Result<T> {
+        let mut bytes = self.read_value(T::TY)?;
+        T::decode(&mut bytes)
+    }
+
+    /// Same as [ReadDecoder::decode], but returns `None` at the end of input.
+    ///
+    /// Used to decode a sequence of values, that aren't wrapped in a list.
+    pub fn try_decode<T: DecodeOwned>(&mut self) -> Result<Option<T>> {
+        let mut first = [0];
+        if read_at_most(&mut self.reader, &mut first)? == 0 {
+            return Ok(None);
+        }
+        self.buf.clear();
+        let mut reader = first.chain(&mut self.reader);
+        Scan::new(&mut reader, &mut self.buf).value(T::TY)?;
+        T::decode(&mut &*self.buf).map(Some)
+    }
+
+    /// Decodes a list, one item at a time.
+    pub fn decode_items<T: DecodeOwned>(&mut self) -> Result<Items<'_, R, T>> {
+        self.buf.clear();
+        let (len, ty) = Scan::new(&mut self.reader, &mut self.buf).field_id_and_ty()?;
+        let len = usize::try_from(len)?;
+        expected_item_ty(len, ty, T::TY)?;
+        limits::check_len(len)?;
+        Ok(Items {
+            decoder: self,
+            remaining: len,
+            _item: PhantomData,
+        })
+    }
+
+    /// Reads the next field header of a struct, `None` at the end of the struct.
+    pub fn next_field_id_and_ty(&mut self) -> Result<Option<(u64, DataType)>> {
+        self.buf.clear();
+        let (id, ty) = Scan::new(&mut self.reader, &mut self.buf).field_id_and_ty()?;
+        field_or_end(id, ty)
+    }
+
+    /// Reads the value of a field, then decodes it. (See [FieldInfoDecoder::decode_field])
+    pub fn decode_field<T>(
+        &mut self,
+        ty: DataType,
+        name: &'static str,
+    ) -> Result<T, errors::FieldError>
+    where
+        T: FieldDecoderOwned,
+    {
+        let mut bytes =
+            self.read_value(ty)
+                .map_err(|error| errors::FieldError { ty, name, error })?;
+        FieldInfoDecoder::new(&mut bytes).decode_field(ty, name)
+    }
+
+    /// Skips a field, that is not part of the struct. (See [FieldInfoDecoder::unknown_field])
+    pub fn unknown_field(&mut self, id: u64, ty: DataType) -> Result<()> {
+        options::unknown_field(errors::UnknownField { id, ty })?;
+        self.read_value(ty)
+            .map_err(|error| errors::SkipFieldError { id, error })?;
+        Ok(())
+    }
+}
+
+/// Items of a list, decoded by [ReadDecoder::decode_items].
+///
+/// The list isn't fully read, if it's dropped before its last item.
+pub struct Items<'a, R, T> {
+    decoder: &'a mut ReadDecoder<R>,
+    remaining: usize,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<R, T> Items<'_, R, T> {
+    /// Number of items, that haven't been decoded yet.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<R: Read, T: DecodeOwned> Iterator for Items<'_, R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let item = self.decoder.decode();
+        // Stream position is unknown after an error.
+        self.remaining = match item {
+            Ok(_) => self.remaining - 1,
+            Err(_) => 0,
+        };
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+/// `None` at the end of a struct.
+pub(super) fn field_or_end(id: u64, ty: DataType) -> Result<Option<(u64, DataType)>> {
+    if ty == DataType::StructEnd {
+        assert_or_err!(
+            id == 0,
+            format!("invalid struct end field id ({id}), expected `0`")
+        );
+        return Ok(None);
+    }
+    Ok(Some((id, ty)))
+}
+
+fn read_at_most(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match reader.read(buf) {
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            result => return result,
+        }
+    }
+}
+
+/// Copies a single encoded value from the reader, same as [FieldInfoDecoder::skip_field_value].
+pub(super) struct Scan<'a, R> {
+    reader: &'a mut R,
+    buf: &'a mut Vec<u8>,
+}
+
+impl<'a, R: Read> Scan<'a, R> {
+    pub(super) fn new(reader: &'a mut R, buf: &'a mut Vec<u8>) -> Self {
+        Self { reader, buf }
+    }
+
+    /// Reads exactly `len` bytes, without allocating all of them upfront.
+    fn bytes(&mut self, len: usize) -> Result<()> {
+        let read = (&mut *self.reader).take(len as u64).read_to_end(self.buf)?;
+
+        if read < len {
+            return Err(errors::UnexpectedEof { needed: len - read }.into());
+        }
+        Ok(())
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        self.bytes(1)?;
+        Ok(self.buf[self.buf.len() - 1])
+    }
+
+    /// Returns the start of the varint, in the buffer.
+    fn varint(&mut self) -> Result<usize> {
+        let start = self.buf.len();
+        // 128-bit value has at most 19 bytes.
+        for _ in 0..19 {
+            if self.byte()? & 0b_1000_0000 == 0 {
+                return Ok(start);
+            }
+        }
+        Err(errors::VarIntError.into())
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        let start = self.varint()?;
+        decode_len(&mut &self.buf[start..])
+    }
+
+    pub(super) fn field_id_and_ty(&mut self) -> Result<(u64, DataType)> {
+        let start = self.buf.len();
+        if self.byte()? >> 4 == 0b_1111 {
+            self.varint()?;
+        }
+        decode_field_id_and_ty(&mut &self.buf[start..])
+    }
+
+    fn str(&mut self) -> Result<()> {
+        let len = self.len()?;
+        limits::check_bytes(len)?;
+        self.bytes(len)
+    }
+
+    pub(super) fn value(&mut self, ty: DataType) -> Result<()> {
+        match ty {
+            DataType::True | DataType::False => {}
+            DataType::U8 | DataType::I8 => self.bytes(1)?,
+            DataType::F32 => self.bytes(4)?,
+            DataType::F64 => self.bytes(8)?,
+            DataType::UInt | DataType::Int => {
+                self.varint()?;
+            }
+            DataType::Str | DataType::UnknownI | DataType::UnknownII => self.str()?,
+
+            DataType::StructEnd => return Err("unexpected struct end".into()),
+            DataType::Struct => self.fields()?,
+            DataType::Union => {
+                let _depth = limits::Depth::enter()?;
+                let (_, ty) = self.field_id_and_ty()?;
+                self.value(ty)?;
+            }
+            DataType::List => {
+                let _depth = limits::Depth::enter()?;
+                let (len, ty) = self.field_id_and_ty()?;
+                let len = usize::try_from(len)?;
+                limits::check_len(len)?;
+                self.list_values(len, ty)?;
+            }
+            DataType::Table => {
+                let _depth = limits::Depth::enter()?;
+                let cols = self.len()?;
+                let rows = self.len()?;
+                limits::check_len(rows)?;
+                for _ in 0..cols {
+                    let (_, ty) = self.field_id_and_ty()?;
+                    self.list_values(rows, ty)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fields(&mut self) -> Result<()> {
+        let _depth = limits::Depth::enter()?;
+        loop {
+            match self.field_id_and_ty()? {
+                (_, DataType::StructEnd) => return Ok(()),
+                (_, ty) => self.value(ty)?,
+            }
+        }
+    }
+
+    fn list_values(&mut self, len: usize, ty: DataType) -> Result<()> {
+        match ty {
+            DataType::False => return Err("unexpected bool packed in list".into()),
+            DataType::True => self.bytes(utils::bool_packed_len(len))?,
+            DataType::U8 | DataType::I8 => self.bytes(len)?,
+            DataType::F32 => self.bytes(len.saturating_mul(4))?,
+            DataType::F64 => self.bytes(len.saturating_mul(8))?,
+            DataType::StructEnd => return Err("unexpected list ty struct end".into()),
+            _ => {
+                for _ in 0..len {
+                    self.value(ty)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Encode, Value};
+
+    /// Reader that returns a single byte per read.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn test_read_value() {
+        let value = Value::Struct(vec![
+            (0, Value::U8(1)),
+            (20, Value::Str("lipi".into())),
+            (3, Value::List(DataType::F32, vec![Value::F32(1.0); 2])),
+            (4, Value::List(DataType::True, vec![Value::Bool(true); 9])),
+            (5, Value::Union(7, Box::new(Value::Int(-3)))),
+            (
+                6,
+                Value::Table(vec![(0, DataType::UInt, vec![Value::UInt(300); 2])]),
+            ),
+        ]);
+        let bytes = value.to_bytes().unwrap();
+        let trailing = [&bytes[..], &[42]].concat();
+
+        let mut decoder = ReadDecoder::new(Trickle(&trailing));
+        assert_eq!(decoder.read_value(DataType::Struct).unwrap(), bytes);
+        assert_eq!(decoder.decode::<u8>().unwrap(), 42);
+        assert!(decoder.try_decode::<u8>().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_unexpected_eof() {
+        let bytes = "Hello".to_bytes().unwrap();
+        let mut decoder = ReadDecoder::new(&bytes[..3]);
+        let err = decoder.decode::<String>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unexpected end of file: needed 3 more bytes"
+        );
+
+        let mut decoder = ReadDecoder::new(&[0x80; 32][..]);
+        assert!(
+            decoder
+                .decode::<u64>()
+                .unwrap_err()
+                .is::<errors::VarIntError>()
+        );
+    }
+}
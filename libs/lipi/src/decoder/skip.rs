@@ -1,6 +1,14 @@
 use crate::{DataType, Result, decoder::*, errors, utils, varint};
 
 impl<'c, 'de> FieldInfoDecoder<'c, 'de> {
+    /// Skips a field, that is not part of the struct.
+    ///
+    /// Unless it's rejected or recorded by [DecodeOptions].
+    pub fn unknown_field(&mut self, id: u64, ty: DataType) -> Result<()> {
+        options::unknown_field(errors::UnknownField { id, ty })?;
+        self.skip_field(id, ty)
+    }
+
     pub fn skip_field(&mut self, id: u64, ty: DataType) -> Result<()> {
         self.skip_field_value(ty)
             .map_err(|error| errors::SkipFieldError { id, error }.into())
@@ -18,8 +26,9 @@ impl<'c, 'de> FieldInfoDecoder<'c, 'de> {
             DataType::F64 => {
                 utils::read_bytes(self.reader, 8)?;
             }
+            // Widest integer, so 128-bit values are skipped as well.
             DataType::UInt | DataType::Int => {
-                varint::read_u64(self.reader)?;
+                varint::read_u128(self.reader)?;
             }
             DataType::Str | DataType::UnknownI | DataType::UnknownII => {
                 decode_bytes(self.reader)?;
@@ -35,6 +44,7 @@ impl<'c, 'de> FieldInfoDecoder<'c, 'de> {
     }
 
     fn skip_struct(&mut self) -> Result<()> {
+        let _depth = limits::Depth::enter()?;
         while let Some((id, ty)) = self.next_field_id_and_ty()? {
             self.skip_field(id, ty)?;
         }
@@ -42,11 +52,12 @@ impl<'c, 'de> FieldInfoDecoder<'c, 'de> {
     }
 
     fn skip_union(&mut self) -> Result<()> {
+        let _depth = limits::Depth::enter()?;
         let (id, ty) = decode_field_id_and_ty(self.reader)?;
         self.skip_field(id, ty)
     }
 
-    fn skip_list_values(&mut self, len: usize, ty: DataType) -> Result<()> {
+    pub(crate) fn skip_list_values(&mut self, len: usize, ty: DataType) -> Result<()> {
         match ty {
             DataType::False => return Err("unexpected bool packed in list".into()),
             DataType::True => {
@@ -65,7 +76,7 @@ impl<'c, 'de> FieldInfoDecoder<'c, 'de> {
 
             DataType::UInt | DataType::Int => {
                 for _ in 0..len {
-                    varint::read_u64(self.reader)?;
+                    varint::read_u128(self.reader)?;
                 }
             }
 
@@ -101,13 +112,17 @@ impl<'c, 'de> FieldInfoDecoder<'c, 'de> {
     }
 
     fn skip_list(&mut self) -> Result<()> {
+        let _depth = limits::Depth::enter()?;
         let (len, ty) = decode_list_len_and_ty(self.reader)?;
+        limits::check_len(len)?;
         self.skip_list_values(len, ty)
     }
 
     fn skip_table(&mut self) -> Result<()> {
+        let _depth = limits::Depth::enter()?;
         let cols = decode_len(self.reader)?;
         let rows = decode_len(self.reader)?;
+        limits::check_len(rows)?;
 
         for _ in 0..cols {
             let (col_id, col_ty) = decode_field_id_and_ty(self.reader)?;
@@ -0,0 +1,283 @@
+use super::{
+    read::{Scan, field_or_end},
+    *,
+};
+use crate::DecodeOwned;
+use futures_core::Stream;
+use std::{
+    collections::VecDeque,
+    future::poll_fn,
+    io::{self, Read},
+    marker::PhantomData,
+    pin::Pin,
+};
+
+/// Pull-based decoder over an async [Stream] of byte chunks, such as an HTTP body.
+///
+/// Same as [ReadDecoder], but each value is scanned from the buffered chunks, and more chunks
+/// are awaited only when they aren't enough. Chunks are dropped once they are decoded.
+///
+/// Decoding happens between awaits, so [DecodeOptions::scope] can't span them.
+/// Instead, options are set by [StreamDecoder::options], and applied to each value separately.
+///
+/// ```
+/// use lipi::{Encode, decoder::StreamDecoder};
+///
+/// let bytes = vec!["a".to_string(), "b".to_string()].to_bytes().unwrap();
+/// let chunks = bytes.chunks(2).map(Ok::<_, std::io::Error>);
+/// let mut decoder = StreamDecoder::new(futures::stream::iter(chunks));
+///
+/// futures::executor::block_on(async {
+///     let mut items = decoder.decode_items::<String>().await.unwrap();
+///     assert_eq!(items.next().await.unwrap().unwrap(), "a");
+///     assert_eq!(items.next().await.unwrap().unwrap(), "b");
+///     assert!(items.next().await.is_none());
+/// });
+/// ```
+#[derive(Debug)]
+pub struct StreamDecoder<S, B> {
+    stream: S,
+    ended: bool,
+    chunks: VecDeque<B>,
+    /// Bytes of the first chunk, that are already decoded.
+    offset: usize,
+    /// Bytes of the chunks, that aren't decoded yet.
+    buffered: usize,
+    buf: Vec<u8>,
+    options: DecodeOptions,
+}
+
+impl<S, B, E> StreamDecoder<S, B>
+where
+    S: Stream<Item = Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+    E: Into<crate::Error>,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            ended: false,
+            chunks: VecDeque::new(),
+            offset: 0,
+            buffered: 0,
+            buf: Vec::new(),
+            options: DecodeOptions::new(),
+        }
+    }
+
+    /// Applies `options` to every value, including the ones that are only read or skipped.
+    pub fn options(mut self, options: DecodeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Reads the next value of type `ty`, without decoding it.
+    pub async fn read_value(&mut self, ty: DataType) -> Result<&[u8]> {
+        self.scan(|scan| scan.value(ty)).await?;
+        Ok(&self.buf)
+    }
+
+    pub async fn decode<T: DecodeOwned>(&mut self) -> Result<T> {
+        self.read_value(T::TY).await?;
+        scope(&self.options, || T::decode(&mut &*self.buf))
+    }
+
+    /// Same as [StreamDecoder::decode], but returns `None` at the end of the stream.
+    ///
+    /// Used to decode a sequence of values, that aren't wrapped in a list.
+    pub async fn try_decode<T: DecodeOwned>(&mut self) -> Result<Option<T>> {
+        if self.buffered == 0 && !self.fill(1).await? {
+            return Ok(None);
+        }
+        self.decode().await.map(Some)
+    }
+
+    /// Decodes a list, one item at a time.
+    pub async fn decode_items<T: DecodeOwned>(&mut self) -> Result<StreamItems<'_, S, B, T>> {
+        let (len, ty) = self.scan(|scan| scan.field_id_and_ty()).await?;
+        let len = usize::try_from(len)?;
+        scope(&self.options, || {
+            expected_item_ty(len, ty, T::TY)?;
+            limits::check_len(len)
+        })?;
+        Ok(StreamItems {
+            decoder: self,
+            remaining: len,
+            _item: PhantomData,
+        })
+    }
+
+    /// Reads the next field header of a struct, `None` at the end of the struct.
+    pub async fn next_field_id_and_ty(&mut self) -> Result<Option<(u64, DataType)>> {
+        let (id, ty) = self.scan(|scan| scan.field_id_and_ty()).await?;
+        field_or_end(id, ty)
+    }
+
+    /// Reads the value of a field, then decodes it. (See [FieldInfoDecoder::decode_field])
+    pub async fn decode_field<T>(
+        &mut self,
+        ty: DataType,
+        name: &'static str,
+    ) -> Result<T, errors::FieldError>
+    where
+        T: FieldDecoderOwned,
+    {
+        self.read_value(ty)
+            .await
+            .map_err(|error| errors::FieldError { ty, name, error })?;
+        let decoded = self
+            .options
+            .scope(|| Ok(FieldInfoDecoder::new(&mut &*self.buf).decode_field(ty, name)))
+            .map_err(|error| errors::FieldError { ty, name, error })?;
+        decoded.value
+    }
+
+    /// Skips a field, that is not part of the struct. (See [FieldInfoDecoder::unknown_field])
+    pub async fn unknown_field(&mut self, id: u64, ty: DataType) -> Result<()> {
+        scope(&self.options, || {
+            options::unknown_field(errors::UnknownField { id, ty })
+        })?;
+        self.read_value(ty)
+            .await
+            .map_err(|error| errors::SkipFieldError { id, error })?;
+        Ok(())
+    }
+
+    /// Scans the buffered chunks into `buf`, awaiting more of them on [errors::UnexpectedEof].
+    async fn scan<T>(
+        &mut self,
+        mut f: impl FnMut(&mut Scan<'_, Chunks<'_, B>>) -> Result<T>,
+    ) -> Result<T> {
+        loop {
+            self.buf.clear();
+            let mut chunks = Chunks {
+                chunks: &self.chunks,
+                index: 0,
+                offset: self.offset,
+            };
+            let scanned = scope(&self.options, || {
+                f(&mut Scan::new(&mut chunks, &mut self.buf))
+            });
+            let err = match scanned {
+                Ok(value) => {
+                    self.consume(self.buf.len());
+                    return Ok(value);
+                }
+                Err(err) => err,
+            };
+            let Some(&errors::UnexpectedEof { needed }) = err.downcast_ref() else {
+                return Err(err);
+            };
+            // At least doubles the buffered bytes, so a value is rescanned `O(log n)` times.
+            let target = self.buffered.saturating_add(needed.max(self.buffered));
+            if !self.fill(target).await? {
+                return Err(err);
+            }
+        }
+    }
+
+    /// Awaits chunks until `target` bytes are buffered, returns `false` if none were received.
+    async fn fill(&mut self, target: usize) -> Result<bool> {
+        let mut received = false;
+        while !self.ended && self.buffered < target {
+            match poll_fn(|cx| Pin::new(&mut self.stream).poll_next(cx)).await {
+                Some(chunk) => {
+                    let chunk = chunk.map_err(Into::into)?;
+                    let len = chunk.as_ref().len();
+                    if len > 0 {
+                        self.buffered += len;
+                        self.chunks.push_back(chunk);
+                        received = true;
+                    }
+                }
+                None => self.ended = true,
+            }
+        }
+        Ok(received)
+    }
+
+    fn consume(&mut self, len: usize) {
+        self.buffered -= len;
+        let mut offset = self.offset + len;
+        while let Some(chunk) = self.chunks.front() {
+            let chunk_len = chunk.as_ref().len();
+            if offset < chunk_len {
+                break;
+            }
+            offset -= chunk_len;
+            self.chunks.pop_front();
+        }
+        self.offset = offset;
+    }
+}
+
+fn scope<T>(options: &DecodeOptions, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    Ok(options.scope(f)?.value)
+}
+
+/// Items of a list, decoded by [StreamDecoder::decode_items].
+///
+/// The list isn't fully read, if it's dropped before its last item.
+pub struct StreamItems<'a, S, B, T> {
+    decoder: &'a mut StreamDecoder<S, B>,
+    remaining: usize,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<S, B, T> StreamItems<'_, S, B, T> {
+    /// Number of items, that haven't been decoded yet.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<S, B, E, T> StreamItems<'_, S, B, T>
+where
+    S: Stream<Item = Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+    E: Into<crate::Error>,
+    T: DecodeOwned,
+{
+    #[allow(clippy::should_implement_trait)]
+    pub async fn next(&mut self) -> Option<Result<T>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let item = self.decoder.decode().await;
+        // Stream position is unknown after an error.
+        self.remaining = match item {
+            Ok(_) => self.remaining - 1,
+            Err(_) => 0,
+        };
+        Some(item)
+    }
+}
+
+/// Reads the buffered chunks, without consuming them.
+struct Chunks<'a, B> {
+    chunks: &'a VecDeque<B>,
+    index: usize,
+    offset: usize,
+}
+
+impl<B: AsRef<[u8]>> Read for Chunks<'_, B> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while let Some(chunk) = self.chunks.get(self.index) {
+            let rest = &chunk.as_ref()[self.offset..];
+            if rest.is_empty() {
+                self.index += 1;
+                self.offset = 0;
+                continue;
+            }
+            let len = rest.len().min(buf.len());
+            buf[..len].copy_from_slice(&rest[..len]);
+            self.offset += len;
+            return Ok(len);
+        }
+        Ok(0)
+    }
+}
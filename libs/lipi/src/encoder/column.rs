@@ -0,0 +1,78 @@
+use super::*;
+
+/// Values of a table column, without a header. (See [crate::Columns])
+///
+/// Every row has a value, so `Option<T>` is encoded as a `(Option<T>,)` struct.
+pub trait Column {
+    const COLUMN_TY: DataType;
+
+    fn encode_column<'a>(
+        writer: &mut (impl Write + ?Sized),
+        values: impl Iterator<Item = &'a Self>,
+    ) -> Result<()>
+    where
+        Self: 'a;
+}
+
+impl Column for bool {
+    const COLUMN_TY: DataType = DataType::True;
+
+    fn encode_column<'a>(
+        writer: &mut (impl Write + ?Sized),
+        values: impl Iterator<Item = &'a Self>,
+    ) -> Result<()> {
+        let bools: Vec<bool> = values.copied().collect();
+        writer.write_all(&bit_set::bitvec_from(&bools))
+    }
+}
+
+impl<T: OptionalField> Column for Option<T> {
+    const COLUMN_TY: DataType = DataType::Struct;
+
+    fn encode_column<'a>(
+        writer: &mut (impl Write + ?Sized),
+        values: impl Iterator<Item = &'a Self>,
+    ) -> Result<()>
+    where
+        Self: 'a,
+    {
+        for val in values {
+            OptionalField::encode(val, writer, 0)?;
+            writer.write_all(&[DataType::StructEnd.code()])?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Column for T
+where
+    T: Encode + ?Sized,
+{
+    const COLUMN_TY: DataType = T::TY;
+
+    fn encode_column<'a>(
+        writer: &mut (impl Write + ?Sized),
+        values: impl Iterator<Item = &'a Self>,
+    ) -> Result<()>
+    where
+        Self: 'a,
+    {
+        for val in values {
+            T::encode(val, writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Encodes the column header, followed by its `values`.
+pub fn encode_column<'a, T>(
+    writer: &mut (impl Write + ?Sized),
+    id: u32,
+    values: impl Iterator<Item = &'a T>,
+) -> Result<()>
+where
+    T: Column + ?Sized + 'a,
+{
+    encode_field_id_and_ty(writer, id, T::COLUMN_TY)?;
+    T::encode_column(writer, values)
+}
@@ -2,12 +2,19 @@ use super::*;
 
 pub trait Field {
     fn encode(&self, writer: &mut (impl Write + ?Sized), id: u32) -> Result<()>;
+
+    /// Length of the encoded field, including its header.
+    fn encoded_len(&self, id: u32) -> usize;
 }
 
 impl Field for bool {
     fn encode(&self, writer: &mut (impl Write + ?Sized), id: u32) -> Result<()> {
         encode_field_id_and_ty(writer, id, DataType::from(*self))
     }
+
+    fn encoded_len(&self, id: u32) -> usize {
+        field_id_and_ty_len(id)
+    }
 }
 
 impl<T> Field for T
@@ -18,4 +25,8 @@ where
         encode_field_id_and_ty(writer, id, T::TY)?;
         T::encode(self, writer)
     }
+
+    fn encoded_len(&self, id: u32) -> usize {
+        field_id_and_ty_len(id) + T::encoded_len(self)
+    }
 }
@@ -1,13 +1,15 @@
+mod column;
 mod field;
 mod optional_field;
 
 use super::DataType;
 use crate::bit_set;
-use crate::varint::{LEB128, Leb128Buf};
+use crate::varint::{self, LEB128, Leb128Buf};
 use crate::{utils, zig_zag};
 use std::collections::{BTreeMap, HashMap};
 use std::io::{self, Result, Write};
 
+pub use column::{Column, encode_column};
 pub use field::Field;
 pub use optional_field::OptionalField;
 
@@ -26,6 +28,55 @@ pub fn encode_field_id_and_ty(
     }
 }
 
+/// Length of [encode_field_id_and_ty], in bytes.
+#[inline]
+pub const fn field_id_and_ty_len(num: u32) -> usize {
+    if num < 15 {
+        1
+    } else {
+        1 + varint::len_u64((num - 15) as u64)
+    }
+}
+
+/// Length of [encode_len], or of the length prefix of [encode_bytes].
+#[inline]
+pub const fn len_len(len: usize) -> usize {
+    varint::len_u64(len as u64)
+}
+
+/// Length of [encode_list_len_and_ty], in bytes.
+#[inline]
+pub const fn list_len_and_ty_len(len: usize) -> usize {
+    field_id_and_ty_len(if len > u32::MAX as usize {
+        u32::MAX
+    } else {
+        len as u32
+    })
+}
+
+/// Counts the bytes written to it. (See [Encode::encoded_len])
+#[derive(Debug, Default)]
+pub struct LenCounter(pub usize);
+
+impl Write for LenCounter {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        self.0 += buf.len();
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
 pub fn encode_uint(writer: &mut (impl Write + ?Sized), num: u64) -> Result<()> {
     let mut buf = unsafe { Leb128Buf::<10>::new() };
     buf.write_u64(num);
@@ -37,6 +88,17 @@ pub fn encode_int(writer: &mut (impl Write + ?Sized), num: i64) -> Result<()> {
     encode_uint(writer, zig_zag::zigzag_encode(num))
 }
 
+pub fn encode_uint128(writer: &mut (impl Write + ?Sized), num: u128) -> Result<()> {
+    let mut buf = unsafe { Leb128Buf::<19>::new() };
+    buf.write_u128(num);
+    writer.write_all(buf.as_bytes())
+}
+
+#[inline]
+pub fn encode_int128(writer: &mut (impl Write + ?Sized), num: i128) -> Result<()> {
+    encode_uint128(writer, zig_zag::zigzag_encode128(num))
+}
+
 #[inline]
 pub fn encode_len(writer: &mut (impl Write + ?Sized), len: usize) -> Result<()> {
     encode_uint(writer, len as u64)
@@ -58,11 +120,50 @@ pub fn encode_list_len_and_ty(
 
 // ------------------------------------------------------------------------
 
+/// Fields of a struct, without [DataType::StructEnd].
+///
+/// Implemented by the derive for structs, and used by `#[flatten]` to inline their fields.
+pub trait EncodeFields {
+    /// Number of columns, written by [EncodeFields::encode_columns].
+    const COLUMNS: usize;
+
+    fn encode_fields(&self, _: &mut (impl Write + ?Sized)) -> io::Result<()>;
+
+    /// Length of [EncodeFields::encode_fields], in bytes.
+    fn encoded_fields_len(&self) -> usize;
+
+    /// Encodes every field of `rows` as a table column. (See [crate::Columns])
+    fn encode_columns<'a>(
+        _: &mut (impl Write + ?Sized),
+        rows: impl Iterator<Item = &'a Self> + Clone,
+    ) -> io::Result<()>
+    where
+        Self: 'a;
+}
+
 pub trait Encode {
     const TY: DataType;
 
     fn encode(&self, _: &mut (impl Write + ?Sized)) -> io::Result<()>;
 
+    /// Length of the encoded value, in bytes, without encoding it.
+    ///
+    /// Used to allocate the exact buffer, before encoding. By default, it counts the bytes written
+    /// by [Encode::encode] with a [LenCounter].
+    fn encoded_len(&self) -> usize {
+        let mut counter = LenCounter(0);
+        let _ = self.encode(&mut counter);
+        counter.0
+    }
+
+    /// Length of [Encode::encode_slice], in bytes.
+    fn encoded_slice_len(this: &[Self]) -> usize
+    where
+        Self: Sized,
+    {
+        list_len_and_ty_len(this.len()) + this.iter().map(Self::encoded_len).sum::<usize>()
+    }
+
     fn encode_slice(writer: &mut (impl Write + ?Sized), this: &[Self]) -> io::Result<()>
     where
         Self: Sized,
@@ -84,7 +185,7 @@ pub trait Encode {
     }
 
     fn to_bytes(&self) -> io::Result<Vec<u8>> {
-        let mut buf = Vec::new();
+        let mut buf = Vec::with_capacity(self.encoded_len());
         self.encode(&mut buf)?;
         Ok(buf)
     }
@@ -93,22 +194,24 @@ pub trait Encode {
 // ------------------------------- Macros ------------------------------------
 
 macro_rules! encode {
-    [$( $( $ty:ty ),* = $dt:tt ($self:tt, $w:tt) $encode:block)*] => [$(
+    [$( $( $ty:ty ),* = $dt:tt ($self:tt, $w:tt) $encode:block len $len:block)*] => [$(
         $(
             impl Encode for $ty {
                 const TY: DataType = DataType::$dt;
                 #[inline] fn encode(&$self, $w: &mut (impl Write + ?Sized)) -> Result<()> $encode
+                #[inline] fn encoded_len(&$self) -> usize $len
             }
         )*
     )*];
 }
 
 macro_rules! encode_types {
-    [$( $ty:ty = $dt:tt $([ $( $param:tt )* ])? $(where {$( $where:tt )*} )? ($self:tt, $w:tt) $encode:block )*] => [$(
+    [$( $ty:ty = $dt:tt $([ $( $param:tt )* ])? $(where {$( $where:tt )*} )? ($self:tt, $w:tt) $encode:block len $len:block )*] => [$(
         impl <$($($param)*)?> Encode for $ty
         $(where $( $where )* )? {
             const TY: DataType = DataType::$dt;
             #[inline] fn encode(&$self, $w: &mut (impl Write + ?Sized)) -> Result<()> $encode
+            #[inline] fn encoded_len(&$self) -> usize $len
         }
     )*];
 }
@@ -121,6 +224,14 @@ impl Encode for u8 {
         writer.write_all(&[*self])
     }
 
+    fn encoded_len(&self) -> usize {
+        1
+    }
+
+    fn encoded_slice_len(this: &[Self]) -> usize {
+        list_len_and_ty_len(this.len()) + this.len()
+    }
+
     fn encode_slice(writer: &mut (impl Write + ?Sized), this: &[Self]) -> io::Result<()> {
         encode_list_len_and_ty(writer, this.len(), Self::TY)?;
         writer.write_all(this)
@@ -133,6 +244,14 @@ impl Encode for i8 {
         writer.write_all(&[self.cast_unsigned()])
     }
 
+    fn encoded_len(&self) -> usize {
+        1
+    }
+
+    fn encoded_slice_len(this: &[Self]) -> usize {
+        list_len_and_ty_len(this.len()) + this.len()
+    }
+
     fn encode_slice(writer: &mut (impl Write + ?Sized), this: &[Self]) -> io::Result<()> {
         encode_list_len_and_ty(writer, this.len(), Self::TY)?;
         writer.write_all(utils::u8_slice_from(this))
@@ -142,28 +261,50 @@ impl Encode for i8 {
 encode! {
     f32 = F32 (self, writer) {
         writer.write_all(&self.to_le_bytes())
-    }
+    } len { 4 }
 
     f64 = F64 (self, writer) {
         writer.write_all(&self.to_le_bytes())
-    }
+    } len { 8 }
 
     u16, u32, u64 = UInt (self, writer) {
         #[allow(clippy::useless_conversion)]
         encode_uint(writer, (*self).into())
+    } len {
+        #[allow(clippy::useless_conversion)]
+        varint::len_u64((*self).into())
     }
 
     i16, i32, i64 = Int (self, writer) {
         #[allow(clippy::useless_conversion)]
         encode_int(writer, (*self).into())
+    } len {
+        #[allow(clippy::useless_conversion)]
+        varint::len_u64(zig_zag::zigzag_encode((*self).into()))
+    }
+
+    u128 = UInt (self, writer) {
+        encode_uint128(writer, *self)
+    } len {
+        varint::len_u128(*self)
+    }
+
+    i128 = Int (self, writer) {
+        encode_int128(writer, *self)
+    } len {
+        varint::len_u128(zig_zag::zigzag_encode128(*self))
     }
 
     char = UInt (self, writer) {
         encode_uint(writer, u32::from(*self).into())
+    } len {
+        varint::len_u64(u32::from(*self).into())
     }
 
     str, String = Str (self, writer) {
         encode_bytes(writer, self.as_bytes())
+    } len {
+        len_len(self.len()) + self.len()
     }
 
     // --------------------------------- List ----------------------------------
@@ -171,6 +312,8 @@ encode! {
     [bool] = List (self, writer) {
         encode_list_len_and_ty(writer, self.len(), DataType::True)?;
         writer.write_all(&bit_set::bitvec_from(self))
+    } len {
+        list_len_and_ty_len(self.len()) + utils::bool_packed_len(self.len())
     }
 }
 
@@ -183,6 +326,9 @@ macro_rules! encode_list {
             #[inline] fn encode(&self, writer: & mut (impl Write + ?Sized)) -> Result<()> {
                 T::encode_iter(writer, self.len(), self.iter())
             }
+            fn encoded_len(&self) -> usize {
+                list_len_and_ty_len(self.len()) + self.iter().map(T::encoded_len).sum::<usize>()
+            }
         }
     )*];
 }
@@ -198,24 +344,34 @@ encode_list! {
 encode_types! {
     [T] = List [T: Encode] (self, writer) {
         T::encode_slice(writer, self)
+    } len {
+        T::encoded_slice_len(self)
     }
 
     Vec<T> = List [T] where { [T]: Encode } (self, writer) {
         <[T] as Encode>::encode(self, writer)
+    } len {
+        <[T] as Encode>::encoded_len(self)
     }
 
     [T; N] = List [T, const N: usize] where { [T]: Encode } (self, writer) {
         <[T] as Encode>::encode(self, writer)
+    } len {
+        <[T] as Encode>::encoded_len(self)
     }
 
     // --------------------------------- Table ----------------------------------
 
     HashMap<K, V> = Table [K: Encode, V: Encode] (self, writer) {
         encode_map(writer, self.len(), || self.keys(), || self.values())
+    } len {
+        encoded_map_len(self.len(), self.keys(), self.values())
     }
 
     BTreeMap<K, V> = Table [K: Encode, V: Encode] (self, writer) {
         encode_map(writer, self.len(), || self.keys(), || self.values())
+    } len {
+        encoded_map_len(self.len(), self.keys(), self.values())
     }
 }
 
@@ -249,6 +405,23 @@ where
     Ok(())
 }
 
+fn encoded_map_len<'a, K, V>(
+    len: usize,
+    keys: impl Iterator<Item = &'a K>,
+    vals: impl Iterator<Item = &'a V>,
+) -> usize
+where
+    K: Encode + 'a,
+    V: Encode + 'a,
+{
+    // Column count, row count and both column headers.
+    1 + len_len(len)
+        + 1
+        + keys.map(K::encoded_len).sum::<usize>()
+        + 1
+        + vals.map(V::encoded_len).sum::<usize>()
+}
+
 // --------------------------------- Deref ----------------------------------
 
 macro_rules! deref_impl {
@@ -259,6 +432,10 @@ macro_rules! deref_impl {
             fn encode(&self, writer: & mut (impl Write + ?Sized)) -> Result<()> {
                 T::encode(&**self, writer)
             }
+            #[inline]
+            fn encoded_len(&self) -> usize {
+                T::encoded_len(&**self)
+            }
         }
     )*]
 }
@@ -300,6 +477,13 @@ where
         };
         w.write_all(&[DataType::StructEnd.code()])
     }
+
+    fn encoded_len(&self) -> usize {
+        1 + match self {
+            Ok(val) => OptionalField::encoded_len(val, 0),
+            Err(err) => Field::encoded_len(err, 1),
+        }
+    }
 }
 
 // --------------------------------- Other ----------------------------------
@@ -316,6 +500,9 @@ macro_rules! tuples {
                 $($name::encode(&self.$idx, writer, $idx)?;)*
                 writer.write_all(&[DataType::StructEnd.code()])
             }
+            fn encoded_len(&self) -> usize {
+                1 $(+ $name::encoded_len(&self.$idx, $idx))*
+            }
         }
     }
 }
@@ -2,12 +2,19 @@ use super::*;
 
 pub trait OptionalField {
     fn encode(&self, writer: &mut (impl Write + ?Sized), id: u16) -> Result<()>;
+
+    /// Length of the encoded field, including its header. (`0` for `None`)
+    fn encoded_len(&self, id: u16) -> usize;
 }
 
 impl OptionalField for bool {
     fn encode(&self, writer: &mut (impl Write + ?Sized), id: u16) -> Result<()> {
         encode_field_id_and_ty(writer, id.into(), DataType::from(*self))
     }
+
+    fn encoded_len(&self, id: u16) -> usize {
+        field_id_and_ty_len(id.into())
+    }
 }
 
 impl<T: OptionalField> OptionalField for Option<T> {
@@ -17,6 +24,13 @@ impl<T: OptionalField> OptionalField for Option<T> {
             None => Ok(()),
         }
     }
+
+    fn encoded_len(&self, id: u16) -> usize {
+        match self {
+            Some(val) => OptionalField::encoded_len(val, id),
+            None => 0,
+        }
+    }
 }
 
 impl<T> OptionalField for T
@@ -27,4 +41,8 @@ where
         encode_field_id_and_ty(writer, id.into(), T::TY)?;
         T::encode(self, writer)
     }
+
+    fn encoded_len(&self, id: u16) -> usize {
+        field_id_and_ty_len(id.into()) + T::encoded_len(self)
+    }
 }
@@ -118,6 +118,33 @@ error! {
     }
 }
 
+/// Limit of [DecodeLimits](crate::decoder::DecodeLimits).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+    Depth,
+    Len,
+    Bytes,
+    Alloc,
+}
+
+#[derive(Debug, Clone)]
+pub struct LimitExceeded {
+    pub limit: Limit,
+    pub max: usize,
+}
+
+error! {
+    LimitExceeded = (self, f) {
+        let limit = match self.limit {
+            Limit::Depth => "max_depth",
+            Limit::Len => "max_len",
+            Limit::Bytes => "max_bytes",
+            Limit::Alloc => "max_alloc",
+        };
+        write!(f, "decode limit exceeded: `{limit}` is {}", self.max)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct UnknownTag<T> {
     pub tag: T,
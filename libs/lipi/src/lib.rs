@@ -1,5 +1,7 @@
 mod bit_set;
+mod columns;
 mod utils;
+mod value;
 mod varint;
 mod zig_zag;
 
@@ -7,10 +9,18 @@ pub mod decoder;
 pub mod encoder;
 pub mod errors;
 
+#[cfg(feature = "serde")]
+mod serde;
+
 pub use bit_set::BitSet;
+pub use columns::Columns;
 pub use decoder::Decode;
 pub use encoder::Encode;
 pub use lipi_macros::*;
+pub use value::Value;
+
+#[cfg(feature = "serde")]
+pub use self::serde::{from_slice, to_vec};
 
 pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
 pub type Result<T, E = Error> = std::result::Result<T, E>;
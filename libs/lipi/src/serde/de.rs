@@ -0,0 +1,552 @@
+use super::Error;
+use crate::{
+    BitSet, DataType, Decode,
+    decoder::{
+        FieldInfoDecoder, decode_bytes, decode_field_id_and_ty, decode_len, decode_list_len_and_ty,
+        decode_str,
+    },
+    utils,
+};
+use serde::de::{
+    self, DeserializeSeed, Deserializer as _, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor, value::U64Deserializer,
+};
+use serde::forward_to_deserialize_any;
+
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Top level value has no field header, so it's type is inferred from deserialize hint.
+pub(super) struct Deserializer<'a, 'de> {
+    pub reader: &'a mut &'de [u8],
+}
+
+impl<'a, 'de> Deserializer<'a, 'de> {
+    fn value(self, ty: DataType) -> Value<'a, 'de> {
+        Value {
+            reader: self.reader,
+            ty,
+        }
+    }
+}
+
+macro_rules! hint {
+    [$($method:ident ($($arg:ident : $ty:ty),*) = $dt:ident)*] => [$(
+        fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value> {
+            self.value(DataType::$dt).$method($($arg,)* visitor)
+        }
+    )*];
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'_, 'de> {
+    type Error = Error;
+
+    fn deserialize_bool<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
+        Err("bool must be a field, not a top level value".into())
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    hint! {
+        deserialize_any() = Struct
+        deserialize_i8() = I8
+        deserialize_i16() = Int
+        deserialize_i32() = Int
+        deserialize_i64() = Int
+        deserialize_i128() = Int
+        deserialize_u8() = U8
+        deserialize_u16() = UInt
+        deserialize_u32() = UInt
+        deserialize_u64() = UInt
+        deserialize_u128() = UInt
+        deserialize_f32() = F32
+        deserialize_f64() = F64
+        deserialize_char() = UInt
+        deserialize_str() = Str
+        deserialize_string() = Str
+        deserialize_identifier() = Str
+        deserialize_bytes() = List
+        deserialize_byte_buf() = List
+        deserialize_seq() = List
+        deserialize_map() = Table
+        deserialize_unit() = Struct
+        deserialize_unit_struct(name: &'static str) = Struct
+        deserialize_tuple(len: usize) = Struct
+        deserialize_tuple_struct(name: &'static str, len: usize) = Struct
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]) = Struct
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]) = Union
+        deserialize_ignored_any() = Struct
+    }
+}
+
+// ------------------------------------- Value --------------------------------------
+
+/// Field (or variant) identifier is it's position.
+fn field_id(id: u64) -> U64Deserializer<Error> {
+    id.into_deserializer()
+}
+
+/// Value of type `ty`, whose field header is already read.
+struct Value<'a, 'de> {
+    reader: &'a mut &'de [u8],
+    ty: DataType,
+}
+
+impl<'de> Value<'_, 'de> {
+    fn skip(self) -> Result<()> {
+        Ok(FieldInfoDecoder::new(self.reader).skip_field_value(self.ty)?)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Value<'_, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let reader = self.reader;
+        match self.ty {
+            DataType::False => visitor.visit_bool(false),
+            DataType::True => visitor.visit_bool(true),
+            DataType::U8 => visitor.visit_u8(u8::decode(reader)?),
+            DataType::I8 => visitor.visit_i8(i8::decode(reader)?),
+            DataType::F32 => visitor.visit_f32(f32::decode(reader)?),
+            DataType::F64 => visitor.visit_f64(f64::decode(reader)?),
+            DataType::UInt => match u128::decode(reader)? {
+                num if num <= u64::MAX.into() => visitor.visit_u64(num as u64),
+                num => visitor.visit_u128(num),
+            },
+            DataType::Int => match i128::decode(reader)? {
+                num if i64::try_from(num).is_ok() => visitor.visit_i64(num as i64),
+                num => visitor.visit_i128(num),
+            },
+            DataType::Str => visitor.visit_borrowed_str(decode_str(reader)?),
+            DataType::UnknownI | DataType::UnknownII => {
+                visitor.visit_borrowed_bytes(decode_bytes(reader)?)
+            }
+            DataType::StructEnd => Err("unexpected struct end".into()),
+            DataType::Struct => visitor.visit_map(Fields::new(reader, false)),
+            DataType::Union => visitor.visit_map(Fields::new(reader, true)),
+            DataType::List => {
+                let (len, ty) = decode_list_len_and_ty(reader)?;
+                let mut items = Items::new(reader, len, ty)?;
+                let value = visitor.visit_seq(&mut items)?;
+                *reader = items.finish()?;
+                Ok(value)
+            }
+            DataType::Table => {
+                let cols = decode_len(reader)?;
+                if cols != 2 {
+                    return Err(format!("invalid column count: expected `2`, found {cols}").into());
+                }
+                let rows = decode_len(reader)?;
+                let (first_id, first_ty) = decode_field_id_and_ty(reader)?;
+                let first = Items::new(reader, rows, first_ty)?;
+
+                let mut rest = first.finish()?;
+                let (second_id, second_ty) = decode_field_id_and_ty(&mut rest)?;
+                let second = Items::new(&mut rest, rows, second_ty)?;
+
+                let mut table = match (first_id, second_id) {
+                    (0, 1) => Table {
+                        keys: first,
+                        vals: second,
+                    },
+                    (1, 0) => Table {
+                        keys: second,
+                        vals: first,
+                    },
+                    ids => return Err(format!("invalid column ids: {ids:?}").into()),
+                };
+                let value = visitor.visit_map(&mut table)?;
+                *reader = match (first_id, second_id) {
+                    (0, _) => table.vals.finish()?,
+                    _ => table.keys.finish()?,
+                };
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.ty {
+            DataType::UInt => visitor.visit_char(char::decode(self.reader)?),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.ty {
+            DataType::List => {
+                let mut reader = *self.reader;
+                let (len, ty) = decode_list_len_and_ty(&mut reader)?;
+                if ty != DataType::U8 {
+                    return self.deserialize_any(visitor);
+                }
+                let bytes = utils::read_bytes(&mut reader, len)?;
+                *self.reader = reader;
+                visitor.visit_borrowed_bytes(bytes)
+            }
+            DataType::Str => visitor.visit_borrowed_bytes(decode_bytes(self.reader)?),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    /// Present field is always `Some`, missing field is `None`.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.ty {
+            DataType::Struct => {
+                self.skip()?;
+                visitor.visit_unit()
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        match self.ty {
+            DataType::Struct => {
+                let mut tuple = Tuple {
+                    reader: self.reader,
+                    len,
+                    index: 0,
+                    next: None,
+                };
+                let value = visitor.visit_seq(&mut tuple)?;
+                tuple.finish()?;
+                Ok(value)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.ty.expected(DataType::Union)?;
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.skip()?;
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 str string
+        seq map struct identifier
+    }
+}
+
+impl<'a, 'de> EnumAccess<'de> for Value<'a, 'de> {
+    type Error = Error;
+    type Variant = Value<'a, 'de>;
+
+    fn variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<(T::Value, Self::Variant)> {
+        let (id, ty) = decode_field_id_and_ty(self.reader)?;
+        let variant = seed.deserialize(field_id(id))?;
+        Ok((
+            variant,
+            Value {
+                reader: self.reader,
+                ty,
+            },
+        ))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Value<'_, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        self.skip()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_any(visitor)
+    }
+}
+
+// ------------------------------------- Struct -------------------------------------
+
+/// Struct fields (or a single union field) as map, keyed by field id.
+struct Fields<'a, 'de> {
+    reader: &'a mut &'de [u8],
+    ty: DataType,
+    union: bool,
+    done: bool,
+}
+
+impl<'a, 'de> Fields<'a, 'de> {
+    fn new(reader: &'a mut &'de [u8], union: bool) -> Self {
+        Self {
+            reader,
+            ty: DataType::StructEnd,
+            union,
+            done: false,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for Fields<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if self.done {
+            return Ok(None);
+        }
+        let (id, ty) = decode_field_id_and_ty(self.reader)?;
+        if ty == DataType::StructEnd {
+            if self.union || id != 0 {
+                return Err(format!("unexpected struct end, with field id ({id})").into());
+            }
+            self.done = true;
+            return Ok(None);
+        }
+        self.ty = ty;
+        self.done = self.union;
+        seed.deserialize(field_id(id)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(Value {
+            reader: self.reader,
+            ty: self.ty,
+        })
+    }
+}
+
+/// Struct fields as sequence, missing fields are `None`.
+struct Tuple<'a, 'de> {
+    reader: &'a mut &'de [u8],
+    len: usize,
+    index: u64,
+    /// Field header that is read, but not yet consumed.
+    next: Option<(u64, DataType)>,
+}
+
+impl<'de> Tuple<'_, 'de> {
+    /// Returns next field header, `None` at struct end.
+    fn peek(&mut self) -> Result<Option<(u64, DataType)>> {
+        if self.next.is_none() {
+            self.next = FieldInfoDecoder::new(self.reader).next_field_id_and_ty()?;
+        }
+        Ok(self.next)
+    }
+
+    /// Skips remaining fields and struct end.
+    fn finish(mut self) -> Result<()> {
+        while let Some((id, ty)) = self.peek()? {
+            FieldInfoDecoder::new(self.reader).unknown_field(id, ty)?;
+            self.next = None;
+        }
+        Ok(())
+    }
+}
+
+impl<'de> SeqAccess<'de> for Tuple<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if self.index == self.len as u64 {
+            return Ok(None);
+        }
+        let index = self.index;
+        self.index += 1;
+
+        // Fields are skipped, if they are out of order.
+        while let Some((id, ty)) = self.peek()? {
+            if id >= index {
+                break;
+            }
+            FieldInfoDecoder::new(self.reader).unknown_field(id, ty)?;
+            self.next = None;
+        }
+        match self.next {
+            Some((id, ty)) if id == index => {
+                self.next = None;
+                seed.deserialize(Value {
+                    reader: self.reader,
+                    ty,
+                })
+                .map(Some)
+            }
+            _ => seed.deserialize(Missing(index)).map(Some),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.index as usize)
+    }
+}
+
+/// Missing tuple field, which is only valid for `Option`.
+struct Missing(u64);
+
+impl<'de> de::Deserializer<'de> for Missing {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
+        Err(format!("missing required field `{}`", self.0).into())
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_none()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+// ------------------------------------- List ---------------------------------------
+
+/// List items (or a table column), bools are packed.
+#[derive(Clone, Copy)]
+struct Items<'de> {
+    reader: &'de [u8],
+    ty: DataType,
+    len: usize,
+    index: usize,
+    bits: &'de [u8],
+}
+
+impl<'de> Items<'de> {
+    fn new(reader: &mut &'de [u8], len: usize, ty: DataType) -> Result<Self> {
+        let bits = match ty {
+            DataType::True => utils::read_bytes(reader, utils::bool_packed_len(len))?,
+            DataType::False | DataType::StructEnd if len > 0 => {
+                return Err(format!("invalid list item type: {ty:?}").into());
+            }
+            _ => &[],
+        };
+        Ok(Self {
+            reader: *reader,
+            ty,
+            len,
+            index: 0,
+            bits,
+        })
+    }
+
+    fn next(&mut self) -> Option<Value<'_, 'de>> {
+        if self.index == self.len {
+            return None;
+        }
+        let ty = match self.ty {
+            DataType::True => DataType::from(self.bits.has(self.index)),
+            ty => ty,
+        };
+        self.index += 1;
+        Some(Value {
+            reader: &mut self.reader,
+            ty,
+        })
+    }
+
+    /// Skips remaining items, and returns rest of the input.
+    fn finish(mut self) -> Result<&'de [u8]> {
+        while let Some(value) = self.next() {
+            value.skip()?;
+        }
+        Ok(self.reader)
+    }
+}
+
+impl<'de> SeqAccess<'de> for Items<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self.next().map(|value| seed.deserialize(value)).transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.index)
+    }
+}
+
+// ------------------------------------- Table --------------------------------------
+
+struct Table<'de> {
+    keys: Items<'de>,
+    vals: Items<'de>,
+}
+
+impl<'de> MapAccess<'de> for Table<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        self.keys
+            .next()
+            .map(|key| seed.deserialize(key))
+            .transpose()
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        match self.vals.next() {
+            Some(val) => seed.deserialize(val),
+            None => Err("table has no more values".into()),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.keys.len - self.keys.index)
+    }
+}
@@ -0,0 +1,80 @@
+//! [serde] bridge, enabled by the `serde` cargo feature.
+//!
+//! Types that implement [Serialize] and [Deserialize] are mapped to lipi:
+//!
+//! - Struct fields and tuple elements are keyed by their position.
+//! - Enums are externally tagged [Union](crate::DataType::Union), keyed by variant index.
+//! - Sequences are lists, and maps are tables.
+//! - Newtype structs are transparent. Fixed size arrays are encoded as tuples.
+//!
+//! So the output is compatible with derived [Encode](crate::Encode) and [Decode](crate::Decode),
+//! if `#[key = N]` of each field (or variant) is its position.
+mod de;
+mod ser;
+
+use crate::{DataType, errors};
+use ::serde::{Deserialize, Serialize};
+use std::{fmt, io};
+
+/// Serialize `value` to lipi bytes.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    match value.serialize(ser::Serializer { out: &mut out }) {
+        Ok(Some(DataType::False | DataType::True)) => Err(io::Error::other(
+            "bool must be a field, not a top level value",
+        )),
+        Ok(Some(_)) => Ok(out),
+        Ok(None) => Err(io::Error::other("`None` is not a top level value")),
+        Err(Error(err)) => Err(io::Error::other(err)),
+    }
+}
+
+/// Deserialize a value of type `T` from lipi bytes.
+pub fn from_slice<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> crate::Result<T> {
+    let mut reader = bytes;
+    T::deserialize(de::Deserializer {
+        reader: &mut reader,
+    })
+    .map_err(|Error(err)| err)
+}
+
+#[derive(Debug)]
+struct Error(crate::Error);
+
+impl std::error::Error for Error {}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl ::serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string().into())
+    }
+}
+
+impl ::serde::de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string().into())
+    }
+}
+
+macro_rules! from {
+    [$($ty:ty),*] => [$(
+        impl From<$ty> for Error {
+            fn from(err: $ty) -> Self {
+                Error(err.into())
+            }
+        }
+    )*];
+}
+
+from! {
+    crate::Error,
+    &str,
+    String,
+    io::Error,
+    errors::UnexpectedEof,
+    errors::InvalidType
+}
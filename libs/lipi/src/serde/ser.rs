@@ -0,0 +1,424 @@
+use super::Error;
+use crate::{
+    DataType, Encode, bit_set,
+    encoder::{
+        encode_bytes, encode_field_id_and_ty, encode_int, encode_len, encode_list_len_and_ty,
+        encode_uint,
+    },
+};
+use serde::ser::{self, Serialize};
+
+type Result<T = Option<DataType>, E = Error> = std::result::Result<T, E>;
+
+/// Writes a value without field header, and returns its type.
+///
+/// `None` is written as nothing, so it's type is `None` too.
+/// Bools are also written as nothing, as they are part of field header.
+pub(super) struct Serializer<'a> {
+    pub out: &'a mut Vec<u8>,
+}
+
+/// Writes a field with `id`, and returns `false` if it's omitted (`None`).
+fn field<T: Serialize + ?Sized>(out: &mut Vec<u8>, id: u32, value: &T) -> Result<bool> {
+    let start = out.len();
+    // `DataType::False` is zero, actual type is patched after value is written.
+    encode_field_id_and_ty(out, id, DataType::False)?;
+    match value.serialize(Serializer { out })? {
+        Some(ty) => {
+            out[start] |= ty.code();
+            Ok(true)
+        }
+        None => {
+            out.truncate(start);
+            Ok(false)
+        }
+    }
+}
+
+/// Inserts list header at `start`, as list length isn't always known upfront.
+fn insert_list_header(out: &mut Vec<u8>, start: usize, items: &Items) -> Result<()> {
+    let mut header = Vec::new();
+    encode_list_len_and_ty(&mut header, items.len, items.ty())?;
+    out.splice(start..start, header);
+    Ok(())
+}
+
+/// Tracks type of list items, bools are collected to be packed at the end.
+#[derive(Default)]
+struct Items {
+    ty: Option<DataType>,
+    len: usize,
+    bools: Vec<bool>,
+}
+
+impl Items {
+    fn push(&mut self, ty: Option<DataType>) -> Result<()> {
+        let ty = match ty.ok_or("`None` is not supported as list item")? {
+            ty @ (DataType::False | DataType::True) => {
+                self.bools.push(ty == DataType::True);
+                DataType::True
+            }
+            ty => ty,
+        };
+        match self.ty {
+            Some(expected) if expected != ty => {
+                return Err(format!(
+                    "list item type mismatch: expected {expected:?}, found {ty:?}"
+                )
+                .into());
+            }
+            _ => self.ty = Some(ty),
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Item type of an empty list is unknown, so decoders don't check it.
+    fn ty(&self) -> DataType {
+        self.ty.unwrap_or(DataType::U8)
+    }
+
+    fn write_bools(&self, out: &mut Vec<u8>) {
+        if self.ty == Some(DataType::True) {
+            out.extend_from_slice(&bit_set::bitvec_from(&self.bools));
+        }
+    }
+}
+
+impl<'a> ser::Serializer for Serializer<'a> {
+    type Ok = Option<DataType>;
+    type Error = Error;
+
+    type SerializeSeq = Seq<'a>;
+    type SerializeTuple = Struct<'a>;
+    type SerializeTupleStruct = Struct<'a>;
+    type SerializeTupleVariant = Struct<'a>;
+    type SerializeMap = Table<'a>;
+    type SerializeStruct = Struct<'a>;
+    type SerializeStructVariant = Struct<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result {
+        Ok(Some(DataType::from(v)))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result {
+        v.encode(self.out)?;
+        Ok(Some(DataType::I8))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result {
+        encode_int(self.out, v)?;
+        Ok(Some(DataType::Int))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result {
+        v.encode(self.out)?;
+        Ok(Some(DataType::Int))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result {
+        v.encode(self.out)?;
+        Ok(Some(DataType::U8))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result {
+        encode_uint(self.out, v)?;
+        Ok(Some(DataType::UInt))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result {
+        v.encode(self.out)?;
+        Ok(Some(DataType::UInt))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result {
+        v.encode(self.out)?;
+        Ok(Some(DataType::F32))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result {
+        v.encode(self.out)?;
+        Ok(Some(DataType::F64))
+    }
+
+    fn serialize_char(self, v: char) -> Result {
+        self.serialize_u64(u32::from(v).into())
+    }
+
+    fn serialize_str(self, v: &str) -> Result {
+        encode_bytes(self.out, v.as_bytes())?;
+        Ok(Some(DataType::Str))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result {
+        v.encode(self.out)?;
+        Ok(Some(DataType::List))
+    }
+
+    fn serialize_none(self) -> Result {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result {
+        value.serialize(self)
+    }
+
+    /// Same as empty tuple.
+    fn serialize_unit(self) -> Result {
+        self.out.push(DataType::StructEnd.code());
+        Ok(Some(DataType::Struct))
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, index: u32, _: &'static str) -> Result {
+        encode_field_id_and_ty(self.out, index, DataType::False)?;
+        Ok(Some(DataType::Union))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> Result {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result {
+        if !field(self.out, index, value)? {
+            return Err("`None` is not supported as variant value".into());
+        }
+        Ok(Some(DataType::Union))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(Seq {
+            start: self.out.len(),
+            out: self.out,
+            items: Items::default(),
+        })
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple> {
+        Ok(Struct::new(self.out, DataType::Struct))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Ok(Struct::new(self.out, DataType::Struct))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        encode_field_id_and_ty(self.out, index, DataType::Struct)?;
+        Ok(Struct::new(self.out, DataType::Union))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(Table {
+            start: self.out.len(),
+            out: self.out,
+            keys: Items::default(),
+            vals: Items::default(),
+            vals_buf: Vec::new(),
+        })
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct> {
+        Ok(Struct::new(self.out, DataType::Struct))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        encode_field_id_and_ty(self.out, index, DataType::Struct)?;
+        Ok(Struct::new(self.out, DataType::Union))
+    }
+}
+
+// ------------------------------------- List ---------------------------------------
+
+pub(super) struct Seq<'a> {
+    out: &'a mut Vec<u8>,
+    start: usize,
+    items: Items,
+}
+
+impl ser::SerializeSeq for Seq<'_> {
+    type Ok = Option<DataType>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let ty = value.serialize(Serializer { out: self.out })?;
+        self.items.push(ty)
+    }
+
+    fn end(self) -> Result {
+        self.items.write_bools(self.out);
+        insert_list_header(self.out, self.start, &self.items)?;
+        Ok(Some(DataType::List))
+    }
+}
+
+// ------------------------------------- Struct -------------------------------------
+
+/// Fields keyed by their position, `ty` is the type of whole value.
+pub(super) struct Struct<'a> {
+    out: &'a mut Vec<u8>,
+    ty: DataType,
+    index: u32,
+}
+
+impl<'a> Struct<'a> {
+    fn new(out: &'a mut Vec<u8>, ty: DataType) -> Self {
+        Self { out, ty, index: 0 }
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        field(self.out, self.index, value)?;
+        self.index += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result {
+        self.out.push(DataType::StructEnd.code());
+        Ok(Some(self.ty))
+    }
+}
+
+macro_rules! serialize_struct {
+    [$($trait:ident :: $method:ident $(($key:ty))?)*] => [$(
+        impl ser::$trait for Struct<'_> {
+            type Ok = Option<DataType>;
+            type Error = Error;
+
+            fn $method<T: Serialize + ?Sized>(&mut self, $(_: $key,)? value: &T) -> Result<()> {
+                self.field(value)
+            }
+
+            fn end(self) -> Result {
+                Struct::end(self)
+            }
+        }
+    )*];
+}
+
+serialize_struct! {
+    SerializeTuple::serialize_element
+    SerializeTupleStruct::serialize_field
+    SerializeTupleVariant::serialize_field
+}
+
+impl ser::SerializeStruct for Struct<'_> {
+    type Ok = Option<DataType>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &'static str, value: &T) -> Result<()> {
+        self.field(value)
+    }
+
+    fn skip_field(&mut self, _: &'static str) -> Result<()> {
+        self.index += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result {
+        Struct::end(self)
+    }
+}
+
+impl ser::SerializeStructVariant for Struct<'_> {
+    type Ok = Option<DataType>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &'static str, value: &T) -> Result<()> {
+        self.field(value)
+    }
+
+    fn skip_field(&mut self, _: &'static str) -> Result<()> {
+        self.index += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result {
+        Struct::end(self)
+    }
+}
+
+// ------------------------------------- Table --------------------------------------
+
+/// Keys are written in place, values are buffered to be written as the second column.
+pub(super) struct Table<'a> {
+    out: &'a mut Vec<u8>,
+    start: usize,
+    keys: Items,
+    vals: Items,
+    vals_buf: Vec<u8>,
+}
+
+impl ser::SerializeMap for Table<'_> {
+    type Ok = Option<DataType>;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        let ty = key.serialize(Serializer { out: self.out })?;
+        self.keys.push(ty)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let ty = value.serialize(Serializer {
+            out: &mut self.vals_buf,
+        })?;
+        self.vals.push(ty)
+    }
+
+    fn end(self) -> Result {
+        if self.keys.len != self.vals.len {
+            return Err("table keys and values must have the same length".into());
+        }
+        self.keys.write_bools(self.out);
+
+        let mut header = Vec::new();
+        encode_len(&mut header, 2)?; // Column count
+        encode_len(&mut header, self.keys.len)?; // row count
+        encode_field_id_and_ty(&mut header, 0, self.keys.ty())?;
+        self.out.splice(self.start..self.start, header);
+
+        encode_field_id_and_ty(self.out, 1, self.vals.ty())?;
+        self.out.extend_from_slice(&self.vals_buf);
+        self.vals.write_bools(self.out);
+        Ok(Some(DataType::Table))
+    }
+}
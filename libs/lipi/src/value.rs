@@ -0,0 +1,293 @@
+use crate::{
+    DataType, Decode, Encode, Result, assert_or_err, bit_set,
+    decoder::{decode_bytes, decode_field_id_and_ty, decode_len, decode_list_len_and_ty, limits},
+    encoder::{
+        encode_bytes, encode_field_id_and_ty, encode_int128, encode_len, encode_list_len_and_ty,
+        encode_uint128,
+    },
+    utils,
+};
+use std::io::{self, Write};
+
+/// Self-describing lipi value, that can be decoded and encoded without schema.
+///
+/// Top level [Value] is a [Struct](Value::Struct), same as a message of a derived type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    U8(u8),
+    I8(i8),
+    F32(f32),
+    F64(f64),
+    UInt(u128),
+    Int(i128),
+    Str(String),
+    /// Fields, in encoded order.
+    Struct(Vec<(u32, Value)>),
+    Union(u32, Box<Value>),
+    /// Items of the same type, bools are packed as [DataType::True].
+    List(DataType, Vec<Value>),
+    /// Columns of the same length, as `(id, type, values)`.
+    Table(Vec<(u32, DataType, Vec<Value>)>),
+    /// Value of an unknown type, kept as raw bytes.
+    Unknown(DataType, Vec<u8>),
+}
+
+impl Value {
+    pub fn ty(&self) -> DataType {
+        match self {
+            Value::Bool(val) => DataType::from(*val),
+            Value::U8(_) => DataType::U8,
+            Value::I8(_) => DataType::I8,
+            Value::F32(_) => DataType::F32,
+            Value::F64(_) => DataType::F64,
+            Value::UInt(_) => DataType::UInt,
+            Value::Int(_) => DataType::Int,
+            Value::Str(_) => DataType::Str,
+            Value::Struct(_) => DataType::Struct,
+            Value::Union(..) => DataType::Union,
+            Value::List(..) => DataType::List,
+            Value::Table(_) => DataType::Table,
+            Value::Unknown(ty, _) => *ty,
+        }
+    }
+
+    /// Returns the field with `id`, if this is a struct.
+    pub fn get(&self, id: u32) -> Option<&Value> {
+        match self {
+            Value::Struct(fields) => fields.iter().find(|(key, _)| *key == id).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Decodes a value of type `ty`, whose field header is already read.
+    pub fn decode_value(reader: &mut &[u8], ty: DataType) -> Result<Value> {
+        let _depth = match ty {
+            DataType::Struct | DataType::Union | DataType::List | DataType::Table => {
+                Some(limits::Depth::enter()?)
+            }
+            _ => None,
+        };
+        Ok(match ty {
+            DataType::False => Value::Bool(false),
+            DataType::True => Value::Bool(true),
+            DataType::U8 => Value::U8(u8::decode(reader)?),
+            DataType::I8 => Value::I8(i8::decode(reader)?),
+            DataType::F32 => Value::F32(f32::decode(reader)?),
+            DataType::F64 => Value::F64(f64::decode(reader)?),
+            DataType::UInt => Value::UInt(u128::decode(reader)?),
+            DataType::Int => Value::Int(i128::decode(reader)?),
+            DataType::Str => Value::Str(String::decode(reader)?),
+            DataType::UnknownI | DataType::UnknownII => {
+                Value::Unknown(ty, decode_bytes(reader)?.into())
+            }
+            DataType::StructEnd => return Err("unexpected struct end".into()),
+            DataType::Struct => Value::Struct(decode_fields(reader)?),
+            DataType::Union => {
+                let (id, ty) = decode_field_id(reader)?;
+                Value::Union(id, Box::new(Value::decode_value(reader, ty)?))
+            }
+            DataType::List => {
+                let (len, ty) = decode_list_len_and_ty(reader)?;
+                Value::List(ty, decode_list_values(reader, len, ty)?)
+            }
+            DataType::Table => {
+                let cols = decode_len(reader)?;
+                let rows = decode_len(reader)?;
+                limits::check_len(cols)?;
+                limits::alloc::<(u32, DataType, Vec<Value>)>(cols)?;
+                let columns = utils::try_collect(cols, || {
+                    let (id, ty) = decode_field_id(reader)?;
+                    Ok::<_, crate::Error>((id, ty, decode_list_values(reader, rows, ty)?))
+                })?;
+                Value::Table(columns)
+            }
+        })
+    }
+
+    /// Encodes the value, without field header.
+    pub fn encode_value(&self, writer: &mut (impl Write + ?Sized)) -> io::Result<()> {
+        match self {
+            Value::Bool(_) => Ok(()),
+            Value::U8(val) => val.encode(writer),
+            Value::I8(val) => val.encode(writer),
+            Value::F32(val) => val.encode(writer),
+            Value::F64(val) => val.encode(writer),
+            Value::UInt(val) => encode_uint128(writer, *val),
+            Value::Int(val) => encode_int128(writer, *val),
+            Value::Str(val) => encode_bytes(writer, val.as_bytes()),
+            Value::Unknown(_, bytes) => encode_bytes(writer, bytes),
+            Value::Struct(fields) => {
+                for (id, val) in fields {
+                    encode_field_id_and_ty(writer, *id, val.ty())?;
+                    val.encode_value(writer)?;
+                }
+                encode_field_id_and_ty(writer, 0, DataType::StructEnd)
+            }
+            Value::Union(id, val) => {
+                encode_field_id_and_ty(writer, *id, val.ty())?;
+                val.encode_value(writer)
+            }
+            Value::List(ty, items) => {
+                encode_list_len_and_ty(writer, items.len(), *ty)?;
+                encode_list_values(writer, *ty, items)
+            }
+            Value::Table(columns) => {
+                let rows = columns.first().map_or(0, |(_, _, values)| values.len());
+                encode_len(writer, columns.len())?;
+                encode_len(writer, rows)?;
+                for (id, ty, values) in columns {
+                    if values.len() != rows {
+                        return Err(io::Error::other("table columns must have the same length"));
+                    }
+                    encode_field_id_and_ty(writer, *id, *ty)?;
+                    encode_list_values(writer, *ty, values)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn decode_field_id(reader: &mut &[u8]) -> Result<(u32, DataType)> {
+    let (id, ty) = decode_field_id_and_ty(reader)?;
+    Ok((u32::try_from(id)?, ty))
+}
+
+fn decode_fields(reader: &mut &[u8]) -> Result<Vec<(u32, Value)>> {
+    let mut fields = Vec::new();
+    loop {
+        let (id, ty) = decode_field_id(reader)?;
+        if ty == DataType::StructEnd {
+            assert_or_err!(
+                id == 0,
+                format!("invalid struct end field id ({id}), expected `0`")
+            );
+            return Ok(fields);
+        }
+        fields.push((id, Value::decode_value(reader, ty)?));
+    }
+}
+
+fn decode_list_values(reader: &mut &[u8], len: usize, ty: DataType) -> Result<Vec<Value>> {
+    limits::check_len(len)?;
+    limits::alloc::<Value>(len)?;
+    match ty {
+        DataType::False => Err("unexpected bool packed in list".into()),
+        DataType::True => {
+            let bitvec = utils::read_bytes(reader, utils::bool_packed_len(len))?;
+            let bools = bit_set::bitvec_to_bools(bitvec, len);
+            Ok(bools.into_iter().map(Value::Bool).collect())
+        }
+        DataType::StructEnd => Err("unexpected list ty struct end".into()),
+        _ => utils::try_collect(len, || Value::decode_value(reader, ty)),
+    }
+}
+
+fn encode_list_values(
+    writer: &mut (impl Write + ?Sized),
+    ty: DataType,
+    items: &[Value],
+) -> io::Result<()> {
+    if let DataType::True = ty {
+        let mut bools = Vec::with_capacity(items.len());
+        for item in items {
+            match item {
+                Value::Bool(val) => bools.push(*val),
+                _ => return Err(mismatched(ty, item)),
+            }
+        }
+        return writer.write_all(&bit_set::bitvec_from(&bools));
+    }
+    for item in items {
+        if item.ty() != ty {
+            return Err(mismatched(ty, item));
+        }
+        item.encode_value(writer)?;
+    }
+    Ok(())
+}
+
+fn mismatched(ty: DataType, item: &Value) -> io::Error {
+    io::Error::other(format!(
+        "list item type mismatch: expected {ty:?}, found {:?}",
+        item.ty()
+    ))
+}
+
+impl Encode for Value {
+    const TY: DataType = DataType::Struct;
+
+    fn encode(&self, writer: &mut (impl Write + ?Sized)) -> io::Result<()> {
+        match self {
+            Value::Struct(_) => self.encode_value(writer),
+            _ => Err(io::Error::other(format!(
+                "expected struct value, found {:?}",
+                self.ty()
+            ))),
+        }
+    }
+}
+
+impl Decode<'_> for Value {
+    const TY: DataType = DataType::Struct;
+
+    fn decode(reader: &mut &[u8]) -> Result<Self> {
+        decode_fields(reader).map(Value::Struct)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_round_trip() {
+        let map = BTreeMap::from([(1u32, "one"), (2, "two")]);
+        let data = (
+            true,
+            -42i32,
+            "Hello",
+            vec![true, false, true],
+            map,
+            1.5f64,
+            i128::MIN,
+        );
+        let bytes = data.to_bytes().unwrap();
+
+        let value = Value::decode(&mut &*bytes).unwrap();
+        assert_eq!(value.get(0), Some(&Value::Bool(true)));
+        assert_eq!(value.get(1), Some(&Value::Int(-42)));
+        assert_eq!(value.get(6), Some(&Value::Int(i128::MIN)));
+        assert_eq!(
+            value.get(3),
+            Some(&Value::List(
+                DataType::True,
+                vec![Value::Bool(true), Value::Bool(false), Value::Bool(true)]
+            ))
+        );
+        assert_eq!(value.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_modify() {
+        let bytes = (1u8, "name").to_bytes().unwrap();
+        let Value::Struct(mut fields) = Value::decode(&mut &*bytes).unwrap() else {
+            unreachable!()
+        };
+        fields[1].1 = Value::Str("lipi".into());
+        fields.push((2, Value::Union(7, Box::new(Value::UInt(9)))));
+
+        let bytes = Value::Struct(fields).to_bytes().unwrap();
+        let (num, name) = <(u8, String)>::decode(&mut &*bytes).unwrap();
+        assert_eq!((num, name.as_str()), (1, "lipi"));
+    }
+
+    #[test]
+    fn test_invalid_list() {
+        let list = Value::List(DataType::UInt, vec![Value::UInt(1), Value::Int(2)]);
+        assert!(Value::Struct(vec![(0, list)]).to_bytes().is_err());
+        assert!(Value::UInt(1).to_bytes().is_err());
+    }
+}
@@ -25,6 +25,29 @@ pub trait LEB128 {
         }
         self.write_byte(num as u8);
     }
+
+    #[inline]
+    fn write_u128(&mut self, mut num: u128) {
+        while num > 0b_111_1111 {
+            self.write_byte((num as u8) | 0b_1000_0000);
+            num >>= 7;
+        }
+        self.write_byte(num as u8);
+    }
+}
+
+/// Number of bytes, written by [LEB128::write_u64].
+#[inline]
+pub const fn len_u64(num: u64) -> usize {
+    // Bitwise-or'ing by 1 allows the `num = zero` case to work without affecting other cases.
+    let significant_bits = 64 - (num | 1).leading_zeros();
+    (significant_bits + 6) as usize / 7
+}
+
+#[inline]
+pub const fn len_u128(num: u128) -> usize {
+    let significant_bits = 128 - (num | 1).leading_zeros();
+    (significant_bits + 6) as usize / 7
 }
 
 #[inline]
@@ -48,6 +71,26 @@ pub fn read_u64(reader: &mut &[u8]) -> Result<u64> {
     }
 }
 
+pub fn read_u128(reader: &mut &[u8]) -> Result<u128> {
+    let mut result = 0;
+    let mut shift = 0;
+
+    loop {
+        let byte = utils::read_byte(reader)?;
+
+        if shift == 126 && byte >= 4 {
+            return Err(errors::VarIntError.into());
+        }
+
+        if (byte & 0b_1000_0000) == 0 {
+            break Ok(result | ((byte as u128) << shift));
+        }
+
+        result |= ((byte & 0b_111_1111) as u128) << shift;
+        shift += 7;
+    }
+}
+
 #[derive(Debug)]
 pub struct Leb128Buf<const N: usize> {
     buf: [MaybeUninit<u8>; N],
@@ -113,12 +156,6 @@ mod tests {
         assert_eq!(buf.as_bytes(), &[0, 1, 2, 3]);
     }
 
-    fn encoded_varint64_len(value: u64) -> usize {
-        // Bitwise-or'ing by 1 allows the `value = zero` case to work without affecting other cases.
-        let significant_bits = 64 - (value | 1).leading_zeros();
-        (significant_bits + 6) as usize / 7
-    }
-
     fn decode_varint64(mut buf: &[u8]) -> Result<u64> {
         read_u64(&mut buf)
     }
@@ -134,7 +171,8 @@ mod tests {
         fn check(num: u64) {
             let encoded = encode_varint64(num);
 
-            assert_eq!(encoded.len(), encoded_varint64_len(num));
+            assert_eq!(encoded.len(), len_u64(num));
+            assert_eq!(encoded.len(), len_u128(num.into()));
             assert_eq!(num, decode_varint64(&encoded).unwrap());
         }
 
@@ -171,7 +209,8 @@ mod tests {
         fn check(num: u64, encoded: &[u8]) {
             assert_eq!(encode_varint64(num), encoded);
 
-            assert_eq!(encoded.len(), encoded_varint64_len(num));
+            assert_eq!(encoded.len(), len_u64(num));
+            assert_eq!(encoded.len(), len_u128(num.into()));
             assert_eq!(num, decode_varint64(encoded).unwrap());
         }
 
@@ -235,4 +274,32 @@ mod tests {
             decode_varint64(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]).is_err()
         );
     }
+
+    #[test]
+    fn test_varint128() {
+        fn check(num: u128) {
+            let mut buf = Vec::new();
+            buf.write_u128(num);
+            assert_eq!(num, read_u128(&mut &buf[..]).unwrap());
+
+            if let Ok(num) = u64::try_from(num) {
+                assert_eq!(buf, encode_varint64(num));
+            } else {
+                assert!(decode_varint64(&buf).is_err());
+            }
+        }
+
+        for n in (0..).map_while(|exp| 2u128.checked_pow(exp)) {
+            check(n);
+            check(n - 1);
+        }
+        check(u128::MAX);
+
+        let mut max = vec![0xff; 18];
+        max.push(0x03);
+        assert_eq!(read_u128(&mut &max[..]).unwrap(), u128::MAX);
+
+        max[18] = 0x04;
+        assert!(read_u128(&mut &max[..]).is_err());
+    }
 }
@@ -6,6 +6,14 @@ pub fn zigzag_decode(num: u64) -> i64 {
     ((num >> 1) as i64) ^ -((num & 1) as i64)
 }
 
+pub fn zigzag_encode128(num: i128) -> u128 {
+    ((num << 1) ^ (num >> 127)) as u128
+}
+
+pub fn zigzag_decode128(num: u128) -> i128 {
+    ((num >> 1) as i128) ^ -((num & 1) as i128)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -29,4 +37,15 @@ mod tests {
         check_zig_zag(i32::MIN.into(), u32::MAX.into());
         check_zig_zag(i32::MAX.into(), (u32::MAX - 1).into());
     }
+
+    #[test]
+    fn test_zig_zag128_encoding() {
+        for num in [0, -1, 1, i64::MIN, i64::MAX] {
+            assert_eq!(zigzag_encode128(num.into()), zigzag_encode(num).into());
+        }
+        assert_eq!(zigzag_encode128(i128::MIN), u128::MAX);
+        assert_eq!(zigzag_encode128(i128::MAX), u128::MAX - 1);
+        assert_eq!(zigzag_decode128(u128::MAX), i128::MIN);
+        assert_eq!(zigzag_decode128(u128::MAX - 1), i128::MAX);
+    }
 }
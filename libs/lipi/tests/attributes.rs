@@ -0,0 +1,116 @@
+use lipi::{DataType, Decode, Encode, Value};
+use std::net::Ipv4Addr;
+
+mod ipv4 {
+    use std::net::Ipv4Addr;
+
+    pub fn encode(addr: &Ipv4Addr) -> u32 {
+        u32::from(*addr)
+    }
+
+    pub fn decode(bits: u32) -> Result<Ipv4Addr, std::convert::Infallible> {
+        Ok(Ipv4Addr::from(bits))
+    }
+}
+
+fn port(port: u16) -> Result<u16, String> {
+    if port == 0 {
+        return Err("port can't be zero".into());
+    }
+    Ok(port)
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Meta {
+    #[key = 10]
+    #[rename = "createdAt"]
+    created_at: u64,
+    #[key = 11]
+    tags: Vec<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Server<'a> {
+    #[key = 0]
+    name: &'a str,
+    #[key = 1]
+    #[with = "ipv4"]
+    addr: Ipv4Addr,
+    #[key = 2]
+    #[decode_with = "port"]
+    port: u16,
+    #[flatten]
+    meta: Meta,
+    #[skip]
+    connections: usize,
+}
+
+fn server() -> Server<'static> {
+    Server {
+        name: "local",
+        addr: Ipv4Addr::LOCALHOST,
+        port: 8080,
+        meta: Meta {
+            created_at: 42,
+            tags: vec!["dev".into()],
+        },
+        connections: 3,
+    }
+}
+
+#[test]
+fn round_trip() {
+    let bytes = server().to_bytes().unwrap();
+    assert_eq!(
+        Server::decode(&mut &*bytes).unwrap(),
+        Server {
+            connections: 0,
+            ..server()
+        }
+    );
+}
+
+#[test]
+fn flatten() {
+    let bytes = server().to_bytes().unwrap();
+    assert_eq!(
+        Value::decode_value(&mut &*bytes, DataType::Struct).unwrap(),
+        Value::Struct(vec![
+            (0, Value::Str("local".into())),
+            (1, Value::UInt(u32::from(Ipv4Addr::LOCALHOST).into())),
+            (2, Value::UInt(8080)),
+            (10, Value::UInt(42)),
+            (
+                11,
+                Value::List(DataType::Str, vec![Value::Str("dev".into())])
+            ),
+        ])
+    );
+}
+
+#[test]
+fn decode_with_error() {
+    let bytes = Server {
+        port: 0,
+        ..server()
+    }
+    .to_bytes()
+    .unwrap();
+    let err = Server::decode(&mut &*bytes).unwrap_err();
+    assert!(err.to_string().contains("port can't be zero"), "{err}");
+}
+
+#[test]
+fn rename() {
+    let bytes = server().to_bytes().unwrap();
+    let mut value = Value::decode_value(&mut &*bytes, DataType::Struct).unwrap();
+    let Value::Struct(fields) = &mut value else {
+        unreachable!()
+    };
+    fields.retain(|(key, _)| *key != 10);
+
+    let mut bytes = vec![];
+    value.encode_value(&mut bytes).unwrap();
+    let err = Server::decode(&mut &*bytes).unwrap_err();
+    assert!(err.to_string().contains("createdAt"), "{err}");
+}
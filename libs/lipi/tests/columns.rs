@@ -0,0 +1,133 @@
+use lipi::{Columns, DataType, Decode, Encode, Value, decoder::DecodeOptions};
+
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+struct Location {
+    #[key = 10]
+    city: String,
+    #[key = 11]
+    zip: Option<u32>,
+}
+
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+struct Row<'a> {
+    #[key = 0]
+    id: u64,
+    #[key = 1]
+    name: &'a str,
+    #[key = 2]
+    active: bool,
+    #[key = 3]
+    score: Option<f32>,
+    #[flatten]
+    location: Location,
+    #[skip]
+    cached: bool,
+}
+
+#[derive(Decode, Debug, PartialEq)]
+struct Summary {
+    #[key = 0]
+    id: u64,
+    #[key = 2]
+    active: bool,
+}
+
+fn rows() -> Vec<Row<'static>> {
+    (0..100)
+        .map(|id| Row {
+            id,
+            name: "row",
+            active: id % 3 == 0,
+            score: (id % 2 == 0).then_some(id as f32 / 2.0),
+            location: Location {
+                city: "Dhaka".into(),
+                zip: (id % 5 == 0).then_some(1000 + id as u32),
+            },
+            cached: false,
+        })
+        .collect()
+}
+
+#[test]
+fn roundtrip() {
+    let rows = Columns(rows());
+    let bytes = rows.to_bytes().unwrap();
+    assert_eq!(Columns::<Row>::decode(&mut &*bytes).unwrap(), rows);
+
+    // Field headers aren't repeated.
+    assert!(bytes.len() < rows.0.to_bytes().unwrap().len());
+
+    let empty = Columns::<Row>(vec![]).to_bytes().unwrap();
+    assert!(Columns::<Row>::decode(&mut &*empty).unwrap().is_empty());
+}
+
+#[test]
+fn table_layout() {
+    let rows = Columns(rows()[..2].to_vec());
+    let bytes = rows.to_bytes().unwrap();
+
+    let Value::Table(columns) = Value::decode_value(&mut &*bytes, DataType::Table).unwrap() else {
+        panic!("expected a table");
+    };
+    let layout: Vec<_> = columns
+        .iter()
+        .map(|(id, ty, values)| (*id, *ty, values.len()))
+        .collect();
+    assert_eq!(
+        layout,
+        [
+            (0, DataType::UInt, 2),
+            (1, DataType::Str, 2),
+            (2, DataType::True, 2),
+            (3, DataType::Struct, 2),
+            (10, DataType::Str, 2),
+            (11, DataType::Struct, 2),
+        ]
+    );
+    // Optional values are wrapped in a struct.
+    assert_eq!(
+        columns[3].2,
+        [
+            Value::Struct(vec![(0, Value::F32(0.0))]),
+            Value::Struct(vec![])
+        ]
+    );
+}
+
+#[test]
+fn unknown_columns() {
+    let bytes = Columns(rows()).to_bytes().unwrap();
+    let decoded = DecodeOptions::new()
+        .decode::<Columns<Summary>>(&mut &*bytes)
+        .unwrap();
+
+    assert_eq!(decoded.value.len(), 100);
+    assert_eq!(
+        decoded.value[3],
+        Summary {
+            id: 3,
+            active: true
+        }
+    );
+
+    let ids: Vec<_> = decoded.unknown_fields.iter().map(|f| f.id).collect();
+    assert_eq!(ids, [1, 3, 10, 11]);
+
+    let err = DecodeOptions::new()
+        .deny_unknown_fields(true)
+        .decode::<Columns<Summary>>(&mut &*bytes)
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown field"), "{err}");
+}
+
+#[test]
+fn missing_column() {
+    let bytes = Columns(vec![Location {
+        city: "Dhaka".into(),
+        zip: None,
+    }])
+    .to_bytes()
+    .unwrap();
+    let err = Columns::<Row>::decode(&mut &*bytes).unwrap_err();
+    assert_eq!(err.to_string(), "missing required field `id`");
+}
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use lipi::*;
 
 #[derive(Encode, Decode, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Types {
     #[key = 0]
     pub bool: bool,
@@ -55,25 +56,41 @@ pub struct Types {
 
     #[key = 16]
     pub canvas_2d: Vec<Vec<u8>>,
+
+    #[key = 17]
+    pub u128: u128,
+
+    #[key = 18]
+    pub i128: i128,
 }
 
 #[repr(u32)]
 #[derive(Encode, Decode, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TagUnion {
     Unit = 0,
     Simple(u32) = 1,
     Complex(User) = 2,
     Bytes(Vec<u8>) = 3,
     String(String) = 4,
+    Point {
+        #[key = 0]
+        x: i32,
+        #[key = 1]
+        y: i32,
+    } = 5,
+    Pair(u8, String) = 6,
 }
 
 #[derive(Encode, Decode, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct User {
     #[key = 0]
     pub id: Option<u32>,
 
     #[key = 1]
     #[default]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub name: String,
 }
 
@@ -100,6 +117,8 @@ impl Types {
             nested: Vec::new(),
             matrix3d: Vec::new(),
             canvas_2d: Vec::new(),
+            u128: 0,
+            i128: 0,
         }
     }
 
@@ -126,6 +145,8 @@ impl Types {
                 TagUnion::Simple(123),
                 TagUnion::Bytes(vec![1, 2, 3]),
                 TagUnion::String("Test".to_string()),
+                TagUnion::Point { x: -1, y: 2 },
+                TagUnion::Pair(7, "Seven".to_string()),
             ],
             map: HashMap::from([
                 (1, TagUnion::Simple(10)),
@@ -152,6 +173,8 @@ impl Types {
                 vec![0, 255, 0], // Green
                 vec![0, 0, 255], // Blue
             ],
+            u128: 1 << 100,
+            i128: -(1 << 100),
         }
     }
 
@@ -183,6 +206,8 @@ impl Types {
                 vec![255; 100]; // White
                 100
             ],
+            u128: u128::MAX,
+            i128: i128::MIN,
         }
     }
 }
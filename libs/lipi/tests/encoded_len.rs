@@ -0,0 +1,87 @@
+mod data {
+    include!("data.rs");
+}
+
+use data::{TagUnion, Types};
+use lipi::{Columns, DataType, Encode, Value};
+use std::collections::BTreeMap;
+
+mod secs {
+    pub fn encode(secs: &f64) -> u64 {
+        *secs as u64
+    }
+}
+
+#[derive(Encode, Debug, Clone)]
+struct Header {
+    #[key = 14]
+    version: u8,
+    #[key = 15]
+    flags: Vec<bool>,
+}
+
+#[derive(Encode, Debug, Clone)]
+struct Record<'a> {
+    #[key = 0]
+    name: &'a str,
+    #[key = 1]
+    #[encode_with = "secs::encode"]
+    elapsed: f64,
+    #[flatten]
+    header: Header,
+    #[key = 300]
+    note: Option<String>,
+    #[key = 4]
+    done: bool,
+}
+
+fn assert_len(value: &impl Encode) {
+    assert_eq!(value.encoded_len(), value.to_bytes().unwrap().len());
+}
+
+#[test]
+fn derived_types() {
+    for types in [Types::min(), Types::mid(), Types::max()] {
+        assert_len(&types);
+    }
+    assert_len(&(Types::min(), Types::mid(), Types::max()));
+
+    let record = Record {
+        name: "job",
+        elapsed: 1e9,
+        header: Header {
+            version: 1,
+            flags: vec![true; 20],
+        },
+        note: None,
+        done: true,
+    };
+    assert_len(&record);
+    assert_len(&Record {
+        note: Some("x".repeat(200)),
+        ..record.clone()
+    });
+    assert_len(&Columns(vec![record; 20]));
+}
+
+#[test]
+fn std_types() {
+    assert_len(&(u64::MAX, i64::MIN, u128::MAX, i128::MIN, 'ক', -1i16));
+    assert_len(&vec![0u8; 300]);
+    assert_len(&[-1i8; 16]);
+    assert_len(&vec![true; 17]);
+    assert_len(&vec!["lipi"; 16]);
+    assert_len(&BTreeMap::from([(1u32, "one"), (1000, "thousand")]));
+    assert_len(&Ok::<_, String>(Some(TagUnion::Simple(7))));
+    assert_len(&Err::<u8, _>("error".to_string()));
+    assert_len(&Box::new((None::<u8>, false)));
+
+    let value = Value::Struct(vec![
+        (0, Value::Str("value".into())),
+        (
+            100,
+            Value::List(DataType::UInt, vec![Value::UInt(1 << 40); 3]),
+        ),
+    ]);
+    assert_len(&value);
+}
@@ -0,0 +1,124 @@
+use lipi::{
+    DataType, Decode, Encode, Value,
+    decoder::{DecodeLimits, DecodeOptions},
+    errors::{Limit, LimitExceeded},
+};
+
+#[repr(u8)]
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Tree {
+    Leaf = 0,
+    Node(Vec<Tree>) = 1,
+}
+
+#[derive(Encode, Decode, Debug)]
+struct Wrapper {
+    #[key = 0]
+    tree: Tree,
+}
+
+fn nested(depth: usize) -> Tree {
+    (0..depth).fold(Tree::Leaf, |tree, _| Tree::Node(vec![tree]))
+}
+
+fn options(limits: DecodeLimits) -> DecodeOptions {
+    DecodeOptions::new().limits(limits)
+}
+
+fn limit(err: lipi::Error) -> Limit {
+    err.downcast::<LimitExceeded>().unwrap().limit
+}
+
+#[test]
+fn max_depth() {
+    let bytes = vec![nested(100)].to_bytes().unwrap();
+    let limits = DecodeLimits {
+        max_depth: 64,
+        ..DecodeLimits::default()
+    };
+
+    assert_eq!(Vec::<Tree>::decode(&mut &*bytes).unwrap()[0], nested(100));
+    let err = options(limits)
+        .decode::<Vec<Tree>>(&mut &*bytes)
+        .unwrap_err();
+    assert!(err.to_string().contains("`max_depth` is 64"), "{err}");
+
+    let bytes = Wrapper { tree: nested(100) }.to_bytes().unwrap();
+    let err = options(limits)
+        .scope(|| Value::decode_value(&mut &*bytes, DataType::Struct))
+        .unwrap_err();
+    assert!(err.to_string().contains("`max_depth` is 64"), "{err}");
+
+    // Unknown fields are skipped, within the limits.
+    let err = options(limits).decode::<()>(&mut &*bytes).unwrap_err();
+    assert!(err.to_string().contains("`max_depth` is 64"), "{err}");
+}
+
+#[test]
+fn max_len() {
+    let bytes = vec![1u32; 10].to_bytes().unwrap();
+    let limits = DecodeLimits {
+        max_len: 5,
+        ..DecodeLimits::default()
+    };
+    let err = options(limits)
+        .decode::<Vec<u32>>(&mut &*bytes)
+        .unwrap_err();
+    assert_eq!(limit(err), Limit::Len);
+
+    let bytes = [(1u8, 2u8), (3, 4)]
+        .into_iter()
+        .collect::<std::collections::BTreeMap<_, _>>()
+        .to_bytes()
+        .unwrap();
+    let limits = DecodeLimits {
+        max_len: 1,
+        ..DecodeLimits::default()
+    };
+    let err = options(limits)
+        .decode::<std::collections::HashMap<u8, u8>>(&mut &*bytes)
+        .unwrap_err();
+    assert_eq!(limit(err), Limit::Len);
+}
+
+#[test]
+fn max_bytes() {
+    let limits = DecodeLimits {
+        max_bytes: 4,
+        ..DecodeLimits::default()
+    };
+
+    let bytes = "Hello".to_bytes().unwrap();
+    let err = options(limits).decode::<&str>(&mut &*bytes).unwrap_err();
+    assert_eq!(limit(err), Limit::Bytes);
+
+    let bytes = b"Hello".to_bytes().unwrap();
+    let err = options(limits).decode::<Vec<u8>>(&mut &*bytes).unwrap_err();
+    assert_eq!(limit(err), Limit::Bytes);
+
+    let bytes = "Hi".to_bytes().unwrap();
+    let decoded = options(limits).decode::<String>(&mut &*bytes).unwrap();
+    assert_eq!(decoded.value, "Hi");
+}
+
+#[test]
+fn max_alloc() {
+    let bytes = vec!["Hello, World!".to_string(); 8].to_bytes().unwrap();
+    let limits = DecodeLimits {
+        max_alloc: 64,
+        ..DecodeLimits::default()
+    };
+    let err = options(limits)
+        .decode::<Vec<String>>(&mut &*bytes)
+        .unwrap_err();
+    assert_eq!(limit(err), Limit::Alloc);
+
+    // Borrowed strings aren't allocated.
+    let decoded = options(DecodeLimits {
+        max_alloc: 8 * size_of::<&str>(),
+        ..limits
+    })
+    .decode::<Vec<&str>>(&mut &*bytes)
+    .unwrap();
+    assert_eq!(decoded.value.len(), 8);
+}
@@ -0,0 +1,98 @@
+use lipi::{
+    Decode, Encode,
+    decoder::{DecodeLimits, DecodeOptions, ReadDecoder},
+    errors::{Limit, LimitExceeded},
+};
+
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+struct Chunk {
+    #[key = 0]
+    seq: u32,
+    #[key = 1]
+    data: Vec<u8>,
+}
+
+#[derive(Encode, Debug)]
+struct Upload {
+    #[key = 0]
+    name: String,
+    #[key = 1]
+    chunks: Vec<Chunk>,
+    #[key = 2]
+    checksum: u64,
+    #[key = 3]
+    note: String,
+}
+
+fn upload() -> Upload {
+    Upload {
+        name: "file.bin".into(),
+        chunks: (0..10)
+            .map(|seq| Chunk {
+                seq,
+                data: vec![seq as u8; 100],
+            })
+            .collect(),
+        checksum: 42,
+        note: "skipped".into(),
+    }
+}
+
+#[test]
+fn fields_one_at_a_time() {
+    let upload = upload();
+    let bytes = upload.to_bytes().unwrap();
+    let mut decoder = ReadDecoder::new(&*bytes);
+
+    let mut name = None;
+    let mut chunks = vec![];
+    let mut checksum = None;
+    while let Some((key, ty)) = decoder.next_field_id_and_ty().unwrap() {
+        match key {
+            0 => name = Some(decoder.decode_field::<String>(ty, "name").unwrap()),
+            1 => {
+                for chunk in decoder.decode_items::<Chunk>().unwrap() {
+                    chunks.push(chunk.unwrap());
+                }
+            }
+            2 => checksum = Some(decoder.decode_field::<u64>(ty, "checksum").unwrap()),
+            _ => decoder.unknown_field(key, ty).unwrap(),
+        }
+    }
+    assert_eq!(name.as_deref(), Some("file.bin"));
+    assert_eq!(chunks, upload.chunks);
+    assert_eq!(checksum, Some(42));
+    assert!(decoder.into_inner().is_empty());
+}
+
+#[test]
+fn sequence_of_values() {
+    let chunks = upload().chunks;
+    let bytes: Vec<u8> = chunks
+        .iter()
+        .flat_map(|chunk| chunk.to_bytes().unwrap())
+        .collect();
+
+    let mut decoder = ReadDecoder::new(&*bytes);
+    let mut decoded = vec![];
+    while let Some(chunk) = decoder.try_decode::<Chunk>().unwrap() {
+        decoded.push(chunk);
+    }
+    assert_eq!(decoded, chunks);
+}
+
+#[test]
+fn limits_while_reading() {
+    let bytes = vec!["x".repeat(1000)].to_bytes().unwrap();
+    let options = DecodeOptions::new().limits(DecodeLimits {
+        max_bytes: 64,
+        ..DecodeLimits::default()
+    });
+
+    // Rejected by its length, before the string is read.
+    let err = options
+        .scope(|| ReadDecoder::new(&bytes[..10]).decode::<Vec<String>>())
+        .unwrap_err();
+    let limit = err.downcast::<LimitExceeded>().unwrap().limit;
+    assert_eq!(limit, Limit::Bytes);
+}
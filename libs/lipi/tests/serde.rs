@@ -0,0 +1,100 @@
+#[allow(unused)]
+mod data;
+
+use data::{TagUnion, Types, User};
+use lipi::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn compatible_with_derive() {
+    for data in [Types::min(), Types::mid(), Types::max()] {
+        let bytes = data.to_bytes().unwrap();
+        assert_eq!(lipi::from_slice::<Types>(&bytes).unwrap(), data);
+
+        let bytes = lipi::to_vec(&data).unwrap();
+        assert_eq!(Types::decode(&mut &*bytes).unwrap(), data);
+    }
+}
+
+#[test]
+fn same_bytes_as_derive() {
+    let user = User {
+        id: None,
+        name: "Alice".into(),
+    };
+    assert_eq!(lipi::to_vec(&user).unwrap(), user.to_bytes().unwrap());
+
+    let list = Types::mid().list;
+    assert_eq!(lipi::to_vec(&list).unwrap(), list.to_bytes().unwrap());
+    assert_eq!(
+        lipi::to_vec(&[true, false]).unwrap(),
+        (true, false).to_bytes().unwrap()
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum Shape {
+    Point,
+    Circle { x: f32, y: f32, radius: f32 },
+    Line((i32, i32), (i32, i32)),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Canvas<'a> {
+    name: &'a str,
+    #[serde(with = "serde_bytes_")]
+    background: Vec<u8>,
+    shapes: Vec<Shape>,
+    tags: Option<(char, Option<u16>, bool)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    layers: Vec<Vec<bool>>,
+    scale: Option<f64>,
+}
+
+/// Serialize bytes with `serialize_bytes`.
+mod serde_bytes_ {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_bytes(bytes)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        <&[u8]>::deserialize(d).map(Vec::from)
+    }
+}
+
+#[test]
+fn round_trip() {
+    let canvas = Canvas {
+        name: "draft",
+        background: vec![0xff, 0x00, 0x7f],
+        shapes: vec![
+            Shape::Point,
+            Shape::Circle {
+                x: 1.0,
+                y: 2.0,
+                radius: 3.0,
+            },
+            Shape::Line((0, 0), (-4, 8)),
+        ],
+        tags: Some(('#', None, true)),
+        layers: vec![],
+        scale: Some(0.5),
+    };
+    let bytes = lipi::to_vec(&canvas).unwrap();
+    assert_eq!(lipi::from_slice::<Canvas>(&bytes).unwrap(), canvas);
+
+    // `layers` is skipped, but `scale` keeps its key.
+    let value = lipi::Value::decode(&mut &*bytes).unwrap();
+    assert_eq!(value.get(4), None);
+    assert_eq!(value.get(5), Some(&lipi::Value::F64(0.5)));
+}
+
+#[test]
+fn unsupported() {
+    assert!(lipi::to_vec(&true).is_err());
+    assert!(lipi::to_vec(&None::<u8>).is_err());
+    assert!(lipi::to_vec(&vec![Some(1), None]).is_err());
+    assert!(lipi::to_vec(&(1, vec![TagUnion::Unit, TagUnion::Simple(1)])).is_ok());
+}
@@ -0,0 +1,133 @@
+use futures::{executor::block_on, stream};
+use lipi::{
+    Decode, Encode,
+    decoder::{DecodeLimits, DecodeOptions, StreamDecoder},
+    errors::{Limit, LimitExceeded, UnexpectedEof, UnknownField},
+};
+use std::io;
+
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+struct Chunk {
+    #[key = 0]
+    seq: u32,
+    #[key = 1]
+    data: Vec<u8>,
+}
+
+#[derive(Encode, Debug)]
+struct Upload {
+    #[key = 0]
+    name: String,
+    #[key = 1]
+    chunks: Vec<Chunk>,
+    #[key = 2]
+    note: String,
+}
+
+#[derive(Decode, Debug)]
+#[allow(dead_code)]
+struct Name {
+    #[key = 0]
+    name: String,
+}
+
+fn upload() -> Upload {
+    Upload {
+        name: "file.bin".into(),
+        chunks: (0..10)
+            .map(|seq| Chunk {
+                seq,
+                data: vec![seq as u8; 100],
+            })
+            .collect(),
+        note: "skipped".into(),
+    }
+}
+
+/// Splits `bytes` into chunks of `size` bytes, as they would arrive from the network.
+fn chunked(bytes: &[u8], size: usize) -> impl stream::Stream<Item = io::Result<Vec<u8>>> + Unpin {
+    let chunks: Vec<_> = bytes.chunks(size).map(|chunk| Ok(chunk.to_vec())).collect();
+    stream::iter(chunks)
+}
+
+#[test]
+fn fields_one_at_a_time() {
+    let upload = upload();
+    let bytes = upload.to_bytes().unwrap();
+
+    for size in [1, 7, bytes.len()] {
+        let mut decoder = StreamDecoder::new(chunked(&bytes, size));
+        block_on(async {
+            let mut name = None;
+            let mut chunks = vec![];
+            while let Some((key, ty)) = decoder.next_field_id_and_ty().await.unwrap() {
+                match key {
+                    0 => name = Some(decoder.decode_field::<String>(ty, "name").await.unwrap()),
+                    1 => {
+                        let mut items = decoder.decode_items::<Chunk>().await.unwrap();
+                        assert_eq!(items.remaining(), 10);
+                        while let Some(chunk) = items.next().await {
+                            chunks.push(chunk.unwrap());
+                        }
+                    }
+                    _ => decoder.unknown_field(key, ty).await.unwrap(),
+                }
+            }
+            assert_eq!(name.as_deref(), Some("file.bin"));
+            assert_eq!(chunks, upload.chunks);
+            assert!(decoder.try_decode::<u8>().await.unwrap().is_none());
+        });
+    }
+}
+
+#[test]
+fn sequence_of_values() {
+    let chunks = upload().chunks;
+    let bytes: Vec<u8> = chunks
+        .iter()
+        .flat_map(|chunk| chunk.to_bytes().unwrap())
+        .collect();
+
+    let mut decoder = StreamDecoder::new(chunked(&bytes, 33));
+    let decoded = block_on(async {
+        let mut decoded = vec![];
+        while let Some(chunk) = decoder.try_decode::<Chunk>().await.unwrap() {
+            decoded.push(chunk);
+        }
+        decoded
+    });
+    assert_eq!(decoded, chunks);
+}
+
+#[test]
+fn options_of_each_value() {
+    let bytes = upload().to_bytes().unwrap();
+    let options = DecodeOptions::new().deny_unknown_fields(true);
+    let mut decoder = StreamDecoder::new(chunked(&bytes, 5)).options(options);
+    let err = block_on(decoder.decode::<Name>()).unwrap_err();
+    assert!(err.is::<UnknownField>());
+
+    // Rejected by its length, before the rest of the string is awaited.
+    let bytes = vec!["x".repeat(1000)].to_bytes().unwrap();
+    let options = DecodeOptions::new().limits(DecodeLimits {
+        max_bytes: 64,
+        ..DecodeLimits::default()
+    });
+    let mut decoder = StreamDecoder::new(chunked(&bytes[..10], 1)).options(options);
+    let err = block_on(decoder.decode::<Vec<String>>()).unwrap_err();
+    let limit = err.downcast::<LimitExceeded>().unwrap().limit;
+    assert_eq!(limit, Limit::Bytes);
+}
+
+#[test]
+fn stream_errors() {
+    let bytes = "Hello".to_bytes().unwrap();
+    let mut decoder = StreamDecoder::new(chunked(&bytes[..3], 1));
+    let err = block_on(decoder.decode::<String>()).unwrap_err();
+    assert_eq!(err.downcast::<UnexpectedEof>().unwrap().needed, 3);
+
+    let chunks = [Ok(bytes[..3].to_vec()), Err(io::Error::other("reset"))];
+    let mut decoder = StreamDecoder::new(stream::iter(chunks));
+    let err = block_on(decoder.decode::<String>()).unwrap_err();
+    assert_eq!(err.to_string(), "reset");
+}
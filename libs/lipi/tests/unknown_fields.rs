@@ -0,0 +1,86 @@
+use lipi::{
+    DataType, Decode, Encode,
+    decoder::DecodeOptions,
+    errors::{self, UnknownField},
+};
+
+#[derive(Encode, Debug)]
+struct UserV2 {
+    #[key = 0]
+    name: String,
+    #[key = 1]
+    age: u8,
+    #[key = 2]
+    email: String,
+}
+
+#[derive(Decode, Debug, PartialEq)]
+struct User {
+    #[key = 0]
+    name: String,
+    #[key = 1]
+    age: u8,
+}
+
+#[derive(Decode, Debug)]
+#[deny_unknown_fields]
+struct StrictUser {
+    #[key = 0]
+    _name: String,
+}
+
+fn alice() -> UserV2 {
+    UserV2 {
+        name: "Alice".into(),
+        age: 30,
+        email: "alice@example.com".into(),
+    }
+}
+
+fn user_v2() -> Vec<u8> {
+    alice().to_bytes().unwrap()
+}
+
+#[test]
+fn skipped_by_default() {
+    let bytes = user_v2();
+    let user = User::decode(&mut &*bytes).unwrap();
+    assert_eq!(user.name, "Alice");
+}
+
+#[test]
+fn deny_unknown_fields_attr() {
+    let bytes = user_v2();
+    let err = StrictUser::decode(&mut &*bytes).unwrap_err();
+    let UnknownField { id, ty } = *err.downcast::<errors::UnknownField>().unwrap();
+    assert_eq!((id, ty), (1, DataType::U8));
+}
+
+#[test]
+fn deny_unknown_fields_option() {
+    let bytes = user_v2();
+    let options = DecodeOptions::new().deny_unknown_fields(true);
+
+    let err = options.decode::<User>(&mut &*bytes).unwrap_err();
+    let err = err.downcast::<errors::UnknownField>().unwrap();
+    assert_eq!(err.id, 2);
+
+    // Tuples are structs, keyed by position.
+    let bytes = (1u8, 2u8).to_bytes().unwrap();
+    assert!(options.decode::<(u8,)>(&mut &*bytes).is_err());
+
+    // Options are scoped to the call.
+    assert_eq!(<(u8,)>::decode(&mut &*bytes).unwrap(), (1,));
+}
+
+#[test]
+fn collect_unknown_fields() {
+    let bytes = [alice(), alice()].to_bytes().unwrap();
+    let decoded = DecodeOptions::new()
+        .decode::<Vec<User>>(&mut &*bytes)
+        .unwrap();
+
+    assert_eq!(decoded.value.len(), 2);
+    let ids: Vec<_> = decoded.unknown_fields.iter().map(|f| f.id).collect();
+    assert_eq!(ids, [2, 2]);
+}
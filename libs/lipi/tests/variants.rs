@@ -0,0 +1,110 @@
+use lipi::{DataType, Decode, Encode, Value};
+
+#[repr(u8)]
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Shape<'a> {
+    Point = 0,
+    Circle {
+        #[key = 0]
+        x: f32,
+        #[key = 1]
+        y: f32,
+        #[key = 2]
+        #[default]
+        radius: f32,
+        #[skip]
+        label: Option<String>,
+    } = 1,
+    Line((i32, i32), (i32, i32)) = 2,
+    Text(&'a str, Option<u8>) = 3,
+}
+
+fn to_value(shape: &Shape) -> Value {
+    let bytes = shape.to_bytes().unwrap();
+    Value::decode_value(&mut &*bytes, DataType::Union).unwrap()
+}
+
+fn to_bytes(value: &Value) -> Vec<u8> {
+    let mut bytes = vec![];
+    value.encode_value(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn round_trip() {
+    let shapes = vec![
+        Shape::Point,
+        Shape::Circle {
+            x: 1.0,
+            y: 2.0,
+            radius: 3.0,
+            label: None,
+        },
+        Shape::Line((0, 0), (-4, 8)),
+        Shape::Text("Hello", None),
+        Shape::Text("World", Some(2)),
+    ];
+    let bytes = shapes.to_bytes().unwrap();
+    assert_eq!(Vec::<Shape>::decode(&mut &*bytes).unwrap(), shapes);
+}
+
+#[test]
+fn struct_variant() {
+    let circle = Shape::Circle {
+        x: 1.0,
+        y: 2.0,
+        radius: 0.0,
+        label: Some("unkeyed".into()),
+    };
+    let fields = vec![(0, Value::F32(1.0)), (1, Value::F32(2.0))];
+    let mut all_fields = fields.clone();
+    all_fields.push((2, Value::F32(0.0)));
+    assert_eq!(
+        to_value(&circle),
+        Value::Union(1, Box::new(Value::Struct(all_fields)))
+    );
+
+    // `radius` is `#[default]`, `label` is skipped.
+    let bytes = to_bytes(&Value::Union(1, Box::new(Value::Struct(fields))));
+    assert_eq!(
+        Shape::decode(&mut &*bytes).unwrap(),
+        Shape::Circle {
+            x: 1.0,
+            y: 2.0,
+            radius: 0.0,
+            label: None,
+        }
+    );
+
+    let bytes = to_bytes(&Value::Union(1, Box::new(Value::F32(1.0))));
+    assert!(Shape::decode(&mut &*bytes).is_err());
+}
+
+#[test]
+fn tuple_variant() {
+    assert_eq!(
+        to_value(&Shape::Text("Hi", Some(1))),
+        Value::Union(
+            3,
+            Box::new(Value::Struct(vec![
+                (0, Value::Str("Hi".into())),
+                (1, Value::U8(1)),
+            ]))
+        )
+    );
+
+    let bytes = to_bytes(&Value::Union(
+        3,
+        Box::new(Value::Struct(vec![(0, Value::Str("Hi".into()))])),
+    ));
+    assert_eq!(
+        Shape::decode(&mut &*bytes).unwrap(),
+        Shape::Text("Hi", None)
+    );
+
+    let bytes = to_bytes(&Value::Union(
+        3,
+        Box::new(Value::Struct(vec![(1, Value::U8(1))])),
+    ));
+    assert!(Shape::decode(&mut &*bytes).is_err());
+}
@@ -0,0 +1,69 @@
+import 'dart:typed_data';
+
+import 'utils/common.dart';
+
+final class BitVec {
+  final Uint8List _bytes;
+
+  BitVec(int len) : _bytes = Uint8List(boolPackedLen(len));
+  BitVec.fromBytes(this._bytes);
+
+  Uint8List asBytes() => _bytes;
+
+  int get capacity => _bytes.length * 8;
+
+  bool get isEmpty => _bytes.every((slot) => slot == 0);
+
+  bool has(int index) => get(index) ?? false;
+
+  bool? get(int index) {
+    final slotIdx = index ~/ 8;
+    if (slotIdx >= _bytes.length) return null;
+    return (_bytes[slotIdx] & (1 << (index % 8))) != 0;
+  }
+
+  void clear() => _bytes.fillRange(0, _bytes.length, 0);
+
+  bool set(int index) {
+    final slotIdx = index ~/ 8;
+    final mask = 1 << (index % 8);
+
+    ensure(
+      slotIdx < _bytes.length,
+      () => RangeError('Out of bounds slot index: $slotIdx'),
+    );
+
+    final oldValue = (_bytes[slotIdx] & mask) != 0;
+    _bytes[slotIdx] |= mask;
+    return oldValue;
+  }
+
+  bool? remove(int index) {
+    final slotIdx = index ~/ 8;
+    final mask = 1 << (index % 8);
+
+    if (slotIdx >= _bytes.length) return null;
+
+    final oldValue = (_bytes[slotIdx] & mask) != 0;
+    _bytes[slotIdx] &= ~mask;
+    return oldValue;
+  }
+}
+
+int boolPackedLen(int len) {
+  ensure(len >= 0, () => RangeError('length $len cannot be negative'));
+  return (len + 7) ~/ 8;
+}
+
+BitVec bitvecFrom(List<bool> bools) {
+  final bv = BitVec(bools.length);
+  for (var i = 0; i < bools.length; i++) {
+    if (bools[i]) bv.set(i);
+  }
+  return bv;
+}
+
+List<bool> bitvecToBools(Uint8List bytes, int len) {
+  final bv = BitVec.fromBytes(bytes);
+  return List.generate(len, bv.has);
+}
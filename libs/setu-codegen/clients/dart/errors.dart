@@ -0,0 +1,30 @@
+import 'status.dart';
+
+final class EndOfData implements Exception {
+  final String message;
+  const EndOfData([this.message = '']);
+
+  @override
+  String toString() => 'EndOfData: $message';
+}
+
+final class ProtocolError implements Exception {
+  final String message;
+  const ProtocolError([this.message = '']);
+
+  @override
+  String toString() => 'ProtocolError: $message';
+}
+
+/// Error trailer sent by the server.
+final class StatusError implements Exception {
+  final Status status;
+  final String? message;
+  const StatusError(this.status, [this.message]);
+
+  @override
+  String toString() {
+    final reason = message == null ? '' : ' ($message)';
+    return 'unexpected status: ${status.name}$reason';
+  }
+}
@@ -0,0 +1,340 @@
+import 'dart:convert' show utf8;
+import 'dart:typed_data';
+
+import '../bitset.dart';
+import '../errors.dart';
+import '../utils/bytes.dart';
+import '../utils/common.dart';
+import 'result.dart';
+import 'skip.dart';
+import 'type.dart';
+import 'varint.dart';
+import 'zigzag.dart';
+
+typedef DecodeFn<T> = T Function(Decode d);
+
+/// Decoder of `T`, tagged with its wire [DataType].
+final class Decoder<T> {
+  final DataType type;
+  final DecodeFn<T> _decode;
+
+  const Decoder(this.type, this._decode);
+
+  T call(Decode d) => _decode(d);
+}
+
+class Deserialize {
+  final Bytes buf;
+  Deserialize(this.buf);
+
+  BigInt readVarint([int bits = 64]) => decodeVarInt(buf, bits);
+
+  int readLen() => readVarint().toInt();
+
+  Uint8List readBytes() => buf.take(readLen());
+
+  (int, DataType) readFieldIdAndTy() {
+    final byte = buf.nextByte();
+
+    final ty = DataType.from(byte);
+    var id = byte >> 4;
+
+    if (id == 0xF) {
+      id = readLen() + 15;
+    }
+    return (id, ty);
+  }
+
+  (int, DataType) readLenAndTy() => readFieldIdAndTy();
+
+  (int, DataType)? nextFieldIdAndTy() {
+    final (id, ty) = readFieldIdAndTy();
+    if (ty == DataType.structEnd) {
+      ensure(id == 0, () => ProtocolError('invalid struct end id: $id, expected `0`'));
+      return null;
+    }
+    return (id, ty);
+  }
+}
+
+final class Decode extends Deserialize {
+  Decode(super.buf);
+
+  /// Decodes value of a field, whose header is already read.
+  T field<T>(DataType ty, Decoder<T> decoder) {
+    if (decoder.type == DataType.boolTrue) return ty.asBool() as T;
+    DataType.expected(decoder.type, ty);
+    return decoder(this);
+  }
+}
+
+abstract final class Decoders {
+  /// Booleans are packed in field header, see [Decode.field].
+  static final boolean = Decoder<bool>(
+    DataType.boolTrue,
+    (d) => throw StateError('unreachable'),
+  );
+
+  static final u8 = Decoder<int>(DataType.u8, (d) => d.buf.nextByte());
+
+  static final i8 = Decoder<int>(DataType.i8, (d) => d.buf.nextByte().toSigned(8));
+
+  static final f32 = Decoder<double>(
+    DataType.f32,
+    (d) => ByteData.sublistView(d.buf.take(4)).getFloat32(0, Endian.little),
+  );
+
+  static final f64 = Decoder<double>(
+    DataType.f64,
+    (d) => ByteData.sublistView(d.buf.take(8)).getFloat64(0, Endian.little),
+  );
+
+  static final u16 = Decoder<int>(DataType.uint, (d) => checkOverflowUint(d.readLen(), 16));
+
+  static final u32 = Decoder<int>(DataType.uint, (d) => checkOverflowUint(d.readLen(), 32));
+
+  static final u64 = Decoder<BigInt>(DataType.uint, (d) => d.readVarint());
+
+  static final u128 = Decoder<BigInt>(DataType.uint, (d) => d.readVarint(128));
+
+  static final i16 = Decoder<int>(
+    DataType.sint,
+    (d) => checkOverflowInt(i64(d).toInt(), 16),
+  );
+
+  static final i32 = Decoder<int>(
+    DataType.sint,
+    (d) => checkOverflowInt(i64(d).toInt(), 32),
+  );
+
+  static final i64 = Decoder<BigInt>(DataType.sint, (d) => zigzagDecode(d.readVarint()));
+
+  static final i128 = Decoder<BigInt>(DataType.sint, (d) => zigzagDecode(d.readVarint(128)));
+
+  /// Unsigned varint, without overflow check. Used by numeric enums.
+  static final uint = Decoder<int>(DataType.uint, (d) => d.readLen());
+
+  /// Signed (zigzag) varint, without overflow check. Used by numeric enums.
+  static final sint = Decoder<int>(DataType.sint, (d) => i64(d).toInt());
+
+  static final char = Decoder<String>(
+    DataType.uint,
+    (d) => String.fromCharCode(checkOverflowUint(d.readLen(), 32)),
+  );
+
+  static final str = Decoder<String>(DataType.str, (d) => utf8.decode(d.readBytes()));
+
+  static Decoder<List<T>> list<T>(Decoder<T> item) {
+    return Decoder(DataType.list, (d) {
+      final (len, ty) = d.readLenAndTy();
+      DataType.expected(item.type, ty);
+      return List.generate(len, (_) => item(d));
+    });
+  }
+
+  static final listU8 = Decoder<Uint8List>(DataType.list, (d) {
+    final (len, ty) = d.readLenAndTy();
+    DataType.expected(DataType.u8, ty);
+    return d.buf.take(len);
+  });
+
+  static final listI8 = Decoder<Int8List>(DataType.list, (d) {
+    final (len, ty) = d.readLenAndTy();
+    DataType.expected(DataType.i8, ty);
+    return Int8List.sublistView(d.buf.take(len));
+  });
+
+  static final listF32 = _typedList(f32, Float32List.new);
+  static final listF64 = _typedList(f64, Float64List.new);
+
+  static final listU16 = _typedList(u16, Uint16List.new);
+  static final listU32 = _typedList(u32, Uint32List.new);
+  static final listU64 = list(u64);
+
+  static final listI16 = _typedList(i16, Int16List.new);
+  static final listI32 = _typedList(i32, Int32List.new);
+  static final listI64 = list(i64);
+
+  static final listBool = Decoder<List<bool>>(DataType.list, (d) {
+    final (len, ty) = d.readLenAndTy();
+    DataType.expected(DataType.boolTrue, ty);
+    return bitvecToBools(d.buf.take(boolPackedLen(len)), len);
+  });
+
+  static Decoder<Map<K, V>> table<K, V>(Decoder<K> k, Decoder<V> v) {
+    return Decoder(DataType.table, (d) {
+      final columnCount = d.readLen();
+      final len = d.readLen();
+
+      ensure(columnCount == 2, () => ProtocolError('invalid column count: $columnCount'));
+
+      final (field, ty) = d.readFieldIdAndTy();
+      switch (field) {
+        case 0:
+          DataType.expected(k.type, ty);
+          final keys = List.generate(len, (_) => k(d));
+
+          final (field2, ty2) = d.readFieldIdAndTy();
+          ensure(
+            field2 == 1,
+            () => ProtocolError('invalid column (value) id: expected `1`, found $field2'),
+          );
+          DataType.expected(v.type, ty2);
+
+          return {for (final key in keys) key: v(d)};
+
+        case 1:
+          DataType.expected(v.type, ty);
+          final values = List.generate(len, (_) => v(d));
+
+          final (field2, ty2) = d.readFieldIdAndTy();
+          ensure(
+            field2 == 0,
+            () => ProtocolError('invalid column (key) id: expected `0`, found $field2'),
+          );
+          DataType.expected(k.type, ty2);
+
+          return {for (final val in values) k(d): val};
+
+        default:
+          throw ProtocolError('invalid column id: expected `0` or `1`, found $field');
+      }
+    });
+  }
+
+  /// List of structs, encoded as a table with a column per field. (`lipi::Columns`)
+  ///
+  /// [columns] are `(key, decoder, optional)` of each field, values of optional fields
+  /// are wrapped in a struct. [build] creates a row from its values, in the order of [columns].
+  static Decoder<List<T>> columns<T>(
+    List<(int, Decoder<Object?>, bool)> columns,
+    T Function(List<Object?> z) build,
+  ) {
+    return Decoder(DataType.table, (d) {
+      final columnCount = d.readLen();
+      final len = d.readLen();
+      final rows = List.generate(len, (_) => List<Object?>.filled(columns.length, null));
+
+      for (var i = 0; i < columnCount; i++) {
+        final (id, ty) = d.readFieldIdAndTy();
+        final index = columns.indexWhere((column) => column.$1 == id);
+        if (index == -1 || len == 0) {
+          skipListValues(d, len, ty);
+          continue;
+        }
+
+        final (_, decoder, optional) = columns[index];
+        if (optional) {
+          DataType.expected(DataType.struct, ty);
+          for (final row in rows) {
+            structDecoder(d, (key, ty) {
+              if (key != 0) return false;
+              row[index] = d.field(ty, decoder);
+              return true;
+            });
+          }
+        } else if (decoder.type == DataType.boolTrue) {
+          DataType.expected(DataType.boolTrue, ty);
+          final bools = bitvecToBools(d.buf.take(boolPackedLen(len)), len);
+          for (var r = 0; r < len; r++) {
+            rows[r][index] = bools[r];
+          }
+        } else {
+          DataType.expected(decoder.type, ty);
+          for (final row in rows) {
+            row[index] = decoder(d);
+          }
+        }
+      }
+      return [for (final row in rows) build(row)];
+    });
+  }
+
+  /// [required] is `false`, if `Ok` value is optional (`T` is nullable).
+  static Decoder<Result<T, E>> result<T, E>(Decoder<T> ok, Decoder<E> err, bool required) {
+    return Decoder(DataType.struct, (d) {
+      T? value;
+      E? error;
+      structDecoder(d, (id, ty) {
+        switch (id) {
+          case 0:
+            value = d.field(ty, ok);
+          case 1:
+            error = d.field(ty, err);
+          default:
+            return false;
+        }
+        return true;
+      });
+
+      if (error case final error?) {
+        ensure(value == null, () => const ProtocolError('result has both `Ok` and `Err`'));
+        return Err(error);
+      }
+      if (required) return Ok(requiredField(value, 'Ok', 0));
+      return Ok(value as T);
+    });
+  }
+
+  static Decoder<L> _typedList<L extends List<N>, N>(
+    Decoder<N> item,
+    L Function(int len) create,
+  ) {
+    return Decoder(DataType.list, (d) {
+      final (len, ty) = d.readLenAndTy();
+      DataType.expected(item.type, ty);
+
+      final list = create(len);
+      for (var i = 0; i < len; i++) {
+        list[i] = item(d);
+      }
+      return list;
+    });
+  }
+}
+
+// ================================================================================
+
+/// Decodes a struct, `field` returns `false` for unknown fields, which are skipped.
+void structDecoder(Decode d, bool Function(int id, DataType ty) field) {
+  for (var header = d.nextFieldIdAndTy(); header != null; header = d.nextFieldIdAndTy()) {
+    final (id, ty) = header;
+    if (!field(id, ty)) skipField(d, id, ty);
+  }
+}
+
+/// Decodes tuple items from struct fields, `fields` are `(key, decoder)` of each item.
+List<Object?> tupleDecoder(Decode d, List<(int, Decoder<Object?>)> fields) {
+  final items = List<Object?>.filled(fields.length, null);
+  structDecoder(d, (id, ty) {
+    final index = fields.indexWhere((field) => field.$1 == id);
+    if (index == -1) return false;
+    items[index] = d.field(ty, fields[index].$2);
+    return true;
+  });
+  return items;
+}
+
+T requiredField<T>(T? value, String name, int id) {
+  if (value == null) throw ProtocolError("missing required field: '$name' as $id");
+  return value;
+}
+
+/// Skips the payload of a unit variant.
+T unitVariant<T>(Decode d, int id, DataType ty, T value) {
+  skipField(d, id, ty);
+  return value;
+}
+
+// ================================================================================
+
+T? outputDecoder<T>(Decode d, Decoder<T> decoder, bool required) {
+  T? val;
+  if (d.nextFieldIdAndTy() case (final id, final ty) when id == 0) {
+    val = d.field(ty, decoder);
+  }
+  if (required && val == null) {
+    throw ProtocolError('output required of type ${decoder.type.name}');
+  }
+  return val;
+}
@@ -0,0 +1,259 @@
+import 'dart:convert' show utf8;
+import 'dart:typed_data';
+
+import '../bitset.dart';
+import '../utils/buffer.dart';
+import '../utils/common.dart';
+import 'result.dart';
+import 'type.dart';
+import 'varint.dart';
+import 'zigzag.dart';
+
+typedef EncodeFn<T> = void Function(Encode e, T value);
+
+/// Encoder of `T`, tagged with its wire [DataType].
+final class Encoder<T> {
+  final DataType type;
+  final EncodeFn<T> _encode;
+
+  const Encoder(this.type, this._encode);
+
+  void call(Encode e, T value) => _encode(e, value);
+}
+
+class Writer extends Buffer {
+  void writeVarint(BigInt num) => push(encodeVarInt(num));
+
+  void writeLen(int len) => writeVarint(BigInt.from(len));
+
+  void writeBytes(List<int> bytes) {
+    writeLen(bytes.length);
+    push(bytes);
+  }
+
+  void writeFieldIdAndTy(int num, DataType ty) {
+    ensure(num >= 0, () => RangeError('expected non-negative integer, got: $num'));
+
+    if (num < 15) return writeByte((num << 4) | ty.index);
+
+    writeByte((0xF << 4) | ty.index);
+    writeLen(num - 15);
+  }
+
+  void writeLenAndTy(int num, DataType ty) {
+    ensure(num <= 0xFFFFFFF, () => RangeError('length $num exceeds 28 bits'));
+    writeFieldIdAndTy(num, ty);
+  }
+}
+
+final class Encode extends Writer {
+  /// Encodes a field, `null` values are omitted.
+  void field<T>(int id, T? value, Encoder<T> encoder) {
+    if (value == null) return;
+    if (encoder.type == DataType.boolTrue) {
+      return writeFieldIdAndTy(id, DataType.fromBool(value as bool));
+    }
+    writeFieldIdAndTy(id, encoder.type);
+    encoder(this, value);
+  }
+}
+
+abstract final class Encoders {
+  /// Booleans are packed in field header, see [Encode.field].
+  static final boolean = Encoder<bool>(
+    DataType.boolTrue,
+    (e, v) => throw StateError('unreachable'),
+  );
+
+  static final u8 = Encoder<int>(DataType.u8, (e, v) => e.writeByte(v));
+
+  static final i8 = Encoder<int>(
+    DataType.i8,
+    (e, v) => e.writeByte(checkOverflowInt(v, 8) & 0xFF),
+  );
+
+  static final f32 = Encoder<double>(DataType.f32, (e, v) {
+    e.push(Uint8List(4)..buffer.asByteData().setFloat32(0, v, Endian.little));
+  });
+
+  static final f64 = Encoder<double>(DataType.f64, (e, v) {
+    e.push(Uint8List(8)..buffer.asByteData().setFloat64(0, v, Endian.little));
+  });
+
+  static final u16 = Encoder<int>(
+    DataType.uint,
+    (e, v) => e.writeLen(checkOverflowUint(v, 16)),
+  );
+
+  static final u32 = Encoder<int>(
+    DataType.uint,
+    (e, v) => e.writeLen(checkOverflowUint(v, 32)),
+  );
+
+  static final u64 = Encoder<BigInt>(DataType.uint, (e, v) => e.writeVarint(v));
+
+  static final u128 = Encoder<BigInt>(
+    DataType.uint,
+    (e, v) => e.writeVarint(checkOverflowBigUint(v, 128)),
+  );
+
+  static final i16 = Encoder<int>(
+    DataType.sint,
+    (e, v) => i64(e, BigInt.from(checkOverflowInt(v, 16))),
+  );
+
+  static final i32 = Encoder<int>(
+    DataType.sint,
+    (e, v) => i64(e, BigInt.from(checkOverflowInt(v, 32))),
+  );
+
+  static final i64 = Encoder<BigInt>(
+    DataType.sint,
+    (e, v) => e.writeVarint(zigzagEncode(v)),
+  );
+
+  static final i128 = Encoder<BigInt>(
+    DataType.sint,
+    (e, v) => e.writeVarint(zigzagEncode(checkOverflowBigInt(v, 128), 128)),
+  );
+
+  /// Unsigned varint, without overflow check. Used by numeric enums.
+  static final uint = Encoder<int>(DataType.uint, (e, v) => e.writeLen(v));
+
+  /// Signed (zigzag) varint, without overflow check. Used by numeric enums.
+  static final sint = Encoder<int>(DataType.sint, (e, v) => i64(e, BigInt.from(v)));
+
+  /// Single character (unicode scalar value) string.
+  static final char = Encoder<String>(DataType.uint, (e, v) {
+    ensure(v.runes.length == 1, () => ArgumentError.value(v, 'char', 'expected a single character'));
+    e.writeLen(v.runes.first);
+  });
+
+  static final str = Encoder<String>(DataType.str, (e, v) => e.writeBytes(utf8.encode(v)));
+
+  static Encoder<List<T>> list<T>(Encoder<T> item) {
+    return Encoder(DataType.list, (e, vals) {
+      e.writeLenAndTy(vals.length, item.type);
+      for (final v in vals) {
+        item(e, v);
+      }
+    });
+  }
+
+  static final listU8 = Encoder<Uint8List>(DataType.list, (e, v) {
+    e.writeLenAndTy(v.length, DataType.u8);
+    e.push(v);
+  });
+
+  static final listI8 = Encoder<Int8List>(DataType.list, (e, v) {
+    e.writeLenAndTy(v.length, DataType.i8);
+    e.push(Uint8List.sublistView(v));
+  });
+
+  static final listF32 = Encoder<Float32List>(DataType.list, (e, v) {
+    e.writeLenAndTy(v.length, DataType.f32);
+    for (final n in v) {
+      f32(e, n);
+    }
+  });
+
+  static final listF64 = Encoder<Float64List>(DataType.list, (e, v) {
+    e.writeLenAndTy(v.length, DataType.f64);
+    for (final n in v) {
+      f64(e, n);
+    }
+  });
+
+  static final listU16 = Encoder<Uint16List>(DataType.list, list(u16)._encode);
+  static final listU32 = Encoder<Uint32List>(DataType.list, list(u32)._encode);
+  static final listU64 = list(u64);
+
+  static final listI16 = Encoder<Int16List>(DataType.list, list(i16)._encode);
+  static final listI32 = Encoder<Int32List>(DataType.list, list(i32)._encode);
+  static final listI64 = list(i64);
+
+  static final listBool = Encoder<List<bool>>(DataType.list, (e, bools) {
+    e.writeLenAndTy(bools.length, DataType.boolTrue);
+    e.push(bitvecFrom(bools).asBytes());
+  });
+
+  static Encoder<Map<K, V>> table<K, V>(Encoder<K> k, Encoder<V> v) {
+    return Encoder(DataType.table, (e, map) {
+      e.writeLen(2); // Column count
+      e.writeLen(map.length); // Row count
+
+      e.writeFieldIdAndTy(0, k.type);
+      for (final key in map.keys) {
+        k(e, key);
+      }
+
+      e.writeFieldIdAndTy(1, v.type);
+      for (final val in map.values) {
+        v(e, val);
+      }
+    });
+  }
+
+  /// List of structs, encoded as a table with a column per field. (`lipi::Columns`)
+  ///
+  /// [columns] are `(key, encoder, optional)` of each field, values of optional fields
+  /// are wrapped in a struct. [values] returns the fields of a row, in the order of [columns].
+  static Encoder<List<T>> columns<T>(
+    List<(int, Encoder<Object?>, bool)> columns,
+    List<Object?> Function(T z) values,
+  ) {
+    return Encoder(DataType.table, (e, rows) {
+      e.writeLen(columns.length); // Column count
+      e.writeLen(rows.length); // Row count
+
+      final table = [for (final row in rows) values(row)];
+      for (final (index, (id, encoder, optional)) in columns.indexed) {
+        if (optional) {
+          e.writeFieldIdAndTy(id, DataType.struct);
+          for (final row in table) {
+            structEncoder(e, [Field(0, row[index], encoder)]);
+          }
+        } else if (encoder.type == DataType.boolTrue) {
+          e.writeFieldIdAndTy(id, DataType.boolTrue);
+          e.push(bitvecFrom([for (final row in table) row[index] as bool]).asBytes());
+        } else {
+          e.writeFieldIdAndTy(id, encoder.type);
+          for (final row in table) {
+            encoder(e, row[index]);
+          }
+        }
+      }
+    });
+  }
+
+  static Encoder<Result<T, E>> result<T, E>(Encoder<T> ok, Encoder<E> err) {
+    return Encoder(DataType.struct, (e, result) {
+      switch (result) {
+        case Ok(:final value):
+          e.field(0, value, ok);
+        case Err(:final error):
+          e.field(1, error, err);
+      }
+      e.writeByte(DataType.structEnd.index);
+    });
+  }
+}
+
+// ================================================================================
+
+final class Field<T> {
+  final int id;
+  final T? value;
+  final Encoder<T> encoder;
+
+  const Field(this.id, this.value, this.encoder);
+
+  void encodeTo(Encode e) => e.field(id, value, encoder);
+}
+
+void structEncoder(Encode e, List<Field> fields) {
+  for (final field in fields) {
+    field.encodeTo(e);
+  }
+  e.writeByte(DataType.structEnd.index);
+}
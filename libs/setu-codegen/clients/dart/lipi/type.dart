@@ -0,0 +1,42 @@
+import '../errors.dart';
+
+enum DataType {
+  boolFalse, // 0
+  boolTrue, // 1
+
+  u8, // 2
+  i8, // 3
+
+  f32, // 4
+  f64, // 5
+
+  uint, // 6
+  sint, // 7
+
+  str, // 8
+
+  struct, // 9
+  structEnd, // 10
+
+  union, // 11
+  list, // 12
+  table, // 13
+
+  unknownI, // 14
+  unknownII; // 15
+
+  static DataType from(int code) => values[code & 0xF];
+
+  static DataType fromBool(bool value) => value ? boolTrue : boolFalse;
+
+  bool asBool() {
+    if (this == boolFalse || this == boolTrue) return this == boolTrue;
+    throw ProtocolError('expected: boolFalse or boolTrue, found: $name');
+  }
+
+  static void expected(DataType expected, DataType found) {
+    if (expected != found) {
+      throw ProtocolError('expected: ${expected.name}, found: ${found.name}');
+    }
+  }
+}
@@ -0,0 +1,40 @@
+import 'dart:typed_data';
+
+import '../errors.dart';
+import '../utils/bytes.dart';
+
+final _u7 = BigInt.from(0x7F);
+final _msb = BigInt.from(0x80);
+
+Uint8List encodeVarInt(BigInt num) {
+  if (num.isNegative) throw RangeError('expected unsigned number: found $num');
+
+  final buf = <int>[];
+  while (num > _u7) {
+    buf.add(((num & _u7) | _msb).toInt());
+    num >>= 7;
+  }
+  buf.add(num.toInt());
+  return Uint8List.fromList(buf);
+}
+
+/// Decode a varint of (at most) [bits] width, 64 or 128.
+BigInt decodeVarInt(Bytes bytes, [int bits = 64]) {
+  // Last byte (at `maxShift`) only has remaining bits.
+  final maxShift = (bits - 1) ~/ 7 * 7;
+  final limit = 1 << (bits - maxShift);
+
+  var result = BigInt.zero;
+  var shift = 0;
+
+  while (true) {
+    final byte = bytes.nextByte();
+    if (shift == maxShift && byte >= limit) {
+      throw const ProtocolError('invalid variable-length integer');
+    }
+    if (byte & 0x80 == 0) return result | (BigInt.from(byte) << shift);
+
+    result |= BigInt.from(byte & 0x7F) << shift; // low-order 7 bits of value
+    shift += 7;
+  }
+}
@@ -0,0 +1,32 @@
+import 'dart:typed_data';
+
+import '../lipi/encoder.dart';
+import '../status.dart';
+import '../utils/buffer.dart';
+import 'frame.dart';
+import 'trailer.dart';
+
+Uint8List encodeFrame(void Function(Encode e) f) => encode(f);
+
+Uint8List encodeLastFrame(void Function(Encode e) f) => encode(f, Status.ok);
+
+Uint8List encodeErrorFrame(Status status, [String? reason]) {
+  if (status == Status.ok) throw ArgumentError.value(status, 'status', 'expected error status');
+  return encode((e) => Trailer.$encoder(e, Trailer(reason)), status);
+}
+
+Uint8List encode(void Function(Encode e) encoder, [Status? status]) {
+  final e = Encode();
+  encoder(e);
+  e.pushFront(_encodeHeader(e.len, status));
+  return e.data();
+}
+
+Uint8List _encodeHeader(int len, Status? status) {
+  final lenBytes = lenBE(len);
+
+  final buf = Buffer();
+  buf.writeByte(FrameHeader.create(status: status, lenSize: lenBytes.length).encode());
+  buf.push(lenBytes);
+  return buf.data();
+}
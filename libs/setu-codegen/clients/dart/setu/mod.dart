@@ -0,0 +1,3 @@
+export 'frame.dart';
+export 'frame_writer.dart';
+export 'trailer.dart';
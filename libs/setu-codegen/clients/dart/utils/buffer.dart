@@ -0,0 +1,36 @@
+import 'dart:typed_data';
+
+import 'common.dart';
+
+class Buffer {
+  int _len = 0;
+  final List<List<int>> _data = [];
+
+  void push(List<int> buf) {
+    _len += buf.length;
+    _data.add(buf);
+  }
+
+  void pushFront(List<int> buf) {
+    _len += buf.length;
+    _data.insert(0, buf);
+  }
+
+  void writeByte(int byte) {
+    checkOverflowUint(byte, 8);
+    _len += 1;
+    _data.add([byte]);
+  }
+
+  int get len => _len;
+
+  Uint8List data() {
+    final b = Uint8List(_len);
+    var i = 0;
+    for (final buf in _data) {
+      b.setAll(i, buf);
+      i += buf.length;
+    }
+    return b;
+  }
+}
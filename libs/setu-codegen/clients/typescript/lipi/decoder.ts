@@ -4,7 +4,8 @@ import { decodeVarInt } from "./varint.ts";
 import { DataType } from "./type.ts";
 import { zigzagDecode } from "./zigzag.ts";
 import { bitvecToBools, boolPackedLen } from "../bitset.ts";
-import { skip_field } from "./skip.ts";
+import { skip_field, skip_list_values } from "./skip.ts";
+import type { Result } from "./result.ts";
 
 const { expected } = DataType;
 const UTF8_DECODER = new TextDecoder();
@@ -12,8 +13,8 @@ const UTF8_DECODER = new TextDecoder();
 export type Decoder<T> = (this: Decode) => T;
 export class Deserialize {
     constructor(public buf: Bytes) { }
-    read_varint() {
-        return decodeVarInt(this.buf);
+    read_varint(bits = 64n) {
+        return decodeVarInt(this.buf, bits);
     }
 
     read_len() {
@@ -85,6 +86,10 @@ export class Decode extends Deserialize {
         return this.read_varint();
     }
 
+    U128 = function UInt(this: Decode) {
+        return this.read_varint(128n);
+    }
+
     I16 = function Int(this: Decode) {
         return checkOverflowInt(Number(this.I64()), 16);
     }
@@ -97,6 +102,14 @@ export class Decode extends Deserialize {
         return zigzagDecode(this.read_varint())
     }
 
+    I128 = function Int(this: Decode) {
+        return zigzagDecode(this.read_varint(128n))
+    }
+
+    Char = function UInt(this: Decode) {
+        return String.fromCodePoint(this.U32());
+    }
+
     Str() {
         return UTF8_DECODER.decode(this.read_bytes());
     }
@@ -201,6 +214,61 @@ export class Decode extends Deserialize {
             throw new TypeError(`invalid column id: expected \`0\` or \`1\`, found ${field}`);
         }
     }
+
+    /**
+     * List of structs, encoded as a table with a column per field. (`lipi::Columns`)
+     *
+     * Values of optional fields are wrapped in a struct, as `{0: value}` or `{}`.
+     */
+    Columns<const Fields extends readonly StructField[]>(fields: Fields) {
+        return ColumnsDecoder(this, fields, () => ({}) as Struct<Fields>);
+    }
+
+    /** Same as `Columns`, but rows are tuple structs, decoded as arrays. */
+    TupleColumns<const Fields extends readonly StructField[]>(fields: Fields) {
+        return ColumnsDecoder(this, fields, () => [] as unknown as Struct<Fields>);
+    }
+
+    /** Struct of named fields, e.g. payload of a struct variant. */
+    Struct<const Fields extends readonly StructField[]>(fields: Fields) {
+        let self = this;
+        return function Struct(): Struct<Fields> {
+            return StructDecoder(self, fields);
+        }
+    }
+
+    Tuple<const Fields extends readonly TupleField[]>(fields: Fields) {
+        let self = this;
+        return function Struct(): Tuple<Fields> {
+            return TupleDecoder(self, fields);
+        }
+    }
+
+    /** `required` is `false`, if `Ok` value is optional. */
+    Result<T, E>(ok: Decoder<T>, err: Decoder<E>, required: boolean) {
+        let self = this;
+        return function Struct(): Result<T, E> {
+            let value: T | undefined;
+            let error: E | undefined;
+
+            let header: [number, DataType] | undefined;
+            while (header = self.next_field_id_and_ty()) {
+                let [id, ty] = header;
+                if (id == 0) value = fieldValue(self, ok, ty);
+                else if (id == 1) error = fieldValue(self, err, ty);
+                else skip_field(self, id, ty);
+            }
+
+            if (error !== undefined) {
+                assert(value === undefined, TypeError, "result has both `Ok` and `Err`");
+                return { ok: false, error };
+            }
+            if (required && value === undefined) {
+                throw new Error(`missing required field: 'Ok' as 0; type: ${ok.name}`);
+            }
+            return { ok: true, value: value as T };
+        }
+    }
 }
 
 function ListType<T, N>(de: Decoder<N>, from: (_: { length: number }, map: () => N) => T) {
@@ -239,6 +307,51 @@ type Struct<Fields extends readonly StructField[]> =
     & { [Field in Fields[number]as Field[3] extends 0 ? Field[1] : never]?: ReturnType<Field[2]> }
 
 
+function ColumnsDecoder<const Fields extends readonly StructField[]>(self: Decode, fields: Fields, row: () => Struct<Fields>) {
+    return function Table(): Array<Struct<Fields>> {
+        let columnCount = self.read_len();
+        let length = self.read_len();
+        let rows = Array.from({ length }, row);
+
+        for (let i = 0; i < columnCount; i++) {
+            let [id, ty] = self.read_field_id_and_ty();
+
+            let field = fields.find(([field_id]) => field_id === id);
+            if (field === undefined || length == 0) {
+                skip_list_values(self, length, ty);
+                continue;
+            }
+
+            let [_, name, decoder, isRequired] = field;
+            if (!isRequired) {
+                expected(DataType.Struct, ty);
+                for (let row of rows) {
+                    let header: [number, DataType] | undefined;
+                    while (header = self.next_field_id_and_ty()) {
+                        let [key, ty] = header;
+                        if (key == 0) (row as any)[name] = fieldValue(self, decoder, ty);
+                        else skip_field(self, key, ty);
+                    }
+                }
+            } else if (decoder.name == "Bool") {
+                expected(DataType.True, ty);
+                let bools = bitvecToBools(self.buf.take(boolPackedLen(length)), length);
+                rows.forEach((row, index) => (row as any)[name] = bools[index]);
+            } else {
+                expectedTy(decoder, ty);
+                for (let row of rows) (row as any)[name] = decoder.call(self);
+            }
+        }
+
+        for (let [id, name, de, isRequired] of fields) {
+            if (isRequired && length > 0 && !(name in rows[0])) {
+                throw new Error(`missing required field: '${name}' as ${id}; type: ${de.name}`);
+            }
+        }
+        return rows;
+    }
+}
+
 export function StructDecoder<const Fields extends readonly StructField[]>(self: Decode, fields: Fields) {
     let struct = {} as Struct<Fields>;
 
@@ -274,15 +387,62 @@ export function StructDecoder<const Fields extends readonly StructField[]>(self:
 
 // ================================================================================
 
+type TupleField = readonly [
+    id: number,
+    decoder: Decoder<unknown>,
+    required: 1 | 0
+];
+
+type Tuple<Fields extends readonly TupleField[]> = {
+    -readonly [Index in keyof Fields]: Fields[Index][2] extends 1
+    ? ReturnType<Fields[Index][1]>
+    : ReturnType<Fields[Index][1]> | undefined
+};
+
+/** Tuple items are decoded from struct fields, by the key of their position. */
+export function TupleDecoder<const Fields extends readonly TupleField[]>(self: Decode, fields: Fields) {
+    let tuple: unknown[] = new Array(fields.length);
+
+    let header: [number, DataType] | undefined;
+    while (header = self.next_field_id_and_ty()) {
+        let [id, ty] = header;
+
+        let index = fields.findIndex(([field_id]) => field_id === id);
+        if (index == -1) {
+            skip_field(self, id, ty);
+            continue;
+        }
+        tuple[index] = fieldValue(self, fields[index][1], ty);
+    }
+
+    fields.forEach(([id, de, isRequired], index) => {
+        if (isRequired && tuple[index] === undefined) {
+            throw new Error(`missing required field: '${index}' as ${id}; type: ${de.name}`);
+        }
+    });
+
+    return tuple as Tuple<Fields>;
+}
+
+// ================================================================================
+
+/**
+ * `kind` of a variant payload:
+ * - `0`: unit variant, without payload.
+ * - `1`: payload as `value`.
+ * - `2`: struct payload, with fields inlined next to `type`.
+ */
 type EnumItem = readonly [
     id: number,
     type: string,
     decoder: Decoder<unknown>,
-    required: 1 | 0
+    kind: 0 | 1 | 2
 ];
 
 type Enum<Items extends readonly EnumItem[]> = {
-    [Index in keyof Items]: Items[Index][3] extends 1
+    [Index in keyof Items]: Items[Index][3] extends 2
+    ? { type: Items[Index][1] } & ReturnType<Items[Index][2]>
+    : Items[Index][3] extends 1
     ? { type: Items[Index][1]; value: ReturnType<Items[Index][2]> }
     : { type: Items[Index][1] }
 }[number];
@@ -293,19 +453,18 @@ export function EnumDecoder<const Items extends readonly EnumItem[]>(self: Decod
     let item = items.find(([item_id]) => item_id == id);
     assert(item !== undefined);
 
-    let [_, type, decoder, required] = item;
+    let [_, type, decoder, kind] = item;
 
-    if (!required) {
+    if (kind == 0) {
         skip_field(self, id, ty);
         return { type } as Enum<Items>;
     }
 
-    if (decoder.name == "Bool") {
-        return { type, value: DataType.asBool(ty) } as Enum<Items>
-    } else {
-        expectedTy(decoder, ty);
-        return { type, value: decoder.call(self) } as Enum<Items>
+    let value = fieldValue(self, decoder, ty);
+    if (kind == 2) {
+        return { type, ...value as object } as Enum<Items>;
     }
+    return { type, value } as Enum<Items>;
 }
 
 // ================================================================================
@@ -346,3 +505,10 @@ function view(data: Uint8Array) {
 function expectedTy<T>(f: Decoder<T>, ty: DataType) {
     expected(DataType.fromStr(f.name), ty);
 }
+
+/** Decodes value of a field, whose header is already read. */
+function fieldValue<T>(self: Decode, decoder: Decoder<T>, ty: DataType): T {
+    if (decoder.name == "Bool") return DataType.asBool(ty) as T;
+    expectedTy(decoder, ty);
+    return decoder.call(self);
+}
@@ -2,8 +2,9 @@ import { DataType } from "./type.ts";
 import { encodeVarInt } from "./varint.ts";
 import { zigzagEncode } from "./zigzag.ts";
 import { Buffer } from "../utils/buffer.ts";
-import { assert, checkOverflowInt, checkOverflowUint, IS_LITTLE_ENDIAN } from "../utils/common.ts";
+import { assert, checkOverflowBigInt, checkOverflowBigUint, checkOverflowInt, checkOverflowUint, IS_LITTLE_ENDIAN } from "../utils/common.ts";
 import { bitvecFrom } from "../bitset.ts";
+import type { Result } from "./result.ts";
 
 const UTF8_ENCODER = new TextEncoder();
 
@@ -75,6 +76,10 @@ export class Encode extends Writer {
         this.writeVarint(num)
     }
 
+    U128 = function UInt(this: Encode, num: number | bigint) {
+        this.writeVarint(checkOverflowBigUint(BigInt(num), 128n))
+    }
+
     I16 = function Int(this: Encode, num: number) {
         this.I64(checkOverflowInt(num, 16))
     }
@@ -87,6 +92,17 @@ export class Encode extends Writer {
         this.writeVarint(zigzagEncode(BigInt(num)));
     }
 
+    I128 = function Int(this: Encode, num: number | bigint) {
+        this.writeVarint(zigzagEncode(checkOverflowBigInt(BigInt(num), 128n), 128n));
+    }
+
+    /** Single character (unicode scalar value) string. */
+    Char = function UInt(this: Encode, char: string) {
+        let code = char.codePointAt(0);
+        assert(code !== undefined && String.fromCodePoint(code) === char, TypeError, () => `expected a single character, got: ${JSON.stringify(char)}`);
+        this.writeVarint(code);
+    }
+
     Str(text: string) {
         this.writeBytes(UTF8_ENCODER.encode(text));
     }
@@ -147,6 +163,68 @@ export class Encode extends Writer {
             for (let val of map.values()) v.call(self, val);
         }
     }
+
+    /**
+     * List of structs, encoded as a table with a column per field. (`lipi::Columns`)
+     *
+     * Values of optional fields are wrapped in a struct, as `{0: value}` or `{}`.
+     */
+    Columns(fields: readonly ColumnField[]) {
+        let self = this;
+        return function Table(rows: ReadonlyArray<object>) {
+            self.writeVarint(fields.length); // Column count
+            self.writeVarint(rows.length); // Row count
+
+            for (let [id, name, encoder, isRequired] of fields) {
+                let values = rows.map(row => (row as Record<string, unknown>)[name]);
+                if (!isRequired) {
+                    self.write_field_id_and_ty(id, DataType.Struct);
+                    for (let val of values) StructEncoder(self, [[0, val, encoder]]);
+                } else if (encoder.name == "Bool") {
+                    self.write_field_id_and_ty(id, DataType.True);
+                    self.push(bitvecFrom(values as Array<boolean>).asBytes());
+                } else {
+                    self.write_field_id_and_ty(id, DataType.fromStr(encoder.name));
+                    for (let val of values) encoder.call(self, val);
+                }
+            }
+        }
+    }
+
+    /** Same as `Columns`, rows are tuple structs, whose items are indexed by their field names. */
+    TupleColumns(fields: readonly ColumnField[]) {
+        return this.Columns(fields);
+    }
+
+    /** Struct of named fields, e.g. payload of a struct variant. */
+    Struct(fields: readonly StructField[]) {
+        let self = this;
+        return function Struct(struct: { readonly [name: string]: unknown }) {
+            for (let [id, name, encoder] of fields) {
+                FieldEncoder(self, [id, struct[name], encoder]);
+            }
+            self.writeByte(DataType.StructEnd);
+        }
+    }
+
+    Tuple(fields: readonly TupleField[]) {
+        let self = this;
+        return function Struct(tuple: readonly unknown[]) {
+            TupleEncoder(self, tuple, fields);
+        }
+    }
+
+    Result<T, E>(ok: Encoder<T>, err: Encoder<E>) {
+        let self = this;
+        return function Struct(result: Result<T, E>) {
+            if (result.ok) {
+                FieldEncoder(self, [0, result.value, ok]);
+            } else {
+                FieldEncoder(self, [1, result.error, err]);
+            }
+            self.writeByte(DataType.StructEnd);
+        }
+    }
 }
 
 // ================================================================================
@@ -166,6 +244,18 @@ export function StructEncoder<const T extends readonly any[]>(self: Encode, fiel
     self.writeByte(DataType.StructEnd);
 }
 
+type StructField = readonly [id: number, name: string, encoder: Encoder<any>];
+
+type ColumnField = readonly [id: number, name: string, encoder: Encoder<any>, required: 1 | 0];
+
+type TupleField = readonly [id: number, encoder: Encoder<any>];
+
+/** Tuple items are encoded as struct fields, with the key of their position. */
+export function TupleEncoder(self: Encode, tuple: readonly unknown[], fields: readonly TupleField[]) {
+    fields.forEach(([id, encoder], index) => FieldEncoder(self, [id, tuple[index], encoder]));
+    self.writeByte(DataType.StructEnd);
+}
+
 export function FieldEncoder<T>(self: Encode, [id, val, encoder]: Field<T>) {
     if (val === undefined) return;
     if (encoder.name == "Bool") {
@@ -1,6 +1,7 @@
 
 export * from "./decoder.ts"
 export * from "./encoder.ts"
+export * from "./result.ts"
 export * from "./varint.ts"
 export * from "./zigzag.ts"
 
@@ -0,0 +1,16 @@
+/** `Result<T, E>` of rust, encoded as struct with field `0` as `Ok`, or `1` as `Err`. */
+export type Result<T, E> =
+    | { ok: true; value: T }
+    | { ok: false; error: E };
+
+/** Thrown by `unwrap`, holds the `Err` value of a result. */
+export class ResultError<E> extends Error {
+    constructor(public error: E) {
+        super("rpc returned an error result");
+    }
+}
+
+export function unwrap<T, E>(result: Result<T, E>): T {
+    if (result.ok) return result.value;
+    throw new ResultError(result.error);
+}
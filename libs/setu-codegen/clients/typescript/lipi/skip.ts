@@ -18,7 +18,7 @@ export function skip_field_value(self: Deserialize, ty: DataType) {
         case DataType.U8: case DataType.I8: self.buf.nextByte(); break;
         case DataType.F32: self.buf.take(4); break;
         case DataType.F64: self.buf.take(8); break;
-        case DataType.UInt: case DataType.Int: self.read_varint(); break;
+        case DataType.UInt: case DataType.Int: self.read_varint(128n); break;
 
         case DataType.Str: case DataType.UnknownI: case DataType.UnknownII:
             self.read_bytes(); break;
@@ -62,7 +62,7 @@ export function skip_list_values(self: Deserialize, len: number, ty: DataType) {
         case DataType.F32: self.buf.take(len * 4); break;
         case DataType.F64: self.buf.take(len * 8); break;
         case DataType.UInt: case DataType.Int:
-            while (len--) self.read_varint(); break;
+            while (len--) self.read_varint(128n); break;
 
         case DataType.Str: case DataType.UnknownI: case DataType.UnknownII:
             while (len--) self.read_bytes(); break;
@@ -14,13 +14,18 @@ export function encodeVarInt(num: bigint | number) {
     return new Uint8Array(buf);
 }
 
-export function decodeVarInt(bytes: Bytes): bigint {
+/** Decode a varint of (at most) `bits` width, 64 or 128 */
+export function decodeVarInt(bytes: Bytes, bits = 64n): bigint {
+    // Last byte (at `maxShift`) only has remaining bits.
+    const maxShift = (bits - 1n) / 7n * 7n;
+    const limit = 1n << (bits - maxShift);
+
     let result = 0n;
     let shift = 0n;
 
     while (true) {
         let byte = BigInt(bytes.nextByte());
-        if (shift == 63n && byte >= 2) throw new Error("invalid variable-length integer");
+        if (shift == maxShift && byte >= limit) throw new Error("invalid variable-length integer");
 
         if ((byte & 0b1000_0000n) == 0n) return result | (byte << shift);
 
@@ -1,5 +1,5 @@
-export function zigzagEncode(num: bigint): bigint {
-  return (num << 1n) ^ (num >> 63n);
+export function zigzagEncode(num: bigint, bits = 64n): bigint {
+  return (num << 1n) ^ (num >> (bits - 1n));
 }
 
 export function zigzagDecode(num: bigint): bigint {
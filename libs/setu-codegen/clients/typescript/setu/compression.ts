@@ -0,0 +1,24 @@
+/** Encodings supported by `CompressionStream`, in order of preference. */
+export const ENCODINGS = ["gzip", "deflate"] as const;
+
+export type Encoding = typeof ENCODINGS[number];
+
+/** Messages smaller than this (in bytes) are sent uncompressed. */
+export const THRESHOLD = 1024;
+
+export function isEncoding(name: string): name is Encoding {
+    return (ENCODINGS as readonly string[]).includes(name);
+}
+
+export function compress(encoding: Encoding, data: Uint8Array) {
+    return pipe(data, new CompressionStream(encoding));
+}
+
+export function decompress(encoding: Encoding, data: Uint8Array) {
+    return pipe(data, new DecompressionStream(encoding));
+}
+
+async function pipe(data: Uint8Array, transform: ReadableWritablePair<Uint8Array, BufferSource>) {
+    let stream = new Blob([data as BufferSource]).stream().pipeThrough(transform);
+    return new Uint8Array(await new Response(stream).arrayBuffer());
+}
@@ -1,6 +1,8 @@
 import { Status } from "../status.ts";
+import { ProtocolError } from "../errors.ts";
 import { assert } from "../utils/common.ts";
-import { StreamReader } from "../utils/stream.ts";
+import { Stream, StreamReader } from "../utils/stream.ts";
+import { decompress, Encoding } from "./compression.ts";
 
 export class MaybeCompressed<T> {
     constructor(
@@ -83,11 +85,21 @@ export class LenBE {
 }
 
 export class FrameDecoder extends StreamReader {
+    /** `encoding`: Negotiated encoding of compressed frames (`rpc-encoding` header). */
+    constructor(stream: Stream, public encoding: Encoding | null = null) {
+        super(stream);
+    }
+
     async parseFrame(): Promise<MaybeCompressed<Frame>> {
         let header = FrameHeader.parse(await this.readByte());
         let len = await this.parseLenBigEndian(header.lenSize);
         let bytes = await this.readBytes(len);
 
+        if (header.isCompressed) {
+            assert(this.encoding, ProtocolError, "received compressed frame, without `rpc-encoding`");
+            bytes = await decompress(this.encoding, bytes);
+        }
+
         return new MaybeCompressed(
             header.isCompressed,
             header.isTrailer
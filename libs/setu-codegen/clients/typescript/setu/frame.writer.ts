@@ -4,6 +4,7 @@ import { Buffer } from "../utils/buffer.ts";
 import { assert } from "../utils/common.ts";
 import { Trailer } from "./trailer.ts";
 import { FrameHeader, LenBE } from "./frame.ts";
+import { compress, Encoding, THRESHOLD } from "./compression.ts";
 
 export function encodeFrame(f: (_: Encode) => void) {
     return encode(f)
@@ -25,11 +26,29 @@ export function encode(encoder: (_: Encode) => void, status?: Status) {
     return e.data();
 }
 
-function encodeHeader(len: number, status?: Status) {
+/**
+ * Same as `encode`, but message is compressed with `encoding`,
+ * if it's larger than `THRESHOLD` bytes.
+ */
+export async function encodeCompressed(encoding: Encoding | undefined, encoder: (_: Encode) => void, status?: Status) {
+    let e = new Encode();
+    encoder(e);
+    if (!encoding || e.len < THRESHOLD) {
+        e.pushFront(encodeHeader(e.len, status));
+        return e.data();
+    }
+    let msg = await compress(encoding, e.data());
+    let buf = new Buffer();
+    buf.push(encodeHeader(msg.length, status, true));
+    buf.push(msg);
+    return buf.data();
+}
+
+function encodeHeader(len: number, status?: Status, isCompressed?: boolean) {
     let len_be = new LenBE(len);
 
     let buf = new Buffer();
-    buf.writeByte(FrameHeader.new({ status, lenSize: len_be.size }).encode());
+    buf.writeByte(FrameHeader.new({ status, isCompressed, lenSize: len_be.size }).encode());
     buf.push(len_be.asBytes());
     return buf.data()
 }
\ No newline at end of file
@@ -1,3 +1,4 @@
+export * from "./compression.ts"
 export * from "./frame.ts"
 export * from "./frame.writer.ts"
 export * from "./trailer.ts"
\ No newline at end of file
@@ -5,32 +5,45 @@ import { assert } from "../utils/common.ts";
 import { MPSC } from "../utils/mpsc.ts";
 import { Decode } from "../lipi/decoder.ts";
 import { Encode } from "../lipi/encoder.ts";
-import { encodeErrorFrame, encodeFrame, encodeLastFrame } from "../setu/frame.writer.ts";
+import { encodeCompressed, encodeErrorFrame } from "../setu/frame.writer.ts";
+import { Encoding, ENCODINGS, isEncoding } from "../setu/compression.ts";
 import { Status } from "../status.ts";
 
+export interface RpcResponse {
+    body: ReadableStream<Uint8Array>,
+    /** Encoding of compressed frames (`rpc-encoding` header) */
+    encoding: Encoding | null,
+}
+
 export class RPC {
     static URL = new URL("/", "https://localhost:443");
     static TIMEOUT = Timeout.minute(2);
 
     static async call(
         id: number,
-        body: BodyInit,
+        body: BodyInit | Promise<BodyInit>,
         conn: AbortController,
         timeout: Timeout | null = RPC.TIMEOUT,
-        url: URL = RPC.URL
-    ) {
+        url: URL = RPC.URL,
+        encoding?: Encoding,
+    ): Promise<RpcResponse> {
         let headers: HeadersInit = {
             "content-type": "application/setu",
             "rpc-id": id.toString(),
+            "rpc-accept-encoding": ENCODINGS.join(", "),
         };
 
+        if (encoding) {
+            headers["rpc-encoding"] = encoding;
+        }
+
         let timer;
         if (timeout) {
             timer = setTimeout(() => conn.abort(), timeout.duration());
             headers["rpc-timeout"] = timeout.toString();
         }
 
-        let res = await fetch(url, { method: "POST", headers, body, signal: conn.signal });
+        let res = await fetch(url, { method: "POST", headers, body: await body, signal: conn.signal });
 
         clearTimeout(timer);
 
@@ -42,38 +55,43 @@ export class RPC {
 
         assert(contentType == "application/setu", ProtocolError, () => `unexpected content-type: ${contentType ?? "none"}`);
         assert(res.body, ProtocolError, "No response body");
-        return res.body;
+
+        let rpcEncoding = res.headers.get("rpc-encoding");
+        assert(rpcEncoding == null || isEncoding(rpcEncoding), ProtocolError, () => `unsupported rpc-encoding: ${rpcEncoding}`);
+        return { body: res.body, encoding: rpcEncoding };
     }
 }
 
 export interface Context {
     url?: URL,
-    timeout?: Timeout | null
+    timeout?: Timeout | null,
+    /** Compress request messages, larger than `THRESHOLD` bytes. */
+    encoding?: Encoding,
 }
 
 export function rpc<T>(
-    id: number, { timeout, url }: Context,
+    id: number, { timeout, url, encoding }: Context,
     input: (_: Encode) => void,
     output: (_: Decode) => T
 ): Output<T> {
     let conn = new AbortController();
-    let body = encodeLastFrame(input);
-    return Output(conn, RPC.call(id, body, conn, timeout, url), output);
+    let body = encodeCompressed(encoding, input, Status.Ok);
+    return Output(conn, RPC.call(id, body, conn, timeout, url, encoding), output);
 }
 
 export function sse<T, R>(
-    id: number, { timeout, url }: Context,
+    id: number, { timeout, url, encoding }: Context,
     input: (_: Encode) => void,
     yielder: (_: Decode) => T,
     output: (_: Decode) => R,
 ): SSE<T, R> {
     let conn = new AbortController();
-    let body = encodeLastFrame(input);
-    return SSE(conn, RPC.call(id, body, conn, timeout, url), yielder, output);
+    let body = encodeCompressed(encoding, input, Status.Ok);
+    return SSE(conn, RPC.call(id, body, conn, timeout, url, encoding), yielder, output);
 }
 
 export async function uni<T, R, O>(
-    id: number, { timeout, url }: Context,
+    id: number, { timeout, url, encoding }: Context,
     input: (_: Encode) => void,
     send: (_: Encode, z: T) => void,
     final: (_: Encode, z: R) => void,
@@ -82,35 +100,67 @@ export async function uni<T, R, O>(
     let conn = new AbortController();
     let writer = new MPSC<Uint8Array>();
 
-    await writer.send(encodeFrame(input));
+    await writer.send(await encodeCompressed(encoding, input));
 
-    let rpc = Output(conn, RPC.call(id, writer.stream, conn, timeout, url), output);
+    let rpc = Output(conn, RPC.call(id, writer.stream, conn, timeout, url, encoding), output);
 
+    return {
+        ...Sender(writer, encoding, send, final),
+        cancle() {
+            writer.close();
+            rpc.cancle();
+        },
+        async output() {
+            return await rpc;
+        }
+    }
+}
+
+export async function bi<T, R, Y, Z>(
+    id: number, { timeout, url, encoding }: Context,
+    input: (_: Encode) => void,
+    send: (_: Encode, z: T) => void,
+    final: (_: Encode, z: R) => void,
+    yielder: (_: Decode) => Y,
+    output: (_: Decode) => Z,
+) {
+    let conn = new AbortController();
+    let writer = new MPSC<Uint8Array>();
+
+    await writer.send(await encodeCompressed(encoding, input));
+
+    let stream = SSE(conn, RPC.call(id, writer.stream, conn, timeout, url, encoding), yielder, output);
+    let cancle = stream.cancle;
+
+    return Object.assign(stream, Sender(writer, encoding, send, final), {
+        cancle(reason?: any) {
+            writer.close();
+            cancle(reason);
+        },
+    });
+}
+
+function Sender<T, R>(
+    writer: MPSC<Uint8Array>,
+    encoding: Encoding | undefined,
+    send: (_: Encode, z: T) => void,
+    final: (_: Encode, z: R) => void,
+) {
     return {
         [Symbol.dispose]() {
             writer.close();
         },
 
-        send(value: T) {
-            return writer.send(encodeFrame(e => send(e, value)));
+        async send(value: T) {
+            return writer.send(await encodeCompressed(encoding, e => send(e, value)));
         },
         async sendFinal(value: R) {
-            await writer.send(encodeLastFrame(e => final(e, value)));
+            await writer.send(await encodeCompressed(encoding, e => final(e, value), Status.Ok));
             writer.close();
         },
         async sendError(status: Status, reason?: string) {
             await writer.send(encodeErrorFrame(status, reason));
             writer.close();
         },
-
-        cancle() {
-            writer.close();
-            rpc.cancle();
-        },
-        async output() {
-            return await rpc;
-        }
     }
 }
-
-export function bi() { }
\ No newline at end of file
@@ -4,6 +4,7 @@ import { Status } from "../status.ts";
 import { Bytes } from "../utils/bytes.ts";
 import { assert } from "../utils/common.ts";
 import { Stream } from "../utils/stream.ts";
+import type { RpcResponse } from "./http.ts";
 
 export interface Output<T> extends Promise<T> {
     cancle(reason?: any): void;
@@ -11,7 +12,7 @@ export interface Output<T> extends Promise<T> {
 
 export function Output<T>(
     connection: AbortController,
-    body: Promise<ReadableStream<Uint8Array>>,
+    response: Promise<RpcResponse>,
     decoder: (_: Decode) => T
 ) {
     let fut = Promise.withResolvers<T>();
@@ -28,14 +29,14 @@ export function Output<T>(
 
     (async () => {
         try {
-            let res = await body;
+            let { body: res, encoding } = await response;
             if (canceled) {
                 res.cancel(canceled.reason);
                 return fut.reject(canceled.reason);
             }
 
             stream = new Stream(res.getReader());
-            let reader = new FrameDecoder(stream);
+            let reader = new FrameDecoder(stream, encoding);
 
             let { data } = await reader.parseFrame();
             assert(data.type == "trailer", Error, `expected trailer`);
@@ -59,7 +60,7 @@ export interface SSE<T, R> extends AsyncGenerator<T> {
 
 export function SSE<T, R>(
     connection: AbortController,
-    body: Promise<ReadableStream<Uint8Array>>,
+    response: Promise<RpcResponse>,
     yielder: (_: Decode) => T,
     output: (_: Decode) => R,
 ): SSE<T, R> {
@@ -69,7 +70,7 @@ export function SSE<T, R>(
 
     let asyncIter = (async function* () {
         try {
-            let res = await body;
+            let { body: res, encoding } = await response;
             if (canceled) {
                 res.cancel(canceled.reason);
                 connection.abort(canceled.reason);
@@ -77,7 +78,7 @@ export function SSE<T, R>(
             }
 
             stream = new Stream(res.getReader());
-            let reader = new FrameDecoder(stream);
+            let reader = new FrameDecoder(stream, encoding);
 
             while (true) {
                 let { data } = await reader.parseFrame();
@@ -33,6 +33,25 @@ export function checkOverflowUint(num: number, bit: number) {
     return num;
 }
 
+export function checkOverflowBigInt(num: bigint, bit: bigint) {
+    const max = (1n << (bit - 1n)) - 1n;
+    const min = -(1n << (bit - 1n));
+
+    if (num < min || num > max) {
+        throw new RangeError(`Int${bit} overflow: ${num}`);
+    }
+
+    return num;
+}
+
+export function checkOverflowBigUint(num: bigint, bit: bigint) {
+    const max = (1n << bit) - 1n;
+    if (num < 0n || num > max) {
+        throw new RangeError(`Uint${bit} overflow: ${num}`);
+    }
+    return num;
+}
+
 function isLittleEndian() {
     const buf = new ArrayBuffer(4);
     new Uint32Array(buf)[0] = 0x11_22_33_44;
@@ -0,0 +1,137 @@
+use setu_type_info::{FnMetaData, FnOutputTy, Func, GeneratorType};
+use std::format_args as args;
+use type_id::Type;
+
+use super::{Codec, fmt, ident, write_docs};
+use crate::{CodeWriter, Context};
+
+pub fn generate(c: &mut CodeWriter, ctx: &Context, client_name: &str) {
+    c.newline();
+    c.block(args!("final class {client_name}"), |c| {
+        c.line("final $.Client client;");
+        c.line(args!("const {client_name}(this.client);"));
+
+        for Func {
+            meta,
+            input_ty,
+            output_ty,
+            stream,
+        } in &ctx.info.fns
+        {
+            let FnMetaData {
+                docs,
+                index,
+                ident: name,
+                args,
+            } = meta;
+
+            let fn_args = fmt(|f| {
+                for (name, ty) in args.iter().zip(input_ty) {
+                    f.write_fmt(args!("{} {}, ", ctx.dart_ty(ty), ident(name)))?;
+                }
+                Ok(())
+            });
+
+            let (method, output) = match (output_ty, stream) {
+                (FnOutputTy::Return(ty), None) => {
+                    ("rpc", format!("Future<{}>", ctx.dart_output_ty(ty)))
+                }
+                (
+                    FnOutputTy::Generator(GeneratorType {
+                        yield_ty,
+                        return_ty,
+                    }),
+                    None,
+                ) => (
+                    "sse",
+                    format!(
+                        "$.Sse<{}, {}>",
+                        ctx.dart_output_ty(yield_ty),
+                        ctx.dart_output_ty(return_ty)
+                    ),
+                ),
+                (FnOutputTy::Return(ty), Some(s)) => (
+                    "uni",
+                    format!(
+                        "$.Uni<{}, {}, {}>",
+                        ctx.dart_output_ty(&s.yield_ty),
+                        ctx.dart_output_ty(&s.return_ty),
+                        ctx.dart_output_ty(ty)
+                    ),
+                ),
+                (FnOutputTy::Generator(g), Some(s)) => (
+                    "bidi",
+                    format!(
+                        "$.Bidi<{}, {}, {}, {}>",
+                        ctx.dart_output_ty(&s.yield_ty),
+                        ctx.dart_output_ty(&s.return_ty),
+                        ctx.dart_output_ty(&g.yield_ty),
+                        ctx.dart_output_ty(&g.return_ty)
+                    ),
+                ),
+            };
+
+            c.newline();
+            write_docs(c, docs);
+            c.block(
+                args!("{output} {name}({fn_args}[$.Context ctx = const $.Context()])"),
+                |c| {
+                    c.line(args!("return client.{method}("));
+                    c.scope(|c| {
+                        c.line(args!("{index}, ctx,"));
+                        input_encoder(ctx, c, input_ty, args);
+                        if let Some(s) = stream {
+                            message_encoder(ctx, c, &s.yield_ty);
+                            message_encoder(ctx, c, &s.return_ty);
+                        }
+                        match output_ty {
+                            FnOutputTy::Return(ty) => output_decoder(ctx, c, ty),
+                            FnOutputTy::Generator(g) => {
+                                output_decoder(ctx, c, &g.yield_ty);
+                                output_decoder(ctx, c, &g.return_ty);
+                            }
+                        }
+                    });
+                    c.line(");");
+                },
+            );
+        }
+    });
+}
+
+fn input_encoder(ctx: &Context, c: &mut CodeWriter, input_ty: &[Type], args: &[Box<str>]) {
+    let args = args.iter().zip(input_ty);
+    if args.len() == 0 {
+        return c.line("($e) => $.structEncoder($e, []),");
+    }
+    c.line("($e) => $.structEncoder($e, [");
+    ctx.dart_struct_encoder(
+        c,
+        args.enumerate()
+            .map(|(key, (name, ty))| (ident(name), ty, key as u32)),
+    );
+    c.line("]),");
+}
+
+/// Messages are encoded as field `0`.
+fn message_encoder(ctx: &Context, c: &mut CodeWriter, ty: &Type) {
+    if matches!(ty, Type::Tuple(tys) if tys.is_empty()) {
+        return c.line("($e, z) => $.structEncoder($e, []),");
+    }
+    let encoder = ctx.dart_codec(ty, Codec::Encoder);
+    c.line(args!(
+        "($e, z) => $.structEncoder($e, [$.Field(0, z, {encoder})]),"
+    ));
+}
+
+fn output_decoder(ctx: &Context, c: &mut CodeWriter, ty: &Type) {
+    if matches!(ty, Type::Tuple(tys) if tys.is_empty()) {
+        return c.line("($d) {},");
+    }
+    let decoder = ctx.dart_codec(ty, Codec::Decoder);
+    if ty.optional().is_some() {
+        c.line(args!("($d) => $.outputDecoder($d, {decoder}, false),"));
+    } else {
+        c.line(args!("($d) => $.outputDecoder($d, {decoder}, true)!,"));
+    }
+}
@@ -0,0 +1,385 @@
+pub mod client;
+pub mod types;
+
+use std::fmt::Display;
+use std::format_args as args;
+use std::{fs, io, path::PathBuf};
+use type_id::{Discriminant, ListVariant, StructField, Type};
+
+use crate::{CodeWriter, Context, invalid_input, utils::copy_dir, utils::fmt};
+pub use std::fmt::from_fn as fmt;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub out_dir: PathBuf,
+    /// Name of the generated client class. (Default: `App`)
+    pub client_name: String,
+}
+
+impl Config {
+    pub fn out_dir(path: PathBuf) -> Self {
+        Self {
+            out_dir: path,
+            client_name: "App".into(),
+        }
+    }
+
+    pub fn client_name(mut self, name: impl Into<String>) -> Self {
+        self.client_name = name.into();
+        self
+    }
+}
+
+impl Config {
+    pub fn generate(&self, ctx: &Context) -> io::Result<()> {
+        fs::create_dir_all(&self.out_dir)?;
+
+        let lib = self.out_dir.join("lib");
+        if !lib.exists() {
+            let src = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("clients/dart");
+
+            copy_dir(&src, &lib, 1, |file| {
+                file.extension().is_some_and(|ext| ext == "dart")
+            })?;
+        }
+
+        let code = ctx.generate_dart_code(&self.client_name)?;
+        fs::write(self.out_dir.join("mod.dart"), code)
+    }
+}
+
+static DART_PRELUDE: &str = r#"// AUTO-GENERATED FILE. DO NOT EDIT.
+// ignore_for_file: type=lint, unused_import
+import 'dart:typed_data';
+
+import 'lib/mod.dart' as $;
+export 'lib/mod.dart' show AbortController, Client, Context, Err, Ok, Result, Status, StatusError, Timeout;
+"#;
+
+#[derive(Clone, Copy)]
+enum Codec {
+    Encoder,
+    Decoder,
+}
+
+impl Codec {
+    fn runtime(self) -> &'static str {
+        match self {
+            Codec::Encoder => "$.Encoders",
+            Codec::Decoder => "$.Decoders",
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Codec::Encoder => "$.Encoder",
+            Codec::Decoder => "$.Decoder",
+        }
+    }
+
+    fn member(self) -> &'static str {
+        match self {
+            Codec::Encoder => "$encoder",
+            Codec::Decoder => "$decoder",
+        }
+    }
+}
+
+impl Context {
+    /// Fails with [io::ErrorKind::InvalidInput], naming the first type that can't be generated.
+    pub fn generate_dart_code(&self, client_name: &str) -> io::Result<String> {
+        self.check_types().map_err(invalid_input)?;
+
+        let mut c = CodeWriter::new();
+        c.buffer.push_str(DART_PRELUDE);
+        types::generate(&mut c, self);
+        client::generate(&mut c, self, client_name);
+        Ok(c.buffer)
+    }
+
+    fn dart_ty(&self, ty: &Type) -> fmt!(type) {
+        fmt(|f| match ty {
+            Type::U8 | Type::U16 | Type::U32 | Type::I8 | Type::I16 | Type::I32 => {
+                f.write_str("int")
+            }
+            Type::F32 | Type::F64 => f.write_str("double"),
+            Type::U64 | Type::I64 | Type::U128 | Type::I128 => f.write_str("BigInt"),
+
+            Type::Bool => f.write_str("bool"),
+            Type::String | Type::Char => f.write_str("String"),
+
+            Type::Complex(path) => f.write_str(&self.symbol.interface_name(path)),
+
+            Type::List { ty, .. } | Type::Array { ty, .. } => match ty.as_ref() {
+                Type::U8 => f.write_str("Uint8List"),
+                Type::U16 => f.write_str("Uint16List"),
+                Type::U32 => f.write_str("Uint32List"),
+
+                Type::I8 => f.write_str("Int8List"),
+                Type::I16 => f.write_str("Int16List"),
+                Type::I32 => f.write_str("Int32List"),
+
+                Type::F32 => f.write_str("Float32List"),
+                Type::F64 => f.write_str("Float64List"),
+
+                _ => f.write_fmt(args!("List<{}>", self.dart_ty(ty))),
+            },
+            Type::Map { ty, .. } => f.write_fmt(args!(
+                "Map<{}, {}>",
+                self.dart_ty(&ty.0),
+                self.dart_ty(&ty.1)
+            )),
+
+            Type::Tuple(tys) => {
+                f.write_str("(")?;
+                for ty in tys {
+                    f.write_fmt(args!("{}, ", self.dart_ty(ty)))?;
+                }
+                f.write_str(")")
+            }
+
+            Type::Option(ty) => f.write_fmt(args!("{}?", self.dart_ty(ty))),
+            Type::Result(ty) => f.write_fmt(args!(
+                "$.Result<{}, {}>",
+                self.dart_ty(&ty.0),
+                self.dart_ty(&ty.1)
+            )),
+            Type::Other(_) => unreachable!("rejected by `check_types`"),
+        })
+    }
+
+    /// Dart type of a function output, `()` is mapped to `void`.
+    fn dart_output_ty(&self, ty: &Type) -> fmt!(type) {
+        fmt(move |f| match ty {
+            Type::Tuple(tys) if tys.is_empty() => f.write_str("void"),
+            ty => f.write_fmt(args!("{}", self.dart_ty(ty))),
+        })
+    }
+
+    fn dart_codec(&self, ty: &Type, codec: Codec) -> fmt!(type) {
+        fmt(move |f| {
+            let rt = codec.runtime();
+            match ty {
+                Type::U8 => f.write_fmt(args!("{rt}.u8")),
+                Type::I8 => f.write_fmt(args!("{rt}.i8")),
+
+                Type::F32 => f.write_fmt(args!("{rt}.f32")),
+                Type::F64 => f.write_fmt(args!("{rt}.f64")),
+
+                Type::U16 => f.write_fmt(args!("{rt}.u16")),
+                Type::U32 => f.write_fmt(args!("{rt}.u32")),
+                Type::U64 => f.write_fmt(args!("{rt}.u64")),
+                Type::U128 => f.write_fmt(args!("{rt}.u128")),
+
+                Type::I16 => f.write_fmt(args!("{rt}.i16")),
+                Type::I32 => f.write_fmt(args!("{rt}.i32")),
+                Type::I64 => f.write_fmt(args!("{rt}.i64")),
+                Type::I128 => f.write_fmt(args!("{rt}.i128")),
+
+                Type::Bool => f.write_fmt(args!("{rt}.boolean")),
+                Type::Char => f.write_fmt(args!("{rt}.char")),
+                Type::String => f.write_fmt(args!("{rt}.str")),
+
+                Type::List {
+                    variant: ListVariant::Columns,
+                    ty,
+                } => {
+                    let (fields, is_tuple) = self.row_fields(ty);
+                    let row = self.dart_ty(ty);
+                    f.write_fmt(args!("{rt}.columns<{row}>(["))?;
+                    for (_, StructField { key, ty, .. }) in fields {
+                        let optional = ty.optional().is_some();
+                        f.write_fmt(args!(
+                            "({key}, {}, {optional}), ",
+                            self.dart_codec(ty, codec)
+                        ))?;
+                    }
+                    f.write_str("], (z) => ")?;
+                    match codec {
+                        Codec::Encoder => {
+                            f.write_str("[")?;
+                            for (_, StructField { name, .. }) in fields {
+                                if is_tuple {
+                                    f.write_fmt(args!("z.${name}, "))?;
+                                } else {
+                                    f.write_fmt(args!("z.{}, ", ident(name)))?;
+                                }
+                            }
+                            f.write_str("])")
+                        }
+                        // Values of a row are in the order of its fields.
+                        Codec::Decoder => {
+                            f.write_fmt(args!("{row}("))?;
+                            for (i, (_, StructField { key, name, ty })) in fields.iter().enumerate()
+                            {
+                                if is_tuple {
+                                    f.write_fmt(args!("{}, ", self.dart_tuple_item(i, *key, ty)))?;
+                                    continue;
+                                }
+                                let (field, dart_ty) = (ident(name), self.dart_ty(ty));
+                                if ty.optional().is_some() {
+                                    f.write_fmt(args!("{field}: z[{i}] as {dart_ty}, "))?;
+                                } else {
+                                    f.write_fmt(args!(
+                                        "{field}: $.requiredField(z[{i}] as {dart_ty}?, '{name}', {key}), "
+                                    ))?;
+                                }
+                            }
+                            f.write_str("))")
+                        }
+                    }
+                }
+                Type::Array { ty, .. } | Type::List { ty, .. } => match ty.as_ref() {
+                    Type::U8 => f.write_fmt(args!("{rt}.listU8")),
+                    Type::I8 => f.write_fmt(args!("{rt}.listI8")),
+
+                    Type::F32 => f.write_fmt(args!("{rt}.listF32")),
+                    Type::F64 => f.write_fmt(args!("{rt}.listF64")),
+
+                    Type::U16 => f.write_fmt(args!("{rt}.listU16")),
+                    Type::U32 => f.write_fmt(args!("{rt}.listU32")),
+                    Type::U64 => f.write_fmt(args!("{rt}.listU64")),
+
+                    Type::I16 => f.write_fmt(args!("{rt}.listI16")),
+                    Type::I32 => f.write_fmt(args!("{rt}.listI32")),
+                    Type::I64 => f.write_fmt(args!("{rt}.listI64")),
+
+                    Type::Bool => f.write_fmt(args!("{rt}.listBool")),
+                    ty => f.write_fmt(args!("{rt}.list({})", self.dart_codec(ty, codec))),
+                },
+                Type::Complex(path) => f.write_fmt(args!(
+                    "{}.{}",
+                    self.symbol.interface_name(path),
+                    codec.member()
+                )),
+                Type::Option(ty) => f.write_fmt(args!("{}", self.dart_codec(ty, codec))),
+                Type::Map { ty, .. } => f.write_fmt(args!(
+                    "{rt}.table({}, {})",
+                    self.dart_codec(&ty.0, codec),
+                    self.dart_codec(&ty.1, codec)
+                )),
+                // Items of a record are `$1`, `$2`, ...
+                Type::Tuple(tys) => {
+                    let (class, ty) = (codec.class(), self.dart_ty(ty));
+                    let items = tys.iter().enumerate();
+                    match codec {
+                        Codec::Encoder => {
+                            f.write_fmt(args!(
+                                "{class}<{ty}>($.DataType.struct, (e, z) => $.structEncoder(e, ["
+                            ))?;
+                            for (i, ty) in items {
+                                let encoder = self.dart_codec(ty, codec);
+                                f.write_fmt(args!("$.Field({i}, z.${}, {encoder}), ", i + 1))?;
+                            }
+                            f.write_str("]))")
+                        }
+                        Codec::Decoder => {
+                            let keys = items.clone().map(|(i, ty)| (i as u32, ty));
+                            f.write_fmt(args!(
+                                "{class}<{ty}>($.DataType.struct, (d) {{ final z = $.tupleDecoder(d, {}); return (",
+                                self.dart_tuple_decoders(keys)
+                            ))?;
+                            for (i, ty) in items {
+                                f.write_fmt(args!("{}, ", self.dart_tuple_item(i, i as u32, ty)))?;
+                            }
+                            f.write_str("); })")
+                        }
+                    }
+                }
+                Type::Result(ty) => {
+                    f.write_fmt(args!(
+                        "{rt}.result<{}, {}>({}, {}",
+                        self.dart_ty(&ty.0),
+                        self.dart_ty(&ty.1),
+                        self.dart_codec(&ty.0, codec),
+                        self.dart_codec(&ty.1, codec)
+                    ))?;
+                    // Decoder also checks `Ok` value, if it's required.
+                    if let Codec::Decoder = codec {
+                        f.write_fmt(args!(", {}", ty.0.optional().is_none()))?;
+                    }
+                    f.write_str(")")
+                }
+                Type::Other(_) => unreachable!("rejected by `check_types`"),
+            }
+        })
+    }
+
+    /// `[(key, decoder), ..]` of tuple items, for `$.tupleDecoder`.
+    fn dart_tuple_decoders<'a, I>(&'a self, items: I) -> fmt!(type 'a)
+    where
+        I: Iterator<Item = (u32, &'a Type)> + Clone + 'a,
+    {
+        fmt(move |f| {
+            f.write_str("[")?;
+            for (key, ty) in items.clone() {
+                f.write_fmt(args!("({key}, {}), ", self.dart_codec(ty, Codec::Decoder)))?;
+            }
+            f.write_str("]")
+        })
+    }
+
+    /// Item at `index` of decoded tuple `z`.
+    fn dart_tuple_item<'a>(&'a self, index: usize, key: u32, ty: &'a Type) -> fmt!(type 'a) {
+        fmt(move |f| {
+            if ty.optional().is_some() {
+                return f.write_fmt(args!("z[{index}] as {}", self.dart_ty(ty)));
+            }
+            f.write_fmt(args!(
+                "$.requiredField(z[{index}] as {}?, '{index}', {key})",
+                self.dart_ty(ty)
+            ))
+        })
+    }
+
+    fn dart_struct_encoder<'a, I, V>(&'a self, c: &mut CodeWriter, fields: I)
+    where
+        I: Iterator<Item = (V, &'a Type, u32)>,
+        V: Display,
+    {
+        c.scope(|c| {
+            for (value, ty, key) in fields {
+                let encoder = self.dart_codec(ty, Codec::Encoder);
+                c.line(args!("$.Field({key}, {value}, {encoder}),"));
+            }
+        });
+    }
+}
+
+/// Escapes identifiers that are reserved in dart, or shadow a builtin type.
+fn ident(name: &str) -> fmt!(type '_) {
+    const RESERVED: &[&str] = &[
+        "assert", "break", "case", "catch", "class", "const", "continue", "default", "do", "else",
+        "enum", "extends", "false", "final", "finally", "for", "if", "in", "is", "new", "null",
+        "rethrow", "return", "super", "switch", "this", "throw", "true", "try", "var", "void",
+        "while", "with", "bool", "int", "double", "num", "dynamic",
+    ];
+    fmt(move |f| {
+        f.write_str(name)?;
+        if RESERVED.contains(&name) {
+            f.write_str("$")?;
+        }
+        Ok(())
+    })
+}
+
+fn write_docs(c: &mut CodeWriter, docs: &str) {
+    for line in docs.lines() {
+        match line.is_empty() {
+            true => c.line("///"),
+            false => c.line(args!("/// {line}")),
+        }
+    }
+}
+
+/// Wire type of a numeric enum, also the name of its runtime codec.
+fn numeric_repr(discriminant: &Discriminant) -> Option<&'static str> {
+    match discriminant {
+        Discriminant::U8(_) => Some("u8"),
+        Discriminant::I8(_) => Some("i8"),
+
+        Discriminant::U16(_) | Discriminant::U32(_) | Discriminant::U64(_) => Some("uint"),
+        Discriminant::I16(_) | Discriminant::I32(_) | Discriminant::I64(_) => Some("sint"),
+        Discriminant::None => None,
+    }
+}
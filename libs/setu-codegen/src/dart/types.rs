@@ -0,0 +1,364 @@
+use std::format_args as args;
+use type_id::{
+    Attributes, ComplexData, ComplexDataType, EnumField, EnumFieldType, PathIdent, StructField,
+    Type,
+};
+
+use super::{Codec, fmt, ident, numeric_repr, write_docs};
+use crate::{CodeWriter, Context};
+
+pub fn generate(c: &mut CodeWriter, ctx: &Context) {
+    for (path, ComplexData { attrs, ty }) in ctx.info.registry.iter() {
+        match ty {
+            ComplexDataType::Struct { fields } => {
+                c.newline();
+                write_docs(c, &attrs.docs);
+                let class = ctx.symbol.interface_name(path);
+                generate_struct(c, ctx, path, &class, None, fields);
+            }
+            ComplexDataType::Enum { is_numeric, fields } if *is_numeric => {
+                c.newline();
+                write_docs(c, &attrs.docs);
+                generate_numeric_enum(c, ctx, path, fields);
+            }
+            ComplexDataType::Enum { fields, .. } => {
+                c.newline();
+                write_docs(c, &attrs.docs);
+                generate_union(c, ctx, path, fields);
+            }
+            ComplexDataType::Tuple { fields } => {
+                c.newline();
+                write_docs(c, &attrs.docs);
+                let class = ctx.symbol.interface_name(path);
+                generate_tuple(c, ctx, path, &class, None, fields);
+            }
+        }
+    }
+}
+
+/// Codecs are generated, if they are needed by `path`.
+fn generate_struct(
+    c: &mut CodeWriter,
+    ctx: &Context,
+    path: &PathIdent,
+    class: &str,
+    parent: Option<&str>,
+    fields: &[(Attributes, StructField)],
+) {
+    c.block(args!("final class {class}{}", extends(parent)), |c| {
+        for (Attributes { docs }, StructField { name, ty, .. }) in fields {
+            write_docs(c, docs);
+            c.line(args!("final {} {};", ctx.dart_ty(ty), ident(name)));
+        }
+
+        c.newline();
+        if fields.is_empty() {
+            c.line(args!("const {class}();"));
+        } else {
+            c.line(args!("const {class}({{"));
+            c.scope(|c| {
+                for (_, StructField { name, ty, .. }) in fields {
+                    let required = if ty.optional().is_none() {
+                        "required "
+                    } else {
+                        ""
+                    };
+                    c.line(args!("{required}this.{},", ident(name)));
+                }
+            });
+            c.line("});");
+        }
+
+        if ctx.is_encoder_needed(path) {
+            c.newline();
+            codec_field(c, class, Codec::Encoder, "struct", |c| {
+                c.line("$.structEncoder(e, [");
+                ctx.dart_struct_encoder(
+                    c,
+                    fields.iter().map(|(_, s)| {
+                        (
+                            fmt(|f| f.write_fmt(args!("z.{}", ident(&s.name)))),
+                            &s.ty,
+                            s.key,
+                        )
+                    }),
+                );
+                c.line("]);");
+            });
+        }
+
+        if ctx.is_decoder_needed(path) {
+            c.newline();
+            codec_field(c, class, Codec::Decoder, "struct", |c| {
+                for (_, StructField { name, ty, .. }) in fields {
+                    let ty = ty.optional().unwrap_or(ty);
+                    c.line(args!("{}? ${};", ctx.dart_ty(ty), ident(name)));
+                }
+                c.line("$.structDecoder(d, (id, ty) {");
+                c.scope(|c| {
+                    c.block("switch (id)", |c| {
+                        for (_, StructField { name, ty, key }) in fields {
+                            let decoder = ctx.dart_codec(ty, Codec::Decoder);
+                            c.line(args!(
+                                "case {key}: ${} = d.field(ty, {decoder});",
+                                ident(name)
+                            ));
+                        }
+                        c.line("default: return false;");
+                    });
+                    c.line("return true;");
+                });
+                c.line("});");
+                c.line(args!("return {class}("));
+                c.scope(|c| {
+                    for (_, StructField { name, ty, key }) in fields {
+                        let field = ident(name);
+                        if ty.optional().is_some() {
+                            c.line(args!("{field}: ${field},"));
+                        } else {
+                            c.line(args!(
+                                "{field}: $.requiredField(${field}, '{name}', {key}),"
+                            ));
+                        }
+                    }
+                });
+                c.line(");");
+            });
+        }
+    });
+}
+
+/// Items of a tuple struct are named `$0`, `$1`, ...
+fn generate_tuple(
+    c: &mut CodeWriter,
+    ctx: &Context,
+    path: &PathIdent,
+    class: &str,
+    parent: Option<&str>,
+    fields: &[(Attributes, StructField)],
+) {
+    c.block(args!("final class {class}{}", extends(parent)), |c| {
+        for (Attributes { docs }, StructField { name, ty, .. }) in fields {
+            write_docs(c, docs);
+            c.line(args!("final {} ${name};", ctx.dart_ty(ty)));
+        }
+
+        c.newline();
+        let params = fmt(|f| {
+            for (_, StructField { name, .. }) in fields {
+                f.write_fmt(args!("this.${name}, "))?;
+            }
+            Ok(())
+        });
+        c.line(args!("const {class}({params});"));
+
+        if ctx.is_encoder_needed(path) {
+            c.newline();
+            codec_field(c, class, Codec::Encoder, "struct", |c| {
+                c.line("$.structEncoder(e, [");
+                ctx.dart_struct_encoder(
+                    c,
+                    fields
+                        .iter()
+                        .map(|(_, s)| (fmt(|f| f.write_fmt(args!("z.${}", s.name))), &s.ty, s.key)),
+                );
+                c.line("]);");
+            });
+        }
+
+        if ctx.is_decoder_needed(path) {
+            c.newline();
+            codec_field(c, class, Codec::Decoder, "struct", |c| {
+                let keys = fields.iter().map(|(_, s)| (s.key, &s.ty));
+                c.line(args!(
+                    "final z = $.tupleDecoder(d, {});",
+                    ctx.dart_tuple_decoders(keys)
+                ));
+                c.line(args!("return {class}("));
+                c.scope(|c| {
+                    for (index, (_, StructField { ty, key, .. })) in fields.iter().enumerate() {
+                        c.line(args!("{},", ctx.dart_tuple_item(index, *key, ty)));
+                    }
+                });
+                c.line(");");
+            });
+        }
+    });
+}
+
+fn generate_numeric_enum(
+    c: &mut CodeWriter,
+    ctx: &Context,
+    path: &PathIdent,
+    fields: &[(Attributes, EnumField)],
+) {
+    let Some(repr) = fields
+        .iter()
+        .find_map(|(_, field)| numeric_repr(&field.discriminant))
+    else {
+        return;
+    };
+    let class = ctx.symbol.interface_name(path);
+    c.block(args!("enum {class}"), |c| {
+        for (i, (Attributes { docs }, field)) in fields.iter().enumerate() {
+            let sep = if i + 1 == fields.len() { ';' } else { ',' };
+            write_docs(c, docs);
+            c.line(args!("{}({}){sep}", field.name, field.discriminant));
+        }
+
+        c.newline();
+        c.line("final int value;");
+        c.line(args!("const {class}(this.value);"));
+
+        if ctx.is_encoder_needed(path) {
+            c.newline();
+            codec_field(c, &class, Codec::Encoder, repr, |c| {
+                c.line(args!("$.Encoders.{repr}(e, z.value);"));
+            });
+        }
+
+        if ctx.is_decoder_needed(path) {
+            c.newline();
+            codec_field(c, &class, Codec::Decoder, repr, |c| {
+                c.line(args!("return switch ($.Decoders.{repr}(d)) {{"));
+                c.scope(|c| {
+                    for (_, field) in fields {
+                        let (key, name) = (&field.discriminant, &field.name);
+                        c.line(args!("{key} => {class}.{name},"));
+                    }
+                    c.line("final tag => throw $.ProtocolError('unknown tag: $tag'),");
+                });
+                c.line("};");
+            });
+        }
+    });
+}
+
+fn generate_union(
+    c: &mut CodeWriter,
+    ctx: &Context,
+    path: &PathIdent,
+    fields: &[(Attributes, EnumField)],
+) {
+    let class = ctx.symbol.interface_name(path);
+    let variants = || {
+        fields
+            .iter()
+            .map(|(attrs, field)| (attrs, field, enum_field_kind(&field.ty)))
+    };
+
+    c.block(args!("sealed class {class}"), |c| {
+        c.line(args!("const {class}();"));
+
+        if ctx.is_encoder_needed(path) {
+            c.newline();
+            codec_field(c, &class, Codec::Encoder, "union", |c| {
+                c.block("switch (z)", |c| {
+                    for (_, EnumField { name, discriminant: key, .. }, kind) in variants() {
+                        match kind {
+                            EnumKind::Unit => c.line(args!(
+                                "case {class}{name}(): e.field({key}, false, $.Encoders.boolean);"
+                            )),
+                            EnumKind::Field(ty) => {
+                                let encoder = ctx.dart_codec(ty, Codec::Encoder);
+                                c.line(args!(
+                                    "case {class}{name}(:final value): e.field({key}, value, {encoder});"
+                                ))
+                            }
+                            EnumKind::Tuple(_) | EnumKind::Struct(_) => c.line(args!(
+                                "case final {class}{name} variant: e.field({key}, variant, {class}{name}.$encoder);"
+                            )),
+                        }
+                    }
+                });
+            });
+        }
+
+        if ctx.is_decoder_needed(path) {
+            c.newline();
+            codec_field(c, &class, Codec::Decoder, "union", |c| {
+                c.line("final (id, ty) = d.readFieldIdAndTy();");
+                c.line("return switch (id) {");
+                c.scope(|c| {
+                    for (_, EnumField { name, discriminant: key, .. }, kind) in variants() {
+                        match kind {
+                            EnumKind::Unit => c.line(args!(
+                                "{key} => $.unitVariant(d, id, ty, const {class}{name}()),"
+                            )),
+                            EnumKind::Field(ty) => {
+                                let decoder = ctx.dart_codec(ty, Codec::Decoder);
+                                c.line(args!("{key} => {class}{name}(d.field(ty, {decoder})),"))
+                            }
+                            EnumKind::Tuple(_) | EnumKind::Struct(_) => c.line(args!(
+                                "{key} => d.field(ty, {class}{name}.$decoder),"
+                            )),
+                        }
+                    }
+                    c.line("_ => throw $.ProtocolError('unknown variant: $id'),");
+                });
+                c.line("};");
+            });
+        }
+    });
+
+    for (Attributes { docs }, EnumField { name, .. }, kind) in variants() {
+        c.newline();
+        write_docs(c, docs);
+        let variant = format!("{class}{name}");
+        match kind {
+            EnumKind::Tuple(items) => generate_tuple(c, ctx, path, &variant, Some(&class), items),
+            EnumKind::Struct(items) => generate_struct(c, ctx, path, &variant, Some(&class), items),
+            EnumKind::Unit => c.block(args!("final class {variant} extends {class}"), |c| {
+                c.line(args!("const {variant}();"))
+            }),
+            EnumKind::Field(ty) => c.block(args!("final class {variant} extends {class}"), |c| {
+                c.line(args!("final {} value;", ctx.dart_ty(ty)));
+                c.line(args!("const {variant}(this.value);"));
+            }),
+        }
+    }
+}
+
+fn extends(parent: Option<&str>) -> fmt!(type '_) {
+    fmt(move |f| match parent {
+        Some(parent) => f.write_fmt(args!(" extends {parent}")),
+        None => Ok(()),
+    })
+}
+
+/// `static final $encoder = $.Encoder<T>($.DataType.ty, (e, z) { .. });`
+fn codec_field(
+    c: &mut CodeWriter,
+    class: &str,
+    codec: Codec,
+    data_ty: &str,
+    f: impl FnOnce(&mut CodeWriter),
+) {
+    let (member, kind) = (codec.member(), codec.class());
+    let params = match codec {
+        Codec::Encoder => "(e, z)",
+        Codec::Decoder => "(d)",
+    };
+    c.line(args!(
+        "static final {member} = {kind}<{class}>($.DataType.{data_ty}, {params} {{"
+    ));
+    c.scope(f);
+    c.line("});");
+}
+
+enum EnumKind<'a> {
+    Unit,
+    Field(&'a Type),
+    /// Tuple variant of multiple fields, as `$0`, `$1`, ...
+    Tuple(&'a [(Attributes, StructField)]),
+    Struct(&'a [(Attributes, StructField)]),
+}
+
+fn enum_field_kind(ty: &EnumFieldType) -> EnumKind<'_> {
+    match ty {
+        EnumFieldType::Unit => EnumKind::Unit,
+        EnumFieldType::Tuple(items) if items.len() == 1 => EnumKind::Field(&items[0].1.ty),
+        EnumFieldType::Tuple(items) => EnumKind::Tuple(items),
+        EnumFieldType::Struct(items) => EnumKind::Struct(items),
+    }
+}
@@ -3,13 +3,19 @@ mod path_of_complex_type;
 mod symbol_trie;
 mod utils;
 
+pub mod dart;
+pub mod rust;
 pub mod typescript;
 pub use setu_type_info as type_info;
 
 pub use code_writer::CodeWriter;
 
+use std::io;
 use symbol_trie::SymbolTrie;
-use type_info::TypeInfo;
+use type_info::type_id::{
+    Attributes, ComplexData, ComplexDataType, EnumFieldType, StructField, Type,
+};
+use type_info::{FnOutputTy, Func, TypeInfo};
 
 use crate::path_of_complex_type::PathsOfComplexType;
 
@@ -38,4 +44,133 @@ impl Context {
     pub(crate) fn is_decoder_needed(&self, path: &str) -> bool {
         self.obj_that_needed_decoder.contains(path)
     }
+
+    /// Types that no client can be generated for (e.g. a nested stream) are rejected,
+    /// naming the first one.
+    pub(crate) fn check_types(&self) -> Result<(), String> {
+        for (path, data) in self.info.registry.iter() {
+            match &data.ty {
+                ComplexDataType::Struct { fields } | ComplexDataType::Tuple { fields } => {
+                    for (_, field) in fields {
+                        check(&field.ty, || format!("field `{}` of `{path}`", field.name))?;
+                    }
+                }
+                ComplexDataType::Enum { fields, .. } => {
+                    for (_, variant) in fields {
+                        let items = match &variant.ty {
+                            EnumFieldType::Unit => continue,
+                            EnumFieldType::Struct(items) | EnumFieldType::Tuple(items) => items,
+                        };
+                        for (_, field) in items {
+                            let name = &variant.name;
+                            check(&field.ty, || {
+                                format!("field `{}` of `{path}::{name}`", field.name)
+                            })?;
+                        }
+                    }
+                }
+            }
+        }
+
+        for Func {
+            meta,
+            input_ty,
+            output_ty,
+            stream,
+        } in &self.info.fns
+        {
+            let ident = &meta.ident;
+            for (name, ty) in meta.args.iter().zip(input_ty) {
+                check(ty, || format!("argument `{name}` of rpc `{ident}`"))?;
+            }
+            let output = match output_ty {
+                FnOutputTy::Return(ty) => vec![ty],
+                FnOutputTy::Generator(g) => vec![&g.yield_ty, &g.return_ty],
+            };
+            let stream = stream.iter().flat_map(|s| [&s.yield_ty, &s.return_ty]);
+            for ty in output.into_iter().chain(stream) {
+                check(ty, || format!("rpc `{ident}`"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fields of a row of `lipi::Columns`, which is a struct or a tuple struct (`true`).
+    pub(crate) fn row_fields(&self, ty: &Type) -> (&[(Attributes, StructField)], bool) {
+        let row = ty.complex().and_then(|path| self.info.registry.get(path));
+        match row {
+            Some(ComplexData {
+                ty: ComplexDataType::Struct { fields },
+                ..
+            }) => (fields, false),
+            Some(ComplexData {
+                ty: ComplexDataType::Tuple { fields },
+                ..
+            }) => (fields, true),
+            Some(_) => panic!(
+                "row of `Columns<{}>` must be a struct, found an enum",
+                ty_name(ty)
+            ),
+            None => panic!("row of `Columns<{}>` must be a struct", ty_name(ty)),
+        }
+    }
+}
+
+/// Code generation fails with [io::ErrorKind::InvalidInput], for unsupported types.
+pub(crate) fn invalid_input(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+fn check(ty: &Type, what: impl FnOnce() -> String) -> Result<(), String> {
+    match is_supported(ty) {
+        true => Ok(()),
+        false => Err(format!("unsupported type of {}", what())),
+    }
+}
+
+fn is_supported(ty: &Type) -> bool {
+    match ty {
+        Type::Other(_) => false,
+        Type::Option(ty) | Type::Array { ty, .. } | Type::List { ty, .. } => is_supported(ty),
+        Type::Result(ty) | Type::Map { ty, .. } => is_supported(&ty.0) && is_supported(&ty.1),
+        Type::Tuple(tys) => tys.iter().all(is_supported),
+        _ => true,
+    }
+}
+
+fn ty_name(ty: &Type) -> String {
+    match ty.complex() {
+        Some(path) => path.to_string(),
+        None => format!("{ty:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{path::PathBuf, sync::Arc};
+    use type_info::type_id::{ListVariant, OtherType};
+
+    #[test]
+    fn test_unsupported_types() {
+        let mut info = TypeInfo::from_schema("fn 1 get() -> Option<u8>\n").unwrap();
+        let FnOutputTy::Return(ty) = &mut info.fns[0].output_ty else {
+            unreachable!()
+        };
+        *ty = Type::Option(Box::new(Type::List {
+            variant: ListVariant::Vec,
+            ty: Box::new(Type::Other(OtherType(Arc::new(())))),
+        }));
+        let ctx = Context::new(info);
+
+        let config = typescript::Config::out_dir(PathBuf::new());
+        let errors = [
+            ctx.generate_typescript_code(&config).unwrap_err(),
+            ctx.generate_dart_code("App").unwrap_err(),
+        ];
+        for err in errors {
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+            assert_eq!(err.to_string(), "unsupported type of rpc `get`");
+        }
+    }
 }
@@ -96,21 +96,17 @@ fn visit_struct<'a>(fields: &'a [(Attributes, StructField)], f: &mut impl FnMut(
     }
 }
 
-fn visit_tuple<'a>(fields: &'a [(Attributes, Type)], f: &mut impl FnMut(&'a PathIdent)) {
-    for (_, ty) in fields {
-        visit_complex_type(ty, f)
-    }
-}
-
 fn visit_user_defined<'a>(ty: &'a ComplexDataType, f: &mut impl FnMut(&'a PathIdent)) {
     match ty {
-        ComplexDataType::Struct { fields } => visit_struct(fields, f),
-        ComplexDataType::Tuple { fields } => visit_tuple(fields, f),
+        ComplexDataType::Struct { fields } | ComplexDataType::Tuple { fields } => {
+            visit_struct(fields, f)
+        }
         ComplexDataType::Enum { fields, .. } => {
             for (_, field) in fields {
                 match &field.ty {
-                    EnumFieldType::Struct(fields) => visit_struct(fields, f),
-                    EnumFieldType::Tuple(fields) => visit_tuple(fields, f),
+                    EnumFieldType::Struct(fields) | EnumFieldType::Tuple(fields) => {
+                        visit_struct(fields, f)
+                    }
                     EnumFieldType::Unit => {}
                 }
             }
@@ -0,0 +1,85 @@
+use setu_type_info::{FnMetaData, FnOutputTy, Func};
+use std::format_args as args;
+
+use super::{fmt, write_docs};
+use crate::{CodeWriter, Context};
+
+pub fn generate(c: &mut CodeWriter, ctx: &Context, client_name: &str) {
+    c.newline();
+    c.line("#[derive(Clone)]");
+    c.line(args!(
+        "pub struct {client_name}(pub ::setu::client::Client);"
+    ));
+
+    c.newline();
+    c.block(args!("impl {client_name}"), |c| {
+        c.block("pub fn new(client: ::setu::client::Client) -> Self", |c| {
+            c.line("Self(client)")
+        });
+
+        for Func {
+            meta,
+            input_ty,
+            output_ty,
+            stream,
+        } in &ctx.info.fns
+        {
+            let FnMetaData {
+                docs,
+                index,
+                ident,
+                args,
+            } = meta;
+
+            let fn_args = fmt(|f| {
+                for (name, ty) in args.iter().zip(input_ty) {
+                    f.write_fmt(args!(", {name}: {}", ctx.rust_ty(ty)))?;
+                }
+                Ok(())
+            });
+            let call_args = fmt(|f| match args.len() {
+                0 => f.write_str("()"),
+                1 => f.write_fmt(args!("({},)", args[0])),
+                _ => f.write_fmt(args!("({})", args.join(", "))),
+            });
+
+            let (method, output) = match (output_ty, stream) {
+                (FnOutputTy::Return(ty), None) => ("call", format!("{}", ctx.rust_ty(ty))),
+                (FnOutputTy::Generator(g), None) => (
+                    "sse",
+                    format!(
+                        "::setu::Stream<{}, {}>",
+                        ctx.rust_ty(&g.yield_ty),
+                        ctx.rust_ty(&g.return_ty)
+                    ),
+                ),
+                (FnOutputTy::Return(ty), Some(s)) => (
+                    "client_stream",
+                    format!(
+                        "(::setu::client::Sender<{}, {}>, ::setu::client::Response<{}>)",
+                        ctx.rust_ty(&s.yield_ty),
+                        ctx.rust_ty(&s.return_ty),
+                        ctx.rust_ty(ty)
+                    ),
+                ),
+                (FnOutputTy::Generator(g), Some(s)) => (
+                    "bidi",
+                    format!(
+                        "(::setu::client::Sender<{}, {}>, ::setu::Stream<{}, {}>)",
+                        ctx.rust_ty(&s.yield_ty),
+                        ctx.rust_ty(&s.return_ty),
+                        ctx.rust_ty(&g.yield_ty),
+                        ctx.rust_ty(&g.return_ty)
+                    ),
+                ),
+            };
+
+            c.newline();
+            write_docs(c, docs);
+            c.block(
+                args!("pub async fn {ident}(&self{fn_args}) -> ::setu::Result<{output}>"),
+                |c| c.line(args!("self.0.{method}({index}, {call_args}).await")),
+            );
+        }
+    });
+}
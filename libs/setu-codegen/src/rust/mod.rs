@@ -0,0 +1,198 @@
+pub mod client;
+pub mod types;
+
+use std::format_args as args;
+use std::{fs, io, path::PathBuf};
+use type_id::{ComplexDataType, Discriminant, ListVariant, MapVariant, Type};
+
+use crate::{CodeWriter, Context, invalid_input, utils::fmt};
+pub use std::fmt::from_fn as fmt;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub out_dir: PathBuf,
+    /// Name of the generated client struct. (Default: `App`)
+    pub client_name: String,
+}
+
+impl Config {
+    pub fn out_dir(path: PathBuf) -> Self {
+        Self {
+            out_dir: path,
+            client_name: "App".into(),
+        }
+    }
+
+    pub fn client_name(mut self, name: impl Into<String>) -> Self {
+        self.client_name = name.into();
+        self
+    }
+}
+
+impl Config {
+    pub fn generate(&self, ctx: &Context) -> io::Result<()> {
+        fs::create_dir_all(&self.out_dir)?;
+        let code = ctx.generate_rust_code(&self.client_name)?;
+        fs::write(self.out_dir.join("mod.rs"), code)
+    }
+}
+
+static RS_PRELUDE: &str = "// AUTO-GENERATED FILE. DO NOT EDIT.\n";
+
+impl Context {
+    /// Fails with [io::ErrorKind::InvalidInput], naming the first type that can't be generated.
+    pub fn generate_rust_code(&self, client_name: &str) -> io::Result<String> {
+        self.check_rust_enums()
+            .and_then(|()| self.check_types())
+            .map_err(invalid_input)?;
+
+        let mut c = CodeWriter::new();
+        c.buffer.push_str(RS_PRELUDE);
+        types::generate(&mut c, self);
+        client::generate(&mut c, self, client_name);
+        Ok(c.buffer)
+    }
+
+    /// Enums need variants with explicit discriminants, to be generated with `#[repr]`.
+    fn check_rust_enums(&self) -> Result<(), String> {
+        for (path, data) in self.info.registry.iter() {
+            let ComplexDataType::Enum { fields, .. } = &data.ty else {
+                continue;
+            };
+            if fields.is_empty() {
+                return Err(format!("enum `{path}` has no variants"));
+            }
+            if fields
+                .iter()
+                .all(|(_, v)| repr_ty(&v.discriminant).is_none())
+            {
+                return Err(format!("enum `{path}` has no explicit discriminants"));
+            }
+        }
+        Ok(())
+    }
+
+    fn rust_ty(&self, ty: &Type) -> fmt!(type) {
+        fmt(|f| match ty {
+            Type::U8 => f.write_str("u8"),
+            Type::U16 => f.write_str("u16"),
+            Type::U32 => f.write_str("u32"),
+            Type::U64 => f.write_str("u64"),
+            Type::U128 => f.write_str("u128"),
+
+            Type::I8 => f.write_str("i8"),
+            Type::I16 => f.write_str("i16"),
+            Type::I32 => f.write_str("i32"),
+            Type::I64 => f.write_str("i64"),
+            Type::I128 => f.write_str("i128"),
+
+            Type::F32 => f.write_str("f32"),
+            Type::F64 => f.write_str("f64"),
+
+            Type::Bool => f.write_str("bool"),
+            Type::Char => f.write_str("char"),
+            Type::String => f.write_str("::std::string::String"),
+
+            Type::Option(ty) => f.write_fmt(args!("::std::option::Option<{}>", self.rust_ty(ty))),
+            Type::Result(ty) => f.write_fmt(args!(
+                "::std::result::Result<{}, {}>",
+                self.rust_ty(&ty.0),
+                self.rust_ty(&ty.1)
+            )),
+
+            Type::Array { ty, len } => f.write_fmt(args!("[{}; {len}]", self.rust_ty(ty))),
+            Type::List { variant, ty } => {
+                let list = match variant {
+                    ListVariant::Vec => "::std::vec::Vec",
+                    ListVariant::VecDeque => "::std::collections::VecDeque",
+                    ListVariant::LinkedList => "::std::collections::LinkedList",
+                    ListVariant::BinaryHeap => "::std::collections::BinaryHeap",
+                    ListVariant::HashSet => "::std::collections::HashSet",
+                    ListVariant::BTreeSet => "::std::collections::BTreeSet",
+                    ListVariant::Columns => "::setu::Columns",
+                };
+                f.write_fmt(args!("{list}<{}>", self.rust_ty(ty)))
+            }
+            Type::Map { variant, ty } => {
+                let map = match variant {
+                    MapVariant::HashMap => "::std::collections::HashMap",
+                    MapVariant::BTreeMap => "::std::collections::BTreeMap",
+                };
+                f.write_fmt(args!(
+                    "{map}<{}, {}>",
+                    self.rust_ty(&ty.0),
+                    self.rust_ty(&ty.1)
+                ))
+            }
+            Type::Tuple(tys) => {
+                f.write_str("(")?;
+                for ty in tys {
+                    f.write_fmt(args!("{}, ", self.rust_ty(ty)))?;
+                }
+                f.write_str(")")
+            }
+
+            Type::Complex(path) => f.write_str(&self.symbol.interface_name(path)),
+            Type::Other(_) => unreachable!("rejected by `check_types`"),
+        })
+    }
+}
+
+fn write_docs(c: &mut CodeWriter, docs: &str) {
+    for line in docs.lines() {
+        match line.is_empty() {
+            true => c.line("///"),
+            false => c.line(args!("/// {line}")),
+        }
+    }
+}
+
+fn repr_ty(discriminant: &Discriminant) -> Option<&'static str> {
+    match discriminant {
+        Discriminant::U8(_) => Some("u8"),
+        Discriminant::U16(_) => Some("u16"),
+        Discriminant::U32(_) => Some("u32"),
+        Discriminant::U64(_) => Some("u64"),
+        Discriminant::I8(_) => Some("i8"),
+        Discriminant::I16(_) => Some("i16"),
+        Discriminant::I32(_) => Some("i32"),
+        Discriminant::I64(_) => Some("i64"),
+        Discriminant::None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use setu_type_info::TypeInfo;
+    use std::sync::Arc;
+    use type_id::OtherType;
+
+    fn generate(info: TypeInfo) -> String {
+        let err = Context::new(info).generate_rust_code("App").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        err.to_string()
+    }
+
+    #[test]
+    fn test_unsupported_types() {
+        let info = TypeInfo::from_schema("fn 1 get() -> app::E\n\nenum app::E\n    _ A\n").unwrap();
+        assert_eq!(
+            generate(info),
+            "enum `app::E` has no explicit discriminants"
+        );
+
+        let info = TypeInfo::from_schema("fn 1 get() -> app::E\n\nenum app::E\n").unwrap();
+        assert_eq!(generate(info), "enum `app::E` has no variants");
+
+        let mut info = TypeInfo::from_schema("fn 1 add(a: u8, b: Vec<u8>) -> u8\n").unwrap();
+        info.fns[0].input_ty[1] = Type::List {
+            variant: ListVariant::Vec,
+            ty: Box::new(Type::Other(OtherType(Arc::new(())))),
+        };
+        assert_eq!(
+            generate(info),
+            "unsupported type of argument `b` of rpc `add`"
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::format_args as args;
+use type_id::{Attributes, ComplexData, ComplexDataType, EnumField, EnumFieldType, StructField};
+
+use super::{repr_ty, write_docs};
+use crate::{CodeWriter, Context};
+
+const DERIVE: &str = "#[derive(Debug, Clone, PartialEq, ::setu::Message)]";
+
+pub fn generate(c: &mut CodeWriter, ctx: &Context) {
+    for (path, ComplexData { attrs, ty }) in ctx.info.registry.iter() {
+        let name = ctx.symbol.interface_name(path);
+        match ty {
+            ComplexDataType::Struct { fields } => {
+                c.newline();
+                write_docs(c, &attrs.docs);
+                c.line(DERIVE);
+                c.block(args!("pub struct {name}"), |c| {
+                    for (Attributes { docs }, StructField { key, name, ty }) in fields {
+                        write_docs(c, docs);
+                        c.line(args!("#[key = {key}]"));
+                        c.line(args!("pub {name}: {},", ctx.rust_ty(ty)));
+                    }
+                });
+            }
+            ComplexDataType::Enum { is_numeric, fields } => {
+                let Some(repr) = fields.iter().find_map(|(_, f)| repr_ty(&f.discriminant)) else {
+                    unreachable!("rejected by `check_rust_enums`");
+                };
+                c.newline();
+                write_docs(c, &attrs.docs);
+                c.line(DERIVE);
+                if *is_numeric {
+                    c.line("#[numeric]");
+                }
+                c.line(args!("#[repr({repr})]"));
+                c.block(args!("pub enum {name}"), |c| {
+                    for (Attributes { docs }, field) in fields {
+                        let EnumField {
+                            name,
+                            ty,
+                            discriminant,
+                        } = field;
+
+                        write_docs(c, docs);
+                        match ty {
+                            EnumFieldType::Unit => c.line(args!("{name} = {discriminant},")),
+                            EnumFieldType::Tuple(items) => {
+                                c.line(args!("{name}("));
+                                c.scope(|c| {
+                                    for (Attributes { docs }, StructField { ty, .. }) in items {
+                                        write_docs(c, docs);
+                                        c.line(args!("{},", ctx.rust_ty(ty)));
+                                    }
+                                });
+                                c.line(args!(") = {discriminant},"));
+                            }
+                            EnumFieldType::Struct(items) => {
+                                c.line(args!("{name} {{"));
+                                c.scope(|c| {
+                                    for (Attributes { docs }, StructField { key, name, ty }) in
+                                        items
+                                    {
+                                        write_docs(c, docs);
+                                        c.line(args!("#[key = {key}]"));
+                                        c.line(args!("{name}: {},", ctx.rust_ty(ty)));
+                                    }
+                                });
+                                c.line(args!("}} = {discriminant},"));
+                            }
+                        }
+                    }
+                });
+            }
+            ComplexDataType::Tuple { fields } => {
+                c.newline();
+                write_docs(c, &attrs.docs);
+                c.line(DERIVE);
+                c.line(args!("pub struct {name}("));
+                c.scope(|c| {
+                    for (Attributes { docs }, StructField { key, ty, .. }) in fields {
+                        write_docs(c, docs);
+                        c.line(args!("#[key = {key}] pub {},", ctx.rust_ty(ty)));
+                    }
+                });
+                c.line(");");
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use setu_type_info::TypeInfo;
 use std::collections::HashMap;
+use type_id::PathIdent;
 
 type Child = HashMap<Box<str>, Node>;
 
@@ -78,6 +79,40 @@ impl<'a> Iterator for ShortestSymbolIter<'_, 'a> {
     }
 }
 
+mod cached {
+    use crate::utils::LocalCachedTable;
+    use std::rc::Rc;
+    use type_id::PathIdent;
+
+    thread_local! {
+       pub static SYMBOL: LocalCachedTable<PathIdent, str> = LocalCachedTable::new();
+    }
+
+    pub fn get_symbol(path: &PathIdent, init: impl FnOnce() -> String) -> Rc<str> {
+        SYMBOL.with(|cached| cached.get_or_insert_with(path.clone(), init))
+    }
+}
+
+impl SymbolTrie {
+    pub fn interface_name(&self, path: &PathIdent) -> std::rc::Rc<str> {
+        cached::get_symbol(path, || {
+            self.shortest_symbol(path)
+                .flat_map(|part| {
+                    let mut chars = part.chars();
+
+                    let ch = chars.next()?.to_ascii_uppercase();
+                    let rest = chars.as_str();
+
+                    Some(format!("{ch}{rest}"))
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev()
+                .collect()
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,10 +1,11 @@
 use setu_type_info::{FnMetaData, FnOutputTy, Func};
 
+use super::Config;
 use crate::{CodeWriter, Context};
 use std::format_args as args;
 use type_id::Type;
 
-pub fn generate(c: &mut CodeWriter, ctx: &Context) {
+pub fn generate(c: &mut CodeWriter, ctx: &Context, config: &Config) {
     for Func {
         meta,
         input_ty,
@@ -50,12 +51,18 @@ pub fn generate(c: &mut CodeWriter, ctx: &Context) {
 
                             for ty in return_tys {
                                 if matches!(ty, Type::Tuple(tys) if tys.is_empty()) {
-                                    c.line("_ => { }");
+                                    c.line("_ => { },");
                                     continue;
                                 }
                                 let required = ty.optional().is_none();
                                 let decoder = ctx.serde_ty(ty, "$D");
 
+                                if config.throw_result_error && matches!(ty, Type::Result(_)) {
+                                    c.line(args!(
+                                        "_ => $.lipi.unwrap($OD(_, {decoder}, {required})),"
+                                    ));
+                                    continue;
+                                }
                                 c.line(args!("_ => $OD(_, {decoder}, {required}),"));
                             }
                         });
@@ -63,17 +70,21 @@ pub fn generate(c: &mut CodeWriter, ctx: &Context) {
                     };
 
                 match output_ty {
-                    FnOutputTy::Return(return_ty) if let Some(ty) = stream => {
+                    FnOutputTy::Return(return_ty) if let Some(s) = stream => {
                         fn_call_body("uni", &[return_ty], &|c| {
-                            
-                            // ty.yield_ty;
-                            // ty.yield_ty;
+                            stream_encoder(ctx, c, &s.yield_ty);
+                            stream_encoder(ctx, c, &s.return_ty);
                         });
                     }
                     FnOutputTy::Return(return_ty) => {
                         fn_call_body("rpc", &[return_ty], &|_| {});
                     }
-                    FnOutputTy::Generator(_) if let Some(_) = stream => {}
+                    FnOutputTy::Generator(g) if let Some(s) = stream => {
+                        fn_call_body("bi", &[&g.yield_ty, &g.return_ty], &|c| {
+                            stream_encoder(ctx, c, &s.yield_ty);
+                            stream_encoder(ctx, c, &s.return_ty);
+                        });
+                    }
                     FnOutputTy::Generator(g) => {
                         fn_call_body("sse", &[&g.yield_ty, &g.return_ty], &|_| {});
                     }
@@ -103,3 +114,15 @@ fn input_encoder(ctx: &Context, c: &mut CodeWriter, input_ty: &[Type], args: &[B
     ctx.struct_encoder(c, fields);
     c.line("]),");
 }
+
+/// Stream messages are encoded as field `0`.
+fn stream_encoder(ctx: &Context, c: &mut CodeWriter, ty: &Type) {
+    if matches!(ty, Type::Tuple(tys) if tys.is_empty()) {
+        return c.line("(_, _z: void) => $SE(_, []),");
+    }
+    let encoder = ctx.serde_ty(ty, "$E");
+    c.line(args!(
+        "(_, z: {}) => $SE(_, [[0, z, {encoder}]]),",
+        ctx.data_ty(ty)
+    ));
+}
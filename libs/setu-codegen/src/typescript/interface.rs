@@ -4,6 +4,7 @@ use type_id::{
     StructField, Type,
 };
 
+use super::fmt;
 use crate::{CodeWriter, Context};
 
 pub fn generate(c: &mut CodeWriter, ctx: &Context) {
@@ -41,19 +42,43 @@ pub fn generate(c: &mut CodeWriter, ctx: &Context) {
                 c.line(args!("export type {interface} ="));
                 c.scope(|c| {
                     for (_, EnumField { name, ty, .. }) in fields {
-                        let Some(kind) = enum_field_kind(ty) else {
-                            continue;
-                        };
-                        let value = match kind {
-                            EnumKind::Unit => args!(" "),
-                            EnumKind::Field(ty) => args!("; value: {} ", ctx.data_ty(ty)),
-                        };
+                        let value = fmt(|f| match enum_field_kind(ty) {
+                            EnumKind::Unit => f.write_str(" "),
+                            EnumKind::Field(ty) => {
+                                f.write_fmt(args!("; value: {} ", ctx.data_ty(ty)))
+                            }
+                            EnumKind::Tuple(items) => {
+                                let tys = items.iter().map(|(_, s)| s.ty.clone()).collect();
+                                f.write_fmt(args!("; value: {} ", ctx.data_ty(&Type::Tuple(tys))))
+                            }
+                            EnumKind::Struct(items) => {
+                                for (_, StructField { name, ty, .. }) in items {
+                                    match ty.optional() {
+                                        Some(ty) => {
+                                            f.write_fmt(args!("; {name}?: {}", ctx.data_ty(ty)))?
+                                        }
+                                        None => {
+                                            f.write_fmt(args!("; {name}: {}", ctx.data_ty(ty)))?
+                                        }
+                                    }
+                                }
+                                f.write_str(" ")
+                            }
+                        });
                         c.line(args!("| {{ type: {name:?}{value}}}"));
                     }
                 });
                 c.newline();
             }
-            ComplexDataType::Tuple { .. } => {}
+            ComplexDataType::Tuple { fields } => {
+                c.line(args!("export type {interface} = ["));
+                c.scope(|c| {
+                    for (_, StructField { ty, .. }) in fields {
+                        c.line(args!("{},", ctx.data_ty(ty)));
+                    }
+                });
+                c.line("];");
+            }
         }
     }
 }
@@ -89,7 +114,13 @@ fn generate_encoder(c: &mut CodeWriter, ctx: &Context, path: &PathIdent, data: &
                 });
             });
         }
-        ComplexDataType::Tuple { .. } => {}
+        ComplexDataType::Tuple { fields } => {
+            ctx.encoder_fn(c, path, "Struct", |c| {
+                let items = fields.iter().map(|(_, s)| (s.key, &s.ty));
+                c.line("let _ = this;");
+                c.line(args!("$TE(_, z, {});", ctx.tuple_fields(items, "$E")));
+            });
+        }
     }
 }
 
@@ -100,11 +131,7 @@ fn field_encoder(ctx: &Context, c: &mut CodeWriter, field: &EnumField) {
         discriminant: key,
     } = field;
 
-    let Some(kind) = enum_field_kind(ty) else {
-        return;
-    };
-
-    match kind {
+    match enum_field_kind(ty) {
         EnumKind::Unit => {
             c.line(args!(
                 "case {name:?}: return $FE(_, [{key}, false, _.Bool]);"
@@ -116,6 +143,19 @@ fn field_encoder(ctx: &Context, c: &mut CodeWriter, field: &EnumField) {
                 "case {name:?}: return $FE(_, [{key}, z.value, {decoder}]);"
             ));
         }
+        EnumKind::Tuple(items) => {
+            let items = items.iter().map(|(_, s)| (s.key, &s.ty));
+            c.line(args!(
+                "case {name:?}: return $FE(_, [{key}, z.value, _.Tuple({})]);",
+                ctx.tuple_fields(items, "$E")
+            ));
+        }
+        EnumKind::Struct(items) => {
+            c.line(args!(
+                "case {name:?}: return $FE(_, [{key}, z, _.Struct({})]);",
+                ctx.struct_fields(items, "$E")
+            ));
+        }
     }
 }
 
@@ -163,22 +203,34 @@ fn generate_decoder(c: &mut CodeWriter, ctx: &Context, path: &PathIdent, data: &
                             ty,
                             discriminant: key,
                         } = field;
-                        let Some(kind) = enum_field_kind(ty) else {
-                            continue;
-                        };
-                        match kind {
+                        match enum_field_kind(ty) {
                             EnumKind::Unit => c.line(args!("[{key}, {name:?}, _.Bool, 0],")),
                             EnumKind::Field(ty) => {
                                 let de = ctx.serde_ty(ty, "$D");
                                 c.line(args!("[{key}, {name:?}, {de}, 1],"))
                             }
+                            EnumKind::Tuple(items) => {
+                                let items = items.iter().map(|(_, s)| (s.key, &s.ty));
+                                let de = ctx.tuple_fields(items, "$D");
+                                c.line(args!("[{key}, {name:?}, _.Tuple({de}), 1],"))
+                            }
+                            EnumKind::Struct(items) => {
+                                let de = ctx.struct_fields(items, "$D");
+                                c.line(args!("[{key}, {name:?}, _.Struct({de}), 2],"))
+                            }
                         }
                     }
                 });
                 c.line("]);");
             });
         }
-        ComplexDataType::Tuple { .. } => {}
+        ComplexDataType::Tuple { fields } => {
+            ctx.decoder_fn(c, &interface, "Struct", |c| {
+                let items = fields.iter().map(|(_, s)| (s.key, &s.ty));
+                c.line("let _ = this;");
+                c.line(args!("return $TD(_, {});", ctx.tuple_fields(items, "$D")));
+            });
+        }
     }
 }
 
@@ -203,15 +255,17 @@ fn enum_numeric_repr(fields: &[(Attributes, EnumField)]) -> Option<(&str, &str)>
 enum EnumKind<'a> {
     Unit,
     Field(&'a Type),
+    /// Tuple variant of multiple fields, as `value: [..]`.
+    Tuple(&'a [(Attributes, StructField)]),
+    /// Fields of struct variant, next to `type`.
+    Struct(&'a [(Attributes, StructField)]),
 }
 
-fn enum_field_kind(ty: &EnumFieldType) -> Option<EnumKind<'_>> {
+fn enum_field_kind(ty: &EnumFieldType) -> EnumKind<'_> {
     match ty {
-        EnumFieldType::Tuple(items) if items.len() == 1 => {
-            let (_, ty) = &items[0];
-            Some(EnumKind::Field(ty))
-        }
-        EnumFieldType::Unit => Some(EnumKind::Unit),
-        _ => None,
+        EnumFieldType::Unit => EnumKind::Unit,
+        EnumFieldType::Tuple(items) if items.len() == 1 => EnumKind::Field(&items[0].1.ty),
+        EnumFieldType::Tuple(items) => EnumKind::Tuple(items),
+        EnumFieldType::Struct(items) => EnumKind::Struct(items),
     }
 }
@@ -3,10 +3,9 @@ pub mod interface;
 
 use std::format_args as args;
 use std::{fs, io, path::PathBuf};
-use type_id::{Ident, PathIdent, Type};
+use type_id::{Attributes, Ident, ListVariant, PathIdent, StructField, Type};
 
-use crate::symbol_trie::SymbolTrie;
-use crate::{CodeWriter, utils::copy_dir};
+use crate::{CodeWriter, invalid_input, utils::copy_dir};
 use crate::{Context, utils::fmt};
 pub use std::fmt::from_fn as fmt;
 
@@ -14,6 +13,9 @@ pub use std::fmt::from_fn as fmt;
 pub struct Config {
     pub out_dir: PathBuf,
     pub preserve_import_extension: bool,
+    /// Rpc output of `Result<T, E>` is unwrapped to `T`, and `E` is thrown as `ResultError`.
+    /// Otherwise it's returned as `$.lipi.Result<T, E>`.
+    pub throw_result_error: bool,
 }
 
 impl Config {
@@ -21,6 +23,7 @@ impl Config {
         Self {
             out_dir: path,
             preserve_import_extension: false,
+            throw_result_error: false,
         }
     }
 
@@ -28,6 +31,11 @@ impl Config {
         self.preserve_import_extension = true;
         self
     }
+
+    pub fn throw_result_error(mut self) -> Self {
+        self.throw_result_error = true;
+        self
+    }
 }
 
 impl Config {
@@ -43,7 +51,7 @@ impl Config {
             })?;
         }
 
-        let code = ctx.generate_typescript_code();
+        let code = ctx.generate_typescript_code(self)?;
         fs::write(self.out_dir.join("mod.ts"), code)
     }
 }
@@ -55,18 +63,23 @@ export const $etu = { RPC: $.RPC };
 const $FE = $.lipi.FieldEncoder;
 const $SE = $.lipi.StructEncoder;
 const $SD = $.lipi.StructDecoder;
+const $TE = $.lipi.TupleEncoder;
+const $TD = $.lipi.TupleDecoder;
 const $OD = $.lipi.OutputDecoder;
 const $ED = $.lipi.EnumDecoder;
 
 "#;
 
 impl Context {
-    pub fn generate_typescript_code(&self) -> String {
+    /// Fails with [io::ErrorKind::InvalidInput], naming the first type that can't be generated.
+    pub fn generate_typescript_code(&self, config: &Config) -> io::Result<String> {
+        self.check_types().map_err(invalid_input)?;
+
         let mut c = CodeWriter::new();
         c.buffer.push_str(TS_PRELUDE);
         interface::generate(&mut c, self);
-        function::generate(&mut c, self);
-        c.buffer
+        function::generate(&mut c, self, config);
+        Ok(c.buffer)
     }
 
     fn data_ty(&self, ty: &Type) -> fmt!(type) {
@@ -80,10 +93,10 @@ impl Context {
             | Type::I16
             | Type::I32 => f.write_str("number"),
 
-            Type::U64 | Type::I64 => f.write_str("bigint"),
+            Type::U64 | Type::I64 | Type::U128 | Type::I128 => f.write_str("bigint"),
 
             Type::Bool => f.write_str("boolean"),
-            Type::String => f.write_str("string"),
+            Type::String | Type::Char => f.write_str("string"),
 
             Type::Complex(path) => f.write_str(&self.symbol.interface_name(path)),
 
@@ -118,9 +131,12 @@ impl Context {
             }
 
             Type::Option(ty) => f.write_fmt(args!("{} | undefined", self.data_ty(ty))),
-            Type::Result(_) | Type::Char | Type::U128 | Type::I128 | Type::Other(_) => {
-                unimplemented!()
-            }
+            Type::Result(ty) => f.write_fmt(args!(
+                "$.lipi.Result<{}, {}>",
+                self.data_ty(&ty.0),
+                self.data_ty(&ty.1)
+            )),
+            Type::Other(_) => unreachable!("rejected by `check_types`"),
         })
     }
 
@@ -148,14 +164,25 @@ impl Context {
             Type::U16 => f.write_str("_.U16"),
             Type::U32 => f.write_str("_.U32"),
             Type::U64 => f.write_str("_.U64"),
+            Type::U128 => f.write_str("_.U128"),
 
             Type::I16 => f.write_str("_.I16"),
             Type::I32 => f.write_str("_.I32"),
             Type::I64 => f.write_str("_.I64"),
+            Type::I128 => f.write_str("_.I128"),
 
             Type::Bool => f.write_str("_.Bool"),
+            Type::Char => f.write_str("_.Char"),
             Type::String => f.write_str("_.Str"),
 
+            Type::List {
+                variant: ListVariant::Columns,
+                ty,
+            } => {
+                let (fields, is_tuple) = self.row_fields(ty);
+                let columns = if is_tuple { "TupleColumns" } else { "Columns" };
+                f.write_fmt(args!("_.{columns}({})", self.column_fields(fields, codex)))
+            }
             Type::Array { ty, .. } | Type::List { ty, .. } => match ty.as_ref() {
                 Type::U8 => f.write_str("_.ListU8"),
                 Type::I8 => f.write_str("_.ListI8"),
@@ -183,7 +210,79 @@ impl Context {
                 self.serde_ty(&ty.0, codex),
                 self.serde_ty(&ty.1, codex)
             )),
-            ty => unimplemented!("{ty:?}"),
+            Type::Tuple(tys) => {
+                let items = tys.iter().enumerate().map(|(key, ty)| (key as u32, ty));
+                f.write_fmt(args!("_.Tuple({})", self.tuple_fields(items, codex)))
+            }
+            // Decoder also checks `Ok` value, if it's required.
+            Type::Result(ty) => {
+                f.write_fmt(args!(
+                    "_.Result({}, {}",
+                    self.serde_ty(&ty.0, codex),
+                    self.serde_ty(&ty.1, codex)
+                ))?;
+                if codex == "$D" {
+                    f.write_fmt(args!(", {}", ty.0.optional().is_none()))?;
+                }
+                f.write_str(")")
+            }
+            Type::Other(_) => unreachable!("rejected by `check_types`"),
+        })
+    }
+
+    /// `[[key, encoder], ..]` or `[[key, decoder, required], ..]` of tuple items.
+    fn tuple_fields<'a, I>(&'a self, items: I, codex: &'a str) -> fmt!(type 'a)
+    where
+        I: Iterator<Item = (u32, &'a Type)> + Clone + 'a,
+    {
+        fmt(move |f| {
+            f.write_str("[")?;
+            for (key, ty) in items.clone() {
+                f.write_fmt(args!("[{key}, {}", self.serde_ty(ty, codex)))?;
+                if codex == "$D" {
+                    f.write_fmt(args!(", {}", ty.optional().is_none() as u8))?;
+                }
+                f.write_str("], ")?;
+            }
+            f.write_str("]")
+        })
+    }
+
+    /// `[[key, "name", encoder], ..]` or `[[key, "name", decoder, required], ..]` of struct fields.
+    fn struct_fields<'a>(
+        &'a self,
+        fields: &'a [(Attributes, StructField)],
+        codex: &'a str,
+    ) -> fmt!(type 'a) {
+        fmt(move |f| {
+            f.write_str("[")?;
+            for (_, StructField { key, name, ty }) in fields {
+                f.write_fmt(args!("[{key}, \"{name}\", {}", self.serde_ty(ty, codex)))?;
+                if codex == "$D" {
+                    f.write_fmt(args!(", {}", ty.optional().is_none() as u8))?;
+                }
+                f.write_str("], ")?;
+            }
+            f.write_str("]")
+        })
+    }
+
+    /// `[[key, "name", codec, required], ..]` of row fields, for `_.Columns`.
+    fn column_fields<'a>(
+        &'a self,
+        fields: &'a [(Attributes, StructField)],
+        codex: &'a str,
+    ) -> fmt!(type 'a) {
+        fmt(move |f| {
+            f.write_str("[")?;
+            for (_, StructField { key, name, ty }) in fields {
+                let required = ty.optional().is_none() as u8;
+                f.write_fmt(args!(
+                    "[{key}, \"{name}\", {}, {required}], ",
+                    self.serde_ty(ty, codex)
+                ))?;
+            }
+            f.write_str("]")
         })
     }
 
@@ -229,37 +328,3 @@ impl Context {
         c.line("},");
     }
 }
-
-mod cached {
-    use crate::utils::LocalCachedTable;
-    use std::rc::Rc;
-    use type_id::PathIdent;
-
-    thread_local! {
-       pub static SYMBOL: LocalCachedTable<PathIdent, str> = LocalCachedTable::new();
-    }
-
-    pub fn get_symbol(path: &PathIdent, init: impl FnOnce() -> String) -> Rc<str> {
-        SYMBOL.with(|cached| cached.get_or_insert_with(path.clone(), init))
-    }
-}
-
-impl SymbolTrie {
-    fn interface_name(&self, path: &PathIdent) -> std::rc::Rc<str> {
-        cached::get_symbol(path, || {
-            self.shortest_symbol(path)
-                .flat_map(|part| {
-                    let mut chars = part.chars();
-
-                    let ch = chars.next()?.to_ascii_uppercase();
-                    let rest = chars.as_str();
-
-                    Some(format!("{ch}{rest}"))
-                })
-                .collect::<Vec<_>>()
-                .into_iter()
-                .rev()
-                .collect()
-        })
-    }
-}
@@ -0,0 +1,67 @@
+use setu_type_info::{TypeInfo, diff, type_id::Type};
+use std::env;
+use std::fs;
+use std::process;
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+type Result<T = (), E = Error> = std::result::Result<T, E>;
+
+const HELP: &str = r#"setu-schema — compare snapshots of a setu service
+DESCRIPTION:
+    Snapshots are written by `TypeInfo::to_schema`.
+
+    `diff` prints every change between two snapshots,
+    and exits with code 1 if any of them is breaking.
+
+USAGE:
+    setu-schema diff OLD NEW
+    setu-schema fingerprint FILE
+
+ARGS:
+    OLD, NEW    Snapshots of the previous and the next version
+    FILE        Snapshot, whose rpcs and types are fingerprinted
+"#;
+
+fn main() -> Result {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args[..] {
+        ["diff", old, new] => {
+            let changes = diff(&read(old)?, &read(new)?);
+            for change in &changes {
+                println!("{change}");
+            }
+            if changes.iter().any(|change| change.breaking) {
+                process::exit(1);
+            }
+        }
+        ["fingerprint", file] => {
+            let info = read(file)?;
+            println!("{} service", info.fingerprint());
+
+            let mut fns: Vec<_> = info.fns.iter().collect();
+            fns.sort_by_key(|func| func.meta.index);
+            for func in fns {
+                let meta = &func.meta;
+                let fingerprint = info.fn_fingerprint(func);
+                println!("{fingerprint} rpc {} ({})", meta.ident, meta.index);
+            }
+            for path in info.registry.keys() {
+                let fingerprint = info.type_fingerprint(&Type::Complex(path.clone()));
+                println!("{fingerprint} type {path}");
+            }
+        }
+        ["-h" | "--help"] => print!("{HELP}"),
+        _ => {
+            eprint!("{HELP}");
+            process::exit(2);
+        }
+    }
+    Ok(())
+}
+
+fn read(path: &str) -> Result<TypeInfo> {
+    let schema = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+    Ok(TypeInfo::from_schema(&schema).map_err(|err| format!("{path}: {err}"))?)
+}
@@ -0,0 +1,359 @@
+use crate::fingerprint::is_bare_value;
+use crate::schema::TypeName;
+use crate::{FnMetaData, FnOutputTy, Func, GeneratorType, TypeInfo};
+use std::{collections::HashSet, fmt};
+use type_id::{
+    Attributes, ComplexDataType, EnumField, EnumFieldType, ListVariant, PathIdent, StructField,
+    Type,
+};
+
+/// A difference between two versions of a service. (See [diff])
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// Peers of the old version can't talk to the new one.
+    pub breaking: bool,
+    /// The rpc or type that has changed, e.g. ``rpc `add` (2)``.
+    pub item: String,
+    pub message: String,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = if self.breaking {
+            "breaking"
+        } else {
+            "compatible"
+        };
+        write!(f, "{severity}: {}: {}", self.item, self.message)
+    }
+}
+
+/// Compares two versions of a service, rpcs are matched by their id.
+///
+/// User defined types are compared by their fields, keys and discriminants, not by their path.
+/// So moving or renaming a type is not a change. Any change of a field type is breaking,
+/// even if it's wire-compatible (e.g. `u16` to `u32`).
+pub fn diff(old: &TypeInfo, new: &TypeInfo) -> Vec<Change> {
+    let mut diff = Diff {
+        old,
+        new,
+        compared: HashSet::new(),
+        changes: Vec::new(),
+    };
+    for f in &old.fns {
+        let item = rpc(&f.meta);
+        match find(new, |g| g.index == f.meta.index) {
+            Some(g) => diff.func(f, g),
+            None => match find(new, |g| g.ident == f.meta.ident) {
+                Some(g) => diff.push(
+                    true,
+                    item,
+                    format!("renumbered from {} to {}", f.meta.index, g.meta.index),
+                ),
+                None => diff.push(true, item, "removed"),
+            },
+        }
+    }
+    for g in &new.fns {
+        let exists = |f: &FnMetaData| f.index == g.meta.index || f.ident == g.meta.ident;
+        if find(old, exists).is_none() {
+            diff.push(false, rpc(&g.meta), "added");
+        }
+    }
+    diff.changes
+}
+
+fn find(info: &TypeInfo, f: impl Fn(&FnMetaData) -> bool) -> Option<&Func<FnMetaData>> {
+    info.fns.iter().find(|func| f(&func.meta))
+}
+
+fn rpc(meta: &FnMetaData) -> String {
+    format!("rpc `{}` ({})", meta.ident, meta.index)
+}
+
+struct Diff<'a> {
+    old: &'a TypeInfo,
+    new: &'a TypeInfo,
+    /// Pairs of user defined types, that are already compared.
+    compared: HashSet<(&'a PathIdent, &'a PathIdent)>,
+    changes: Vec<Change>,
+}
+
+impl<'a> Diff<'a> {
+    fn push(&mut self, breaking: bool, item: impl Into<String>, message: impl Into<String>) {
+        self.changes.push(Change {
+            breaking,
+            item: item.into(),
+            message: message.into(),
+        });
+    }
+
+    fn ty_changed(&mut self, item: &str, what: &str, old: &'a Type, new: &'a Type) {
+        if !self.ty(old, new) {
+            let msg = format!(
+                "{what} type changed from `{}` to `{}`",
+                TypeName(old),
+                TypeName(new)
+            );
+            self.push(true, item, msg);
+        }
+    }
+
+    fn func(&mut self, f: &'a Func<FnMetaData>, g: &'a Func<FnMetaData>) {
+        let item = rpc(&g.meta);
+        if f.meta.ident != g.meta.ident {
+            self.push(false, &item, format!("renamed from `{}`", f.meta.ident));
+        }
+
+        let arg = |meta: &FnMetaData, idx: usize| match meta.args.get(idx) {
+            Some(name) => format!("argument `{name}`"),
+            None => format!("argument {idx}"),
+        };
+        let len = f.input_ty.len().max(g.input_ty.len());
+        for idx in 0..len {
+            match (f.input_ty.get(idx), g.input_ty.get(idx)) {
+                (Some(old), Some(new)) => self.ty_changed(&item, &arg(&g.meta, idx), old, new),
+                (None, Some(new)) => match new.optional() {
+                    Some(_) => self.push(false, &item, format!("{} added", arg(&g.meta, idx))),
+                    None => self.push(true, &item, format!("required {} added", arg(&g.meta, idx))),
+                },
+                (Some(_), None) => {
+                    self.push(false, &item, format!("{} removed", arg(&f.meta, idx)))
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+
+        match (&f.stream, &g.stream) {
+            (Some(old), Some(new)) => self.generator(&item, "stream", old, new),
+            (None, Some(_)) => self.push(true, &item, "stream input added"),
+            (Some(_), None) => self.push(true, &item, "stream input removed"),
+            (None, None) => {}
+        }
+
+        match (&f.output_ty, &g.output_ty) {
+            (FnOutputTy::Return(old), FnOutputTy::Return(new)) => {
+                self.ty_changed(&item, "output", old, new)
+            }
+            (FnOutputTy::Generator(old), FnOutputTy::Generator(new)) => {
+                self.generator(&item, "output stream", old, new)
+            }
+            (FnOutputTy::Return(_), FnOutputTy::Generator(_)) => {
+                self.push(true, &item, "output changed to a stream")
+            }
+            (FnOutputTy::Generator(_), FnOutputTy::Return(_)) => {
+                self.push(true, &item, "output changed from a stream")
+            }
+        }
+    }
+
+    fn generator(
+        &mut self,
+        item: &str,
+        what: &str,
+        old: &'a GeneratorType,
+        new: &'a GeneratorType,
+    ) {
+        let yield_ty = format!("{what} message");
+        self.ty_changed(item, &yield_ty, &old.yield_ty, &new.yield_ty);
+        let return_ty = format!("{what} final message");
+        self.ty_changed(item, &return_ty, &old.return_ty, &new.return_ty);
+    }
+
+    /// Returns `false`, if the types have a different wire format.
+    ///
+    /// User defined types are compared separately, so their changes are reported by their path.
+    fn ty(&mut self, old: &'a Type, new: &'a Type) -> bool {
+        match (old, new) {
+            (Type::Complex(old), Type::Complex(new)) => {
+                self.complex(old, new);
+                true
+            }
+            (Type::Option(old), Type::Option(new)) => self.ty(old, new),
+            (Type::Result(old), Type::Result(new)) => {
+                self.ty(&old.0, &new.0) & self.ty(&old.1, &new.1)
+            }
+            (Type::Array { ty: old, len: a }, Type::Array { ty: new, len: b }) => {
+                a == b && self.ty(old, new)
+            }
+            (
+                Type::List {
+                    variant: a,
+                    ty: old,
+                },
+                Type::List {
+                    variant: b,
+                    ty: new,
+                },
+            ) => {
+                let is_table = |v: &ListVariant| *v == ListVariant::Columns;
+                is_table(a) == is_table(b) && self.ty(old, new)
+            }
+            (Type::Map { ty: old, .. }, Type::Map { ty: new, .. }) => {
+                self.ty(&old.0, &new.0) & self.ty(&old.1, &new.1)
+            }
+            (Type::Tuple(old), Type::Tuple(new)) => {
+                old.len() == new.len()
+                    && old
+                        .iter()
+                        .zip(new)
+                        .fold(true, |same, (old, new)| self.ty(old, new) & same)
+            }
+            (old, new) => old == new,
+        }
+    }
+
+    fn complex(&mut self, old_path: &'a PathIdent, new_path: &'a PathIdent) {
+        if !self.compared.insert((old_path, new_path)) {
+            return;
+        }
+        let (Some(old), Some(new)) = (
+            self.old.registry.get(old_path),
+            self.new.registry.get(new_path),
+        ) else {
+            return;
+        };
+        let item = format!("type `{new_path}`");
+        match (&old.ty, &new.ty) {
+            (
+                ComplexDataType::Struct { fields: old } | ComplexDataType::Tuple { fields: old },
+                ComplexDataType::Struct { fields: new } | ComplexDataType::Tuple { fields: new },
+            ) => self.fields(&item, old, new),
+            (
+                ComplexDataType::Enum {
+                    is_numeric: a,
+                    fields: old,
+                },
+                ComplexDataType::Enum {
+                    is_numeric: b,
+                    fields: new,
+                },
+            ) => {
+                if a != b {
+                    let msg = match b {
+                        true => "changed to a numeric enum",
+                        false => "changed from a numeric enum",
+                    };
+                    self.push(true, &item, msg);
+                }
+                self.variants(&item, old, new);
+            }
+            (ComplexDataType::Enum { .. }, _) => {
+                self.push(true, item, "changed from enum to struct")
+            }
+            (_, ComplexDataType::Enum { .. }) => {
+                self.push(true, item, "changed from struct to enum")
+            }
+        }
+    }
+
+    /// Fields are matched by their key, an optional field can be added or removed.
+    fn fields(
+        &mut self,
+        item: &str,
+        old: &'a [(Attributes, StructField)],
+        new: &'a [(Attributes, StructField)],
+    ) {
+        for (_, f) in old {
+            match new.iter().find(|(_, g)| g.key == f.key) {
+                Some((_, g)) => {
+                    if f.name != g.name {
+                        let msg =
+                            format!("field `{}` ({}) renamed from `{}`", g.name, g.key, f.name);
+                        self.push(false, item, msg);
+                    }
+                    let what = format!("field `{}` ({})", g.name, g.key);
+                    self.ty_changed(item, &what, &f.ty, &g.ty);
+                }
+                None => match f.ty.optional() {
+                    Some(_) => self.push(
+                        false,
+                        item,
+                        format!("field `{}` ({}) removed", f.name, f.key),
+                    ),
+                    None => {
+                        let msg = format!("required field `{}` ({}) removed", f.name, f.key);
+                        self.push(true, item, msg)
+                    }
+                },
+            }
+        }
+        for (_, g) in new {
+            if old.iter().any(|(_, f)| f.key == g.key) {
+                continue;
+            }
+            match g.ty.optional() {
+                Some(_) => self.push(false, item, format!("field `{}` ({}) added", g.name, g.key)),
+                None => {
+                    let msg = format!("required field `{}` ({}) added", g.name, g.key);
+                    self.push(true, item, msg)
+                }
+            }
+        }
+    }
+
+    /// Variants are matched by their discriminant.
+    fn variants(
+        &mut self,
+        item: &str,
+        old: &'a [(Attributes, EnumField)],
+        new: &'a [(Attributes, EnumField)],
+    ) {
+        let discriminant = |v: &EnumField| v.discriminant.to_string();
+
+        for (_, f) in old {
+            let Some((_, g)) = new.iter().find(|(_, g)| discriminant(g) == discriminant(f)) else {
+                match new.iter().find(|(_, g)| g.name == f.name) {
+                    Some((_, g)) => {
+                        let msg = format!(
+                            "variant `{}` renumbered from {} to {}",
+                            f.name, f.discriminant, g.discriminant
+                        );
+                        self.push(true, item, msg);
+                    }
+                    None => self.push(true, item, format!("variant `{}` removed", f.name)),
+                }
+                continue;
+            };
+            if f.name != g.name {
+                // Renamed, only if it has the same fields.
+                let same =
+                    self.old.variant_fingerprint(&f.ty) == self.new.variant_fingerprint(&g.ty);
+                if same && !new.iter().any(|(_, v)| v.name == f.name) {
+                    let msg = format!("variant `{}` renamed from `{}`", g.name, f.name);
+                    self.push(false, item, msg);
+                } else {
+                    let msg = format!(
+                        "discriminant {} reused by `{}`, was `{}`",
+                        g.discriminant, g.name, f.name
+                    );
+                    self.push(true, item, msg);
+                    continue;
+                }
+            }
+            let what = format!("variant `{}`", g.name);
+            match (&f.ty, &g.ty) {
+                (EnumFieldType::Unit, EnumFieldType::Unit) => {}
+                (old, new) if is_bare_value(old) != is_bare_value(new) => {
+                    self.push(true, item, format!("{what} fields changed"))
+                }
+                (
+                    EnumFieldType::Struct(old) | EnumFieldType::Tuple(old),
+                    EnumFieldType::Struct(new) | EnumFieldType::Tuple(new),
+                ) => {
+                    let item = format!("{item}: {what}");
+                    self.fields(&item, old, new);
+                }
+                _ => self.push(true, item, format!("{what} fields changed")),
+            }
+        }
+        for (_, g) in new {
+            let exists = |(_, f): &(Attributes, EnumField)| {
+                discriminant(f) == discriminant(g) || f.name == g.name
+            };
+            if !old.iter().any(exists) {
+                self.push(false, item, format!("variant `{}` added", g.name));
+            }
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{FnMetaData, FnOutputTy, Func, GeneratorType, TypeInfo};
+use std::fmt;
+use type_id::{
+    Attributes, ComplexDataType, EnumField, EnumFieldType, ListVariant, PathIdent, StructField,
+    Type, TypeRegistry,
+};
+
+/// Hash of the wire format of an rpc or a type.
+///
+/// It's stable across builds and platforms (64-bit FNV-1a). Names and docs are not part of it,
+/// as renaming a field or an argument is wire-compatible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fingerprint(pub u64);
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl TypeInfo {
+    /// Fingerprint of every rpc of the service.
+    pub fn fingerprint(&self) -> Fingerprint {
+        let mut fns: Vec<_> = self.fns.iter().collect();
+        fns.sort_by_key(|func| func.meta.index);
+
+        let mut h = Hasher::new(&self.registry);
+        for func in fns {
+            h.func(func);
+        }
+        h.finish()
+    }
+
+    /// Fingerprint of the rpc id, stream, arguments and output of `func`.
+    pub fn fn_fingerprint(&self, func: &Func<FnMetaData>) -> Fingerprint {
+        let mut h = Hasher::new(&self.registry);
+        h.func(func);
+        h.finish()
+    }
+
+    /// Fingerprint of `ty`, including every user defined type it refers to.
+    pub fn type_fingerprint(&self, ty: &Type) -> Fingerprint {
+        let mut h = Hasher::new(&self.registry);
+        h.ty(ty);
+        h.finish()
+    }
+
+    /// Fingerprint of an enum variant. (See [TypeInfo::type_fingerprint])
+    pub(crate) fn variant_fingerprint(&self, ty: &EnumFieldType) -> Fingerprint {
+        let mut h = Hasher::new(&self.registry);
+        h.variant(ty);
+        h.finish()
+    }
+}
+
+struct Hasher<'a> {
+    registry: &'a TypeRegistry,
+    hash: u64,
+    /// User defined types, that are being hashed. So that recursive types can be handled.
+    stack: Vec<&'a PathIdent>,
+}
+
+impl<'a> Hasher<'a> {
+    fn new(registry: &'a TypeRegistry) -> Self {
+        Self {
+            registry,
+            hash: 0xcbf2_9ce4_8422_2325,
+            stack: Vec::new(),
+        }
+    }
+
+    fn finish(self) -> Fingerprint {
+        Fingerprint(self.hash)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Tags are terminated, so that they can't be confused with each other.
+    fn tag(&mut self, tag: &str) {
+        self.bytes(tag.as_bytes());
+        self.bytes(&[0]);
+    }
+
+    fn num(&mut self, num: u64) {
+        self.bytes(&num.to_le_bytes());
+    }
+
+    fn func(&mut self, func: &'a Func<FnMetaData>) {
+        self.num(func.meta.index.into());
+        match &func.stream {
+            Some(stream) => {
+                self.tag("s");
+                self.generator(stream);
+            }
+            None => self.tag("_"),
+        }
+        self.num(func.input_ty.len() as u64);
+        for ty in &func.input_ty {
+            self.ty(ty);
+        }
+        match &func.output_ty {
+            FnOutputTy::Return(ty) => {
+                self.tag("r");
+                self.ty(ty);
+            }
+            FnOutputTy::Generator(generator) => {
+                self.tag("g");
+                self.generator(generator);
+            }
+        }
+    }
+
+    fn generator(&mut self, generator: &'a GeneratorType) {
+        self.ty(&generator.yield_ty);
+        self.ty(&generator.return_ty);
+    }
+
+    fn ty(&mut self, ty: &'a Type) {
+        match ty {
+            Type::U8 => self.tag("u8"),
+            Type::U16 => self.tag("u16"),
+            Type::U32 => self.tag("u32"),
+            Type::U64 => self.tag("u64"),
+            Type::U128 => self.tag("u128"),
+            Type::I8 => self.tag("i8"),
+            Type::I16 => self.tag("i16"),
+            Type::I32 => self.tag("i32"),
+            Type::I64 => self.tag("i64"),
+            Type::I128 => self.tag("i128"),
+            Type::F32 => self.tag("f32"),
+            Type::F64 => self.tag("f64"),
+            Type::Bool => self.tag("bool"),
+            Type::Char => self.tag("char"),
+            Type::String => self.tag("str"),
+            Type::Option(ty) => {
+                self.tag("?");
+                self.ty(ty);
+            }
+            Type::Result(ty) => {
+                self.tag("result");
+                self.ty(&ty.0);
+                self.ty(&ty.1);
+            }
+            Type::Other(_) => self.tag("_"),
+            Type::Array { ty, len } => {
+                self.tag("array");
+                self.num(*len as u64);
+                self.ty(ty);
+            }
+            // Every list, except a table, has the same wire format.
+            Type::List { variant, ty } => {
+                match variant {
+                    ListVariant::Columns => self.tag("table"),
+                    _ => self.tag("list"),
+                }
+                self.ty(ty);
+            }
+            Type::Map { ty, .. } => {
+                self.tag("map");
+                self.ty(&ty.0);
+                self.ty(&ty.1);
+            }
+            Type::Tuple(tys) => {
+                self.tag("tuple");
+                self.num(tys.len() as u64);
+                for ty in tys {
+                    self.ty(ty);
+                }
+            }
+            Type::Complex(path) => self.complex(path),
+        }
+    }
+
+    fn complex(&mut self, path: &'a PathIdent) {
+        if let Some(depth) = self.stack.iter().position(|p| *p == path) {
+            self.tag("recursive");
+            self.num(depth as u64);
+            return;
+        }
+        let Some(data) = self.registry.get(path) else {
+            self.tag("unknown");
+            return;
+        };
+        self.stack.push(path);
+        match &data.ty {
+            // Struct and tuple have the same wire format.
+            ComplexDataType::Struct { fields } | ComplexDataType::Tuple { fields } => {
+                self.tag("struct");
+                self.fields(fields);
+            }
+            ComplexDataType::Enum { is_numeric, fields } => {
+                self.tag(if *is_numeric { "numeric" } else { "enum" });
+                let mut variants: Vec<_> = fields.iter().map(|(_, v)| v).collect();
+                variants.sort_by_key(|v| v.discriminant.to_string());
+
+                self.num(variants.len() as u64);
+                for EnumField {
+                    ty, discriminant, ..
+                } in variants
+                {
+                    self.tag(&discriminant.to_string());
+                    self.variant(ty);
+                }
+            }
+        }
+        self.stack.pop();
+    }
+
+    fn variant(&mut self, ty: &'a EnumFieldType) {
+        match ty {
+            EnumFieldType::Unit => self.tag("unit"),
+            EnumFieldType::Tuple(fields) if is_bare_value(ty) => {
+                self.tag("value");
+                self.ty(&fields[0].1.ty);
+            }
+            EnumFieldType::Struct(fields) | EnumFieldType::Tuple(fields) => {
+                self.tag("struct");
+                self.fields(fields);
+            }
+        }
+    }
+
+    /// Fields are keyed, so their order doesn't matter.
+    fn fields(&mut self, fields: &'a [(Attributes, StructField)]) {
+        let mut fields: Vec<_> = fields.iter().map(|(_, f)| f).collect();
+        fields.sort_by_key(|f| f.key);
+
+        self.num(fields.len() as u64);
+        for field in fields {
+            self.num(field.key.into());
+            self.ty(&field.ty);
+        }
+    }
+}
+
+/// Variant with a single unnamed field, that is encoded as the field value, instead of a struct.
+pub(crate) fn is_bare_value(ty: &EnumFieldType) -> bool {
+    matches!(ty, EnumFieldType::Tuple(fields) if fields.len() == 1)
+}
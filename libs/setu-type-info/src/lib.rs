@@ -1,5 +1,14 @@
+mod diff;
+mod fingerprint;
+mod reflection;
+mod schema;
+
 use std::sync::Arc;
 
+pub use diff::{Change, diff};
+pub use fingerprint::Fingerprint;
+pub use reflection::{REFLECTION_RPC_ID, Reflection};
+pub use schema::SchemaError;
 pub use type_id;
 
 use type_id::{Ident, Type, TypeId, TypeRegistry};
@@ -111,6 +120,18 @@ where
     Fut::Output: TypeId,
 {
     fn fn_output_ty(c: &mut TypeRegistry) -> FnOutputTy {
-        FnOutputTy::Return(<Fut::Output as TypeId>::ty(c))
+        FnOutputTy::Return(output_ty(<Fut::Output as TypeId>::ty(c)))
+    }
+}
+
+/// Marker of an error type, that ends an rpc with an error trailer, instead of the output.
+#[derive(Debug)]
+pub struct ErrorType;
+
+/// Output type of `Result<T, E>` is `T`, if `E` is an [ErrorType].
+pub fn output_ty(ty: Type) -> Type {
+    match ty {
+        Type::Result(ty) if matches!(&ty.1, Type::Other(e) if e.0.is::<ErrorType>()) => ty.0,
+        ty => ty,
     }
 }
@@ -0,0 +1,484 @@
+use crate::{FnMetaData, FnOutputTy, Func, GeneratorType, TypeInfo};
+use lipi::{Decode, Encode};
+use std::sync::Arc;
+use type_id::{
+    Attributes, ComplexData, ComplexDataType, Discriminant, EnumField, EnumFieldType, Ident,
+    ListVariant, MapVariant, OtherType, PathIdent, StructField, Type,
+};
+
+/// Rpc id of the reflection rpc, reserved by `export!` with `#![reflection]`.
+pub const REFLECTION_RPC_ID: u16 = u16::MAX;
+
+/// Output of the reflection rpc, a lipi encoded [TypeInfo], including docs.
+///
+/// Converts from and into [TypeInfo]. User defined types are referred to by their path.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct Reflection {
+    #[key = 0]
+    rpcs: Vec<Rpc>,
+    #[key = 1]
+    types: Vec<TypeDef>,
+}
+
+impl TypeInfo {
+    /// Decodes the output of the reflection rpc. (See [Reflection])
+    pub fn from_reflection(mut bytes: &[u8]) -> lipi::Result<TypeInfo> {
+        Reflection::decode(&mut bytes).map(Into::into)
+    }
+}
+
+impl From<&TypeInfo> for Reflection {
+    fn from(info: &TypeInfo) -> Self {
+        Reflection {
+            rpcs: info.fns.iter().map(Rpc::from).collect(),
+            types: info
+                .registry
+                .iter()
+                .map(|(path, data)| TypeDef {
+                    path: path.to_string(),
+                    docs: data.attrs.docs.clone(),
+                    kind: Kind::from(&data.ty),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Reflection> for TypeInfo {
+    fn from(reflection: Reflection) -> Self {
+        let mut info = TypeInfo {
+            fns: reflection.rpcs.into_iter().map(Func::from).collect(),
+            ..TypeInfo::default()
+        };
+        for TypeDef { path, docs, kind } in reflection.types {
+            let data = ComplexData {
+                attrs: Attributes::docs(docs),
+                ty: kind.into(),
+            };
+            info.registry.insert(PathIdent::from(path), data);
+        }
+        info
+    }
+}
+
+// ---------------------------------------- Rpc ----------------------------------------
+//
+// Following types are `pub`, as `Decode` exposes field types. But they aren't exported.
+
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct Rpc {
+    #[key = 0]
+    id: u16,
+    #[key = 1]
+    name: String,
+    #[key = 2]
+    docs: String,
+    #[key = 3]
+    args: Vec<String>,
+    #[key = 4]
+    input: Vec<Ty>,
+    #[key = 5]
+    stream: Option<Generator>,
+    #[key = 6]
+    output: Output,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct Generator {
+    #[key = 0]
+    yield_ty: Ty,
+    #[key = 1]
+    return_ty: Ty,
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Encode, Decode)]
+pub enum Output {
+    Return(Ty) = 0,
+    Stream(Generator) = 1,
+}
+
+impl From<&Func<FnMetaData>> for Rpc {
+    fn from(func: &Func<FnMetaData>) -> Self {
+        let meta = &func.meta;
+        Rpc {
+            id: meta.index,
+            name: meta.ident.to_string(),
+            docs: meta.docs.clone(),
+            args: meta.args.iter().map(ToString::to_string).collect(),
+            input: func.input_ty.iter().map(Ty::from).collect(),
+            stream: func.stream.as_deref().map(Generator::from),
+            output: match &func.output_ty {
+                FnOutputTy::Return(ty) => Output::Return(ty.into()),
+                FnOutputTy::Generator(generator) => Output::Stream(generator.into()),
+            },
+        }
+    }
+}
+
+impl From<Rpc> for Func<FnMetaData> {
+    fn from(rpc: Rpc) -> Self {
+        Func {
+            stream: rpc.stream.map(|stream| Arc::new(stream.into())),
+            input_ty: rpc.input.into_iter().map(Type::from).collect(),
+            output_ty: match rpc.output {
+                Output::Return(ty) => FnOutputTy::Return(ty.into()),
+                Output::Stream(generator) => FnOutputTy::Generator(generator.into()),
+            },
+            meta: FnMetaData {
+                docs: rpc.docs,
+                index: rpc.id,
+                ident: Ident::from(rpc.name),
+                args: rpc.args.into_iter().map(Ident::from).collect(),
+            },
+        }
+    }
+}
+
+impl From<&GeneratorType> for Generator {
+    fn from(generator: &GeneratorType) -> Self {
+        Generator {
+            yield_ty: (&generator.yield_ty).into(),
+            return_ty: (&generator.return_ty).into(),
+        }
+    }
+}
+
+impl From<Generator> for GeneratorType {
+    fn from(generator: Generator) -> Self {
+        GeneratorType {
+            yield_ty: generator.yield_ty.into(),
+            return_ty: generator.return_ty.into(),
+        }
+    }
+}
+
+// ---------------------------------------- Type ---------------------------------------
+
+#[repr(u8)]
+#[derive(Debug, Clone, Encode, Decode)]
+pub enum Ty {
+    U8 = 0,
+    U16 = 1,
+    U32 = 2,
+    U64 = 3,
+    U128 = 4,
+    I8 = 5,
+    I16 = 6,
+    I32 = 7,
+    I64 = 8,
+    I128 = 9,
+    F32 = 10,
+    F64 = 11,
+    Bool = 12,
+    Char = 13,
+    String = 14,
+    Option(Box<Ty>) = 15,
+    Result(Box<Ty>, Box<Ty>) = 16,
+    /// Types that are only known to the server.
+    Other = 17,
+    Array(Box<Ty>, u64) = 18,
+    List(ListKind, Box<Ty>) = 19,
+    Map(MapKind, Box<Ty>, Box<Ty>) = 20,
+    Tuple(Vec<Ty>) = 21,
+    Complex(String) = 22,
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Encode, Decode)]
+#[numeric]
+pub enum ListKind {
+    BTreeSet = 0,
+    HashSet = 1,
+    BinaryHeap = 2,
+    LinkedList = 3,
+    VecDeque = 4,
+    Vec = 5,
+    Columns = 6,
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Encode, Decode)]
+#[numeric]
+pub enum MapKind {
+    HashMap = 0,
+    BTreeMap = 1,
+}
+
+impl From<&Type> for Ty {
+    fn from(ty: &Type) -> Self {
+        let boxed = |ty: &Type| Box::new(Ty::from(ty));
+        match ty {
+            Type::U8 => Ty::U8,
+            Type::U16 => Ty::U16,
+            Type::U32 => Ty::U32,
+            Type::U64 => Ty::U64,
+            Type::U128 => Ty::U128,
+            Type::I8 => Ty::I8,
+            Type::I16 => Ty::I16,
+            Type::I32 => Ty::I32,
+            Type::I64 => Ty::I64,
+            Type::I128 => Ty::I128,
+            Type::F32 => Ty::F32,
+            Type::F64 => Ty::F64,
+            Type::Bool => Ty::Bool,
+            Type::Char => Ty::Char,
+            Type::String => Ty::String,
+            Type::Option(ty) => Ty::Option(boxed(ty)),
+            Type::Result(ty) => Ty::Result(boxed(&ty.0), boxed(&ty.1)),
+            Type::Other(_) => Ty::Other,
+            Type::Array { ty, len } => Ty::Array(boxed(ty), *len as u64),
+            Type::List { variant, ty } => {
+                let variant = match variant {
+                    ListVariant::BTreeSet => ListKind::BTreeSet,
+                    ListVariant::HashSet => ListKind::HashSet,
+                    ListVariant::BinaryHeap => ListKind::BinaryHeap,
+                    ListVariant::LinkedList => ListKind::LinkedList,
+                    ListVariant::VecDeque => ListKind::VecDeque,
+                    ListVariant::Vec => ListKind::Vec,
+                    ListVariant::Columns => ListKind::Columns,
+                };
+                Ty::List(variant, boxed(ty))
+            }
+            Type::Map { variant, ty } => {
+                let variant = match variant {
+                    MapVariant::HashMap => MapKind::HashMap,
+                    MapVariant::BTreeMap => MapKind::BTreeMap,
+                };
+                Ty::Map(variant, boxed(&ty.0), boxed(&ty.1))
+            }
+            Type::Tuple(tys) => Ty::Tuple(tys.iter().map(Ty::from).collect()),
+            Type::Complex(path) => Ty::Complex(path.to_string()),
+        }
+    }
+}
+
+impl From<Ty> for Type {
+    fn from(ty: Ty) -> Self {
+        let boxed = |ty: Box<Ty>| Box::new(Type::from(*ty));
+        match ty {
+            Ty::U8 => Type::U8,
+            Ty::U16 => Type::U16,
+            Ty::U32 => Type::U32,
+            Ty::U64 => Type::U64,
+            Ty::U128 => Type::U128,
+            Ty::I8 => Type::I8,
+            Ty::I16 => Type::I16,
+            Ty::I32 => Type::I32,
+            Ty::I64 => Type::I64,
+            Ty::I128 => Type::I128,
+            Ty::F32 => Type::F32,
+            Ty::F64 => Type::F64,
+            Ty::Bool => Type::Bool,
+            Ty::Char => Type::Char,
+            Ty::String => Type::String,
+            Ty::Option(ty) => Type::Option(boxed(ty)),
+            Ty::Result(ok, err) => Type::Result(Box::new(((*ok).into(), (*err).into()))),
+            Ty::Other => Type::Other(OtherType(Arc::new(()))),
+            Ty::Array(ty, len) => Type::Array {
+                ty: boxed(ty),
+                len: len as usize,
+            },
+            Ty::List(variant, ty) => Type::List {
+                variant: match variant {
+                    ListKind::BTreeSet => ListVariant::BTreeSet,
+                    ListKind::HashSet => ListVariant::HashSet,
+                    ListKind::BinaryHeap => ListVariant::BinaryHeap,
+                    ListKind::LinkedList => ListVariant::LinkedList,
+                    ListKind::VecDeque => ListVariant::VecDeque,
+                    ListKind::Vec => ListVariant::Vec,
+                    ListKind::Columns => ListVariant::Columns,
+                },
+                ty: boxed(ty),
+            },
+            Ty::Map(variant, key, val) => Type::Map {
+                variant: match variant {
+                    MapKind::HashMap => MapVariant::HashMap,
+                    MapKind::BTreeMap => MapVariant::BTreeMap,
+                },
+                ty: Box::new(((*key).into(), (*val).into())),
+            },
+            Ty::Tuple(tys) => Type::Tuple(tys.into_iter().map(Type::from).collect()),
+            Ty::Complex(path) => Type::Complex(PathIdent::from(path)),
+        }
+    }
+}
+
+// ------------------------------------ User Defined -----------------------------------
+
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct TypeDef {
+    #[key = 0]
+    path: String,
+    #[key = 1]
+    docs: String,
+    #[key = 2]
+    kind: Kind,
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Encode, Decode)]
+pub enum Kind {
+    Struct(Vec<Field>) = 0,
+    Tuple(Vec<Field>) = 1,
+    Enum {
+        #[key = 0]
+        is_numeric: bool,
+        #[key = 1]
+        variants: Vec<Variant>,
+    } = 2,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct Field {
+    #[key = 0]
+    key: u32,
+    #[key = 1]
+    name: String,
+    #[key = 2]
+    docs: String,
+    #[key = 3]
+    ty: Ty,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct Variant {
+    #[key = 0]
+    name: String,
+    #[key = 1]
+    docs: String,
+    #[key = 2]
+    discriminant: Option<Repr>,
+    #[key = 3]
+    fields: Fields,
+}
+
+/// Discriminant of a variant, with the `repr` type of its enum.
+#[repr(u8)]
+#[derive(Debug, Clone, Encode, Decode)]
+pub enum Repr {
+    U8(u8) = 0,
+    U16(u16) = 1,
+    U32(u32) = 2,
+    U64(u64) = 3,
+    I8(i8) = 4,
+    I16(i16) = 5,
+    I32(i32) = 6,
+    I64(i64) = 7,
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Encode, Decode)]
+pub enum Fields {
+    Unit = 0,
+    Struct(Vec<Field>) = 1,
+    Tuple(Vec<Field>) = 2,
+}
+
+fn fields(fields: &[(Attributes, StructField)]) -> Vec<Field> {
+    fields
+        .iter()
+        .map(|(attrs, field)| Field {
+            key: field.key,
+            name: field.name.to_string(),
+            docs: attrs.docs.clone(),
+            ty: (&field.ty).into(),
+        })
+        .collect()
+}
+
+fn struct_fields(fields: Vec<Field>) -> Vec<(Attributes, StructField)> {
+    fields
+        .into_iter()
+        .map(|field| {
+            let attrs = Attributes::docs(field.docs);
+            let field = StructField {
+                key: field.key,
+                name: Ident::from(field.name),
+                ty: field.ty.into(),
+            };
+            (attrs, field)
+        })
+        .collect()
+}
+
+impl From<&ComplexDataType> for Kind {
+    fn from(ty: &ComplexDataType) -> Self {
+        match ty {
+            ComplexDataType::Struct { fields: f } => Kind::Struct(fields(f)),
+            ComplexDataType::Tuple { fields: f } => Kind::Tuple(fields(f)),
+            ComplexDataType::Enum { is_numeric, fields } => Kind::Enum {
+                is_numeric: *is_numeric,
+                variants: fields.iter().map(Variant::from).collect(),
+            },
+        }
+    }
+}
+
+impl From<Kind> for ComplexDataType {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Struct(fields) => ComplexDataType::as_struct(struct_fields(fields)),
+            Kind::Tuple(fields) => ComplexDataType::as_tuple(struct_fields(fields)),
+            Kind::Enum {
+                is_numeric,
+                variants,
+            } => {
+                let variants = variants.into_iter().map(<_>::from).collect();
+                ComplexDataType::as_enum(is_numeric, variants)
+            }
+        }
+    }
+}
+
+impl From<&(Attributes, EnumField)> for Variant {
+    fn from((attrs, variant): &(Attributes, EnumField)) -> Self {
+        let discriminant = match variant.discriminant {
+            Discriminant::U8(v) => Some(Repr::U8(v)),
+            Discriminant::U16(v) => Some(Repr::U16(v)),
+            Discriminant::U32(v) => Some(Repr::U32(v)),
+            Discriminant::U64(v) => Some(Repr::U64(v)),
+            Discriminant::I8(v) => Some(Repr::I8(v)),
+            Discriminant::I16(v) => Some(Repr::I16(v)),
+            Discriminant::I32(v) => Some(Repr::I32(v)),
+            Discriminant::I64(v) => Some(Repr::I64(v)),
+            Discriminant::None => None,
+        };
+        Variant {
+            name: variant.name.to_string(),
+            docs: attrs.docs.clone(),
+            discriminant,
+            fields: match &variant.ty {
+                EnumFieldType::Unit => Fields::Unit,
+                EnumFieldType::Struct(f) => Fields::Struct(fields(f)),
+                EnumFieldType::Tuple(f) => Fields::Tuple(fields(f)),
+            },
+        }
+    }
+}
+
+impl From<Variant> for (Attributes, EnumField) {
+    fn from(variant: Variant) -> Self {
+        let discriminant = match variant.discriminant {
+            Some(Repr::U8(v)) => Discriminant::U8(v),
+            Some(Repr::U16(v)) => Discriminant::U16(v),
+            Some(Repr::U32(v)) => Discriminant::U32(v),
+            Some(Repr::U64(v)) => Discriminant::U64(v),
+            Some(Repr::I8(v)) => Discriminant::I8(v),
+            Some(Repr::I16(v)) => Discriminant::I16(v),
+            Some(Repr::I32(v)) => Discriminant::I32(v),
+            Some(Repr::I64(v)) => Discriminant::I64(v),
+            None => Discriminant::None,
+        };
+        let field = EnumField {
+            name: Ident::from(variant.name),
+            ty: match variant.fields {
+                Fields::Unit => EnumFieldType::Unit,
+                Fields::Struct(fields) => EnumFieldType::as_struct(struct_fields(fields)),
+                Fields::Tuple(fields) => EnumFieldType::as_tuple(struct_fields(fields)),
+            },
+            discriminant,
+        };
+        (Attributes::docs(variant.docs), field)
+    }
+}
@@ -0,0 +1,570 @@
+use crate::{FnMetaData, FnOutputTy, Func, GeneratorType, TypeInfo};
+use std::{error::Error, fmt, iter::Peekable, str::FromStr, sync::Arc};
+use type_id::{
+    Attributes, ComplexData, ComplexDataType, Discriminant, EnumField, EnumFieldType, Ident,
+    ListVariant, MapVariant, PathIdent, StructField, Type,
+};
+
+impl TypeInfo {
+    /// Text snapshot of every rpc and type, that is stable across builds.
+    ///
+    /// ```text
+    /// fn 2 add(a: i32, b: i32) -> i32
+    /// fn 12 chat(prefix: String, stream<String, ()>) -> stream<String, u32>
+    ///
+    /// struct app::Point
+    ///     0 x: i32
+    ///     1 y: Option<i32>
+    ///
+    /// enum app::Shape: u8
+    ///     0 Dot
+    ///     1 Circle {
+    ///         0 radius: f64
+    ///     }
+    /// ```
+    ///
+    /// Docs are not part of the snapshot.
+    pub fn to_schema(&self) -> String {
+        Schema(self).to_string()
+    }
+
+    /// Parses a snapshot, written by [TypeInfo::to_schema].
+    pub fn from_schema(schema: &str) -> Result<TypeInfo, SchemaError> {
+        let mut info = TypeInfo::default();
+        let mut lines = schema
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| (idx + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .peekable();
+
+        while let Some((line, text)) = lines.next() {
+            let err = |msg| SchemaError { line, msg };
+            let (kw, rest) = text.split_once(' ').unwrap_or((text, ""));
+            let mut c = Cursor(rest);
+            match kw {
+                "fn" => {
+                    let f = c.func().map_err(err)?;
+                    if info.fns.iter().any(|g| g.meta.index == f.meta.index) {
+                        return Err(err(format!("duplicate rpc id: {}", f.meta.index)));
+                    }
+                    info.fns.push(f);
+                }
+                "struct" | "tuple" | "enum" => {
+                    let is_numeric = kw == "enum" && c.eat("numeric ");
+                    let path = PathIdent::from(c.ident().map_err(err)?);
+                    let ty = if kw == "enum" {
+                        let repr = if c.eat(":") { Some(c.ident()) } else { None };
+                        let repr = repr.transpose().map_err(err)?;
+                        c.end().map_err(err)?;
+                        ComplexDataType::as_enum(is_numeric, variants(&mut lines, repr)?)
+                    } else {
+                        c.end().map_err(err)?;
+                        let fields = fields(&mut lines, None)?;
+                        match kw {
+                            "struct" => ComplexDataType::as_struct(fields),
+                            _ => ComplexDataType::as_tuple(fields),
+                        }
+                    };
+                    let data = ComplexData {
+                        attrs: Attributes::default(),
+                        ty,
+                    };
+                    if info.registry.insert(path.clone(), data).is_some() {
+                        return Err(err(format!("duplicate type: `{path}`")));
+                    }
+                }
+                _ => {
+                    return Err(err(format!(
+                        "expected `fn`, `struct`, `tuple` or `enum`, found `{kw}`"
+                    )));
+                }
+            }
+        }
+        Ok(info)
+    }
+}
+
+#[derive(Debug)]
+pub struct SchemaError {
+    pub line: usize,
+    pub msg: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.msg)
+    }
+}
+
+impl Error for SchemaError {}
+
+// ------------------------------- Writer ------------------------------------
+
+struct Schema<'a>(&'a TypeInfo);
+
+impl fmt::Display for Schema<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fns: Vec<_> = self.0.fns.iter().collect();
+        fns.sort_by_key(|func| func.meta.index);
+
+        for func in fns {
+            let FnMetaData {
+                index, ident, args, ..
+            } = &func.meta;
+
+            write!(f, "fn {index} {ident}(")?;
+            for (idx, ty) in func.input_ty.iter().enumerate() {
+                if idx > 0 {
+                    f.write_str(", ")?;
+                }
+                let name = args.get(idx).map_or("_", |arg| arg);
+                write!(f, "{name}: {}", TypeName(ty))?;
+            }
+            if let Some(stream) = &func.stream {
+                if !func.input_ty.is_empty() {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", Stream(stream))?;
+            }
+            match &func.output_ty {
+                FnOutputTy::Return(ty) => writeln!(f, ") -> {}", TypeName(ty))?,
+                FnOutputTy::Generator(ty) => writeln!(f, ") -> {}", Stream(ty))?,
+            }
+        }
+
+        for (path, data) in self.0.registry.iter() {
+            f.write_str("\n")?;
+            match &data.ty {
+                ComplexDataType::Struct { fields } => {
+                    writeln!(f, "struct {path}")?;
+                    write_fields(f, fields, 1)?;
+                }
+                ComplexDataType::Tuple { fields } => {
+                    writeln!(f, "tuple {path}")?;
+                    write_fields(f, fields, 1)?;
+                }
+                ComplexDataType::Enum { is_numeric, fields } => {
+                    f.write_str("enum ")?;
+                    if *is_numeric {
+                        f.write_str("numeric ")?;
+                    }
+                    f.write_str(path)?;
+                    match fields.iter().find_map(|(_, v)| repr(&v.discriminant)) {
+                        Some(repr) => writeln!(f, ": {repr}")?,
+                        None => f.write_str("\n")?,
+                    }
+                    for (_, variant) in fields {
+                        write_variant(f, variant)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_fields(
+    f: &mut fmt::Formatter,
+    fields: &[(Attributes, StructField)],
+    depth: usize,
+) -> fmt::Result {
+    for (_, StructField { key, name, ty }) in fields {
+        writeln!(
+            f,
+            "{:indent$}{key} {name}: {}",
+            "",
+            TypeName(ty),
+            indent = depth * 4
+        )?;
+    }
+    Ok(())
+}
+
+fn write_variant(f: &mut fmt::Formatter, variant: &EnumField) -> fmt::Result {
+    let EnumField {
+        name,
+        ty,
+        discriminant,
+    } = variant;
+
+    match discriminant {
+        Discriminant::None => write!(f, "    _ {name}")?,
+        discriminant => write!(f, "    {discriminant} {name}")?,
+    }
+    match ty {
+        EnumFieldType::Unit => writeln!(f),
+        EnumFieldType::Struct(fields) => {
+            writeln!(f, " {{")?;
+            write_fields(f, fields, 2)?;
+            writeln!(f, "    }}")
+        }
+        EnumFieldType::Tuple(fields) => {
+            writeln!(f, " (")?;
+            write_fields(f, fields, 2)?;
+            writeln!(f, "    )")
+        }
+    }
+}
+
+fn repr(discriminant: &Discriminant) -> Option<&'static str> {
+    Some(match discriminant {
+        Discriminant::U8(_) => "u8",
+        Discriminant::U16(_) => "u16",
+        Discriminant::U32(_) => "u32",
+        Discriminant::U64(_) => "u64",
+        Discriminant::I8(_) => "i8",
+        Discriminant::I16(_) => "i16",
+        Discriminant::I32(_) => "i32",
+        Discriminant::I64(_) => "i64",
+        Discriminant::None => return None,
+    })
+}
+
+struct Stream<'a>(&'a GeneratorType);
+
+impl fmt::Display for Stream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let GeneratorType {
+            yield_ty,
+            return_ty,
+        } = self.0;
+        write!(f, "stream<{}, {}>", TypeName(yield_ty), TypeName(return_ty))
+    }
+}
+
+/// Type, as written in the schema.
+pub(crate) struct TypeName<'a>(pub &'a Type);
+
+impl fmt::Display for TypeName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Type::U8 => f.write_str("u8"),
+            Type::U16 => f.write_str("u16"),
+            Type::U32 => f.write_str("u32"),
+            Type::U64 => f.write_str("u64"),
+            Type::U128 => f.write_str("u128"),
+            Type::I8 => f.write_str("i8"),
+            Type::I16 => f.write_str("i16"),
+            Type::I32 => f.write_str("i32"),
+            Type::I64 => f.write_str("i64"),
+            Type::I128 => f.write_str("i128"),
+            Type::F32 => f.write_str("f32"),
+            Type::F64 => f.write_str("f64"),
+            Type::Bool => f.write_str("bool"),
+            Type::Char => f.write_str("char"),
+            Type::String => f.write_str("String"),
+            Type::Option(ty) => write!(f, "Option<{}>", TypeName(ty)),
+            Type::Result(ty) => write!(f, "Result<{}, {}>", TypeName(&ty.0), TypeName(&ty.1)),
+            Type::Other(_) => f.write_str("_"),
+            Type::Array { ty, len } => write!(f, "[{}; {len}]", TypeName(ty)),
+            Type::List { variant, ty } => write!(f, "{variant:?}<{}>", TypeName(ty)),
+            Type::Map { variant, ty } => {
+                write!(f, "{variant:?}<{}, {}>", TypeName(&ty.0), TypeName(&ty.1))
+            }
+            Type::Tuple(tys) => {
+                f.write_str("(")?;
+                for (idx, ty) in tys.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", TypeName(ty))?;
+                }
+                if tys.len() == 1 {
+                    f.write_str(",")?;
+                }
+                f.write_str(")")
+            }
+            Type::Complex(path) => f.write_str(path),
+        }
+    }
+}
+
+// ------------------------------- Parser ------------------------------------
+
+/// Field lines of a struct, or of an enum variant until its `close` line.
+fn fields<'a>(
+    lines: &mut Peekable<impl Iterator<Item = (usize, &'a str)>>,
+    close: Option<(&str, usize)>,
+) -> Result<Vec<(Attributes, StructField)>, SchemaError> {
+    let mut fields = Vec::new();
+    loop {
+        let next = lines.peek().copied();
+        if let (Some((close, _)), Some((_, text))) = (close, next)
+            && close == text
+        {
+            lines.next();
+            return Ok(fields);
+        }
+        let Some((line, text)) =
+            next.filter(|(_, text)| text.starts_with(|ch: char| ch.is_ascii_digit()))
+        else {
+            return match close {
+                None => Ok(fields),
+                Some((close, line)) => Err(SchemaError {
+                    line,
+                    msg: format!("unclosed variant, expected `{close}`"),
+                }),
+            };
+        };
+        lines.next();
+        let field = Cursor(text)
+            .field()
+            .map_err(|msg| SchemaError { line, msg })?;
+        fields.push((Attributes::default(), field));
+    }
+}
+
+fn variants<'a>(
+    lines: &mut Peekable<impl Iterator<Item = (usize, &'a str)>>,
+    repr: Option<&str>,
+) -> Result<Vec<(Attributes, EnumField)>, SchemaError> {
+    let mut variants = Vec::new();
+    while let Some(&(line, text)) = lines.peek() {
+        if !text.starts_with(|ch: char| ch.is_ascii_digit() || ch == '-' || ch == '_') {
+            break;
+        }
+        lines.next();
+        let err = |msg| SchemaError { line, msg };
+
+        let mut c = Cursor(text);
+        let discriminant = c.discriminant(repr).map_err(err)?;
+        let name = Ident::from(c.ident().map_err(err)?);
+        let ty = if c.eat("{") {
+            EnumFieldType::as_struct(fields(lines, Some(("}", line)))?)
+        } else if c.eat("(") {
+            EnumFieldType::as_tuple(fields(lines, Some((")", line)))?)
+        } else {
+            EnumFieldType::Unit
+        };
+        c.end().map_err(err)?;
+        variants.push((
+            Attributes::default(),
+            EnumField {
+                name,
+                ty,
+                discriminant,
+            },
+        ));
+    }
+    Ok(variants)
+}
+
+/// Parser of a single line.
+struct Cursor<'a>(&'a str);
+
+impl<'a> Cursor<'a> {
+    fn eat(&mut self, token: &str) -> bool {
+        self.0 = self.0.trim_start();
+        match self.0.strip_prefix(token) {
+            Some(rest) => {
+                self.0 = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        match self.eat(token) {
+            true => Ok(()),
+            false => Err(format!("expected `{token}`, found `{}`", self.0)),
+        }
+    }
+
+    fn end(&mut self) -> Result<(), String> {
+        match self.0.trim() {
+            "" => Ok(()),
+            rest => Err(format!("unexpected `{rest}`")),
+        }
+    }
+
+    /// Identifier, path (e.g. `app::Point`) or number.
+    fn ident(&mut self) -> Result<&'a str, String> {
+        self.0 = self.0.trim_start();
+        let mut len = 0;
+        while let Some(ch) = self.0[len..].chars().next() {
+            if ch.is_alphanumeric() || ch == '_' || ch == '-' {
+                len += ch.len_utf8();
+            } else if self.0[len..].starts_with("::") {
+                len += 2;
+            } else {
+                break;
+            }
+        }
+
+        let (ident, rest) = self.0.split_at(len);
+        if ident.is_empty() {
+            return Err(format!("expected identifier, found `{rest}`"));
+        }
+        self.0 = rest;
+        Ok(ident)
+    }
+
+    fn number<T: FromStr>(&mut self) -> Result<T, String> {
+        let num = self.ident()?;
+        num.parse().map_err(|_| format!("invalid number: `{num}`"))
+    }
+
+    fn func(&mut self) -> Result<Func<FnMetaData>, String> {
+        let index = self.number()?;
+        let ident = Ident::from(self.ident()?);
+        self.expect("(")?;
+
+        let mut args = Vec::new();
+        let mut input_ty = Vec::new();
+        let mut stream = None;
+        while !self.eat(")") {
+            if !args.is_empty() || stream.is_some() {
+                self.expect(",")?;
+            }
+            if stream.is_some() {
+                return Err("stream must be the last argument".into());
+            }
+            if self.eat("stream<") {
+                stream = Some(Arc::new(self.stream()?));
+            } else {
+                args.push(Ident::from(self.ident()?));
+                self.expect(":")?;
+                input_ty.push(self.ty()?);
+            }
+        }
+        self.expect("->")?;
+        let output_ty = match self.eat("stream<") {
+            true => FnOutputTy::Generator(self.stream()?),
+            false => FnOutputTy::Return(self.ty()?),
+        };
+        self.end()?;
+
+        Ok(Func {
+            stream,
+            input_ty,
+            output_ty,
+            meta: FnMetaData {
+                docs: String::new(),
+                index,
+                ident,
+                args,
+            },
+        })
+    }
+
+    /// `stream<yield, return>`, after `stream<`.
+    fn stream(&mut self) -> Result<GeneratorType, String> {
+        let yield_ty = self.ty()?;
+        self.expect(",")?;
+        let return_ty = self.ty()?;
+        self.expect(">")?;
+        Ok(GeneratorType {
+            yield_ty,
+            return_ty,
+        })
+    }
+
+    fn field(&mut self) -> Result<StructField, String> {
+        let key = self.number()?;
+        let name = Ident::from(self.ident()?);
+        self.expect(":")?;
+        let ty = self.ty()?;
+        self.end()?;
+        Ok(StructField { key, name, ty })
+    }
+
+    fn discriminant(&mut self, repr: Option<&str>) -> Result<Discriminant, String> {
+        if self.eat("_") {
+            return Ok(Discriminant::None);
+        }
+        Ok(match repr {
+            Some("u8") => Discriminant::U8(self.number()?),
+            Some("u16") => Discriminant::U16(self.number()?),
+            Some("u32") => Discriminant::U32(self.number()?),
+            Some("u64") => Discriminant::U64(self.number()?),
+            Some("i8") => Discriminant::I8(self.number()?),
+            Some("i16") => Discriminant::I16(self.number()?),
+            Some("i32") => Discriminant::I32(self.number()?),
+            Some("i64") => Discriminant::I64(self.number()?),
+            Some(repr) => return Err(format!("invalid enum repr: `{repr}`")),
+            None => return Err("discriminant of an enum without repr".into()),
+        })
+    }
+
+    /// Comma separated types, until `close`.
+    fn types(&mut self, close: &str) -> Result<Vec<Type>, String> {
+        let mut tys = Vec::new();
+        while !self.eat(close) {
+            tys.push(self.ty()?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(tys)
+    }
+
+    fn ty(&mut self) -> Result<Type, String> {
+        if self.eat("(") {
+            return self.types(")").map(Type::Tuple);
+        }
+        if self.eat("[") {
+            let ty = Box::new(self.ty()?);
+            self.expect(";")?;
+            let len = self.number()?;
+            self.expect("]")?;
+            return Ok(Type::Array { ty, len });
+        }
+        let name = self.ident()?;
+        let args = match self.eat("<") {
+            true => self.types(">")?,
+            false => Vec::new(),
+        };
+        let len = args.len();
+        let mut args = args.into_iter();
+        let mut arg = || Box::new(args.next().unwrap());
+
+        Ok(match (name, len) {
+            ("u8", 0) => Type::U8,
+            ("u16", 0) => Type::U16,
+            ("u32", 0) => Type::U32,
+            ("u64", 0) => Type::U64,
+            ("u128", 0) => Type::U128,
+            ("i8", 0) => Type::I8,
+            ("i16", 0) => Type::I16,
+            ("i32", 0) => Type::I32,
+            ("i64", 0) => Type::I64,
+            ("i128", 0) => Type::I128,
+            ("f32", 0) => Type::F32,
+            ("f64", 0) => Type::F64,
+            ("bool", 0) => Type::Bool,
+            ("char", 0) => Type::Char,
+            ("String", 0) => Type::String,
+            ("Option", 1) => Type::Option(arg()),
+            ("Result", 2) => Type::Result(Box::new((*arg(), *arg()))),
+            (name, 1) if list_variant(name).is_some() => Type::List {
+                variant: list_variant(name).unwrap(),
+                ty: arg(),
+            },
+            ("HashMap" | "BTreeMap", 2) => Type::Map {
+                variant: match name {
+                    "HashMap" => MapVariant::HashMap,
+                    _ => MapVariant::BTreeMap,
+                },
+                ty: Box::new((*arg(), *arg())),
+            },
+            (path, 0) if path.contains("::") => Type::Complex(PathIdent::from(path)),
+            (name, _) => return Err(format!("unknown type: `{name}`")),
+        })
+    }
+}
+
+fn list_variant(name: &str) -> Option<ListVariant> {
+    Some(match name {
+        "BTreeSet" => ListVariant::BTreeSet,
+        "HashSet" => ListVariant::HashSet,
+        "BinaryHeap" => ListVariant::BinaryHeap,
+        "LinkedList" => ListVariant::LinkedList,
+        "VecDeque" => ListVariant::VecDeque,
+        "Vec" => ListVariant::Vec,
+        "Columns" => ListVariant::Columns,
+        _ => return None,
+    })
+}
@@ -0,0 +1,97 @@
+#![allow(dead_code)]
+
+use lipi::Encode;
+use setu_type_info::{Func, Reflection, TypeInfo, type_id};
+use type_id::{ComplexDataType, EnumFieldType, TypeId};
+
+const SCHEMA: &str = "\
+fn 1 create(user: app::User, tags: BTreeSet<String>) -> Result<u64, app::Error>
+fn 2 list(limit: Option<u32>) -> stream<app::User, ()>
+fn 3 upload(name: String, stream<Vec<u8>, [u8; 32]>) -> (i128, HashMap<char, f32>)
+
+enum app::Error: i16
+    -1 NotFound
+    1 Invalid (
+        0 0: String
+    )
+    2 Conflict {
+        0 id: u64
+    }
+
+enum numeric app::Role: u8
+    0 Admin
+    1 Guest
+
+tuple app::Score
+    0 0: f64
+
+struct app::User
+    0 id: u64
+    1 role: app::Role
+    2 friends: Columns<app::User>
+    3 scores: VecDeque<app::Score>
+";
+
+fn round_trip(info: &TypeInfo) -> TypeInfo {
+    let bytes = Reflection::from(info).to_bytes().unwrap();
+    TypeInfo::from_reflection(&bytes).unwrap()
+}
+
+#[test]
+fn schema() {
+    let info = TypeInfo::from_schema(SCHEMA).unwrap();
+    let decoded = round_trip(&info);
+    assert_eq!(decoded.to_schema(), SCHEMA);
+    assert_eq!(decoded.fingerprint(), info.fingerprint());
+}
+
+/// A shape on the plane.
+#[derive(TypeId)]
+enum Shape {
+    /// Point at `x` and `y`.
+    Dot(
+        /// Horizontal axis.
+        i32,
+        i32,
+    ),
+}
+
+async fn mirror(shape: Shape) -> Shape {
+    shape
+}
+
+#[test]
+fn docs() {
+    let mut info = TypeInfo::default();
+    let f = Func::with_meta(
+        &mut info.registry,
+        "Mirrors a shape.",
+        &mirror,
+        7,
+        "mirror",
+        &["shape"],
+    );
+    info.fns.push(f);
+
+    let decoded = round_trip(&info);
+    assert_eq!(decoded.to_schema(), info.to_schema());
+
+    let meta = &decoded.fns[0].meta;
+    assert_eq!(meta.docs, "Mirrors a shape.");
+    assert_eq!(&*meta.args, ["shape".into()]);
+
+    let shape = &decoded.registry["reflection::Shape"];
+    assert_eq!(shape.attrs.docs, "A shape on the plane.");
+    let ComplexDataType::Enum { fields, .. } = &shape.ty else {
+        panic!("expected an enum");
+    };
+    let (attrs, dot) = &fields[0];
+    assert_eq!(attrs.docs, "Point at `x` and `y`.");
+    let EnumFieldType::Tuple(fields) = &dot.ty else {
+        panic!("expected a tuple variant");
+    };
+    assert_eq!(fields[0].0.docs, "Horizontal axis.");
+    assert_eq!(fields[1].0.docs, "");
+
+    assert!(TypeInfo::from_reflection(&[0xff]).is_err());
+}
@@ -0,0 +1,200 @@
+#![allow(dead_code)]
+
+use setu_type_info::{Change, Func, TypeInfo, diff, type_id};
+use type_id::{Type, TypeId};
+
+const SCHEMA: &str = "\
+fn 1 create(user: app::User, notify: Option<bool>) -> Result<u64, app::Error>
+fn 2 list(limit: u32) -> stream<app::User, ()>
+fn 3 upload(name: String, stream<Vec<u8>, [u8; 32]>) -> (u64, HashMap<String, u8>)
+
+enum app::Error: u8
+    0 NotFound
+    1 Invalid (
+        0 0: String
+    )
+    2 Conflict {
+        0 id: u64
+        1 reason: Option<String>
+    }
+
+struct app::User
+    0 id: u64
+    1 name: String
+    2 tags: BTreeSet<String>
+    3 friends: Columns<app::User>
+";
+
+fn changes(old: &str, new: &str) -> Vec<String> {
+    let old = TypeInfo::from_schema(old).unwrap();
+    let new = TypeInfo::from_schema(new).unwrap();
+    diff(&old, &new).iter().map(Change::to_string).collect()
+}
+
+#[test]
+fn round_trip() {
+    let info = TypeInfo::from_schema(SCHEMA).unwrap();
+    assert_eq!(info.to_schema(), SCHEMA);
+    assert_eq!(info.fns.len(), 3);
+    assert_eq!(info.registry.len(), 2);
+
+    // Fingerprints must not change across builds.
+    assert_eq!(info.fingerprint().to_string(), "d5cacf2110483da7");
+
+    let err = TypeInfo::from_schema("fn 1 add(a: i32, b: Int) -> i32").unwrap_err();
+    assert_eq!(err.to_string(), "line 1: unknown type: `Int`");
+
+    let err = TypeInfo::from_schema("\nenum app::E: u8\n    0 A {\n        0 a: u8\n").unwrap_err();
+    assert_eq!(err.to_string(), "line 3: unclosed variant, expected `}`");
+}
+
+#[derive(TypeId)]
+struct Point {
+    x: i32,
+    y: Option<i32>,
+}
+
+async fn mirror(point: Point) -> Point {
+    point
+}
+
+#[test]
+fn from_type_info() {
+    let mut info = TypeInfo::default();
+    let f = Func::with_meta(&mut info.registry, "", &mirror, 7, "mirror", &["point"]);
+    info.fns.push(f);
+
+    let schema = info.to_schema();
+    let parsed = TypeInfo::from_schema(&schema).unwrap();
+    assert_eq!(parsed.to_schema(), schema);
+    assert_eq!(parsed.fingerprint(), info.fingerprint());
+    assert!(schema.starts_with("fn 7 mirror(point: schema::Point) -> schema::Point\n"));
+
+    let point = Type::Complex("schema::Point".into());
+    assert_eq!(
+        parsed.type_fingerprint(&point),
+        info.type_fingerprint(&point)
+    );
+}
+
+#[test]
+fn fingerprint_ignores_names() {
+    let renamed = SCHEMA
+        .replace("create(user", "create(new_user")
+        .replace("1 name: String", "1 full_name: String")
+        .replace("app::User", "app::Account");
+
+    let old = TypeInfo::from_schema(SCHEMA).unwrap();
+    let new = TypeInfo::from_schema(&renamed).unwrap();
+    assert_eq!(old.fingerprint(), new.fingerprint());
+    assert_eq!(
+        changes(SCHEMA, &renamed),
+        ["compatible: type `app::Account`: field `full_name` (1) renamed from `name`"]
+    );
+
+    let changed = SCHEMA.replace("0 id: u64", "0 id: u32");
+    let new = TypeInfo::from_schema(&changed).unwrap();
+    assert_ne!(old.fingerprint(), new.fingerprint());
+    assert_eq!(
+        old.fn_fingerprint(&old.fns[2]),
+        new.fn_fingerprint(&new.fns[2])
+    );
+}
+
+#[test]
+fn rpc_changes() {
+    let new = SCHEMA
+        .replace("fn 2 list", "fn 4 list")
+        .replace(
+            "fn 3 upload(name: String, ",
+            "fn 3 upload(name: String, size: u64, ",
+        )
+        .replace("notify: Option<bool>", "notify: Option<u8>");
+
+    assert_eq!(
+        changes(SCHEMA, &new),
+        [
+            "breaking: rpc `create` (1): argument `notify` type changed from `Option<bool>` to `Option<u8>`",
+            "breaking: rpc `list` (2): renumbered from 2 to 4",
+            "breaking: rpc `upload` (3): required argument `size` added",
+        ]
+    );
+
+    let new = SCHEMA.replace(
+        "fn 1 create(user: app::User, notify: Option<bool>) -> Result<u64, app::Error>\n",
+        "",
+    );
+    assert_eq!(
+        changes(SCHEMA, &new),
+        ["breaking: rpc `create` (1): removed"]
+    );
+    assert_eq!(
+        changes(&new, SCHEMA),
+        ["compatible: rpc `create` (1): added"]
+    );
+}
+
+#[test]
+fn field_changes() {
+    let new = SCHEMA
+        .replace("    2 tags: BTreeSet<String>\n", "")
+        .replace("3 friends: Columns<app::User>", "3 friends: Vec<app::User>")
+        .replace(
+            "1 reason: Option<String>\n",
+            "1 reason: Option<String>\n        2 code: u16\n",
+        )
+        .replace(
+            "    0 id: u64\n    1 name",
+            "    0 id: u64\n    4 email: Option<String>\n    1 name",
+        );
+
+    assert_eq!(
+        changes(SCHEMA, &new),
+        [
+            "breaking: type `app::User`: required field `tags` (2) removed",
+            "breaking: type `app::User`: field `friends` (3) type changed from `Columns<app::User>` to `Vec<app::User>`",
+            "compatible: type `app::User`: field `email` (4) added",
+            "breaking: type `app::Error`: variant `Conflict`: required field `code` (2) added",
+        ]
+    );
+
+    // Lists, sets and maps have the same wire format.
+    let new = SCHEMA.replace("BTreeSet<String>", "Vec<String>");
+    assert!(changes(SCHEMA, &new).is_empty());
+}
+
+#[test]
+fn variant_changes() {
+    let new = SCHEMA
+        .replace("0 NotFound", "0 Missing")
+        .replace("1 Invalid (\n        0 0: String\n    )", "1 Timeout")
+        .replace("2 Conflict", "3 Conflict");
+
+    assert_eq!(
+        changes(SCHEMA, &new),
+        [
+            "compatible: type `app::Error`: variant `Missing` renamed from `NotFound`",
+            "breaking: type `app::Error`: discriminant 1 reused by `Timeout`, was `Invalid`",
+            "breaking: type `app::Error`: variant `Conflict` renumbered from 2 to 3",
+        ]
+    );
+
+    let new = SCHEMA.replace("2 Conflict {", "3 Retry\n    2 Conflict {");
+    assert_eq!(
+        changes(SCHEMA, &new),
+        ["compatible: type `app::Error`: variant `Retry` added"]
+    );
+
+    // Single unnamed field is encoded as a bare value, instead of a struct.
+    let invalid = "1 Invalid (\n        0 0: String\n    )";
+    let named = SCHEMA.replace(invalid, "1 Invalid {\n        0 reason: String\n    }");
+    let pair = SCHEMA.replace(
+        invalid,
+        "1 Invalid (\n        0 0: String\n        1 1: Option<u8>\n    )",
+    );
+    for new in [named, pair] {
+        let breaking = ["breaking: type `app::Error`: variant `Invalid` fields changed"];
+        assert_eq!(changes(SCHEMA, &new), breaking);
+        assert_eq!(changes(&new, SCHEMA), breaking);
+    }
+}
@@ -5,15 +5,27 @@ use proc_macro2::{Span, TokenStream};
 use quote2::{Quote, quote, quote_spanned};
 
 pub use parse::*;
-use syn::Ident;
+use syn::{Ident, Meta, spanned::Spanned};
 
-use crate::utils::add_compile_error;
+use crate::utils::{add_compile_error, docs};
 
 pub fn expend_export(crate_path: &TokenStream, list: &FnList, t: &mut TokenStream) {
     let rpcs = quote(|t| {
         for Rpc { name, index, .. } in &list.fns {
             quote!(t, {
-                #index => #crate_path::Output::process(#name, ctx),
+                #index => (&Rpc::of(&#name)).processor()(#name, ctx),
+            });
+        }
+    });
+
+    let reflection = quote(|t| {
+        if has_reflection(list) {
+            quote!(t, {
+                id if id == u32::from(#crate_path::__private::setu_type_info::REFLECTION_RPC_ID) => {
+                    static CACHE: ::std::sync::OnceLock<#crate_path::__private::setu_type_info::Reflection> =
+                        ::std::sync::OnceLock::new();
+                    #crate_path::__private::reflection::<Self>(&CACHE, ctx)
+                }
             });
         }
     });
@@ -26,8 +38,13 @@ pub fn expend_export(crate_path: &TokenStream, list: &FnList, t: &mut TokenStrea
 
         impl #crate_path::Application for #name {
             fn execute(id: u32, ctx: #crate_path::transport::http::HttpContext) {
+                use #crate_path::__private::{ProcessOutput as _, ProcessResult as _, Rpc};
+                let ::std::option::Option::Some(ctx) = #crate_path::__private::intercept(id, ctx) else {
+                    return;
+                };
                 match id {
                     #rpcs
+                    #reflection
                     id => #crate_path::__private::unknown_rpc(id, ctx)
                 }
             }
@@ -51,10 +68,15 @@ pub fn expend_type_definition(crate_path: &TokenStream, list: &FnList, t: &mut T
 
     let body = quote(|t| {
         for Rpc {
-            name, index, args, ..
+            attrs,
+            name,
+            index,
+            args,
+            ..
         } in &list.fns
         {
             let raw = name.to_string();
+            let docs = docs(attrs);
             let args = quote(|t| {
                 for arg in args {
                     let arg = arg.to_string();
@@ -62,7 +84,7 @@ pub fn expend_type_definition(crate_path: &TokenStream, list: &FnList, t: &mut T
                 }
             });
             quote!(t, {
-                Func::with_meta(r, "", &#name, #index, #raw, &[#args]),
+                Func::with_meta(r, #docs, &#name, #index, #raw, &[#args]),
             });
         }
     });
@@ -110,6 +132,48 @@ pub fn check_fn_args_count(crate_path: &TokenStream, list: &FnList, t: &mut Toke
     });
 }
 
+/// `#![reflection]` adds the reflection rpc, its id is reserved.
+pub fn expend_reflection(crate_path: &TokenStream, list: &FnList, t: &mut TokenStream) {
+    for attr in &list.attrs {
+        if !matches!(&attr.meta, Meta::Path(path) if path.is_ident("reflection")) {
+            add_compile_error(
+                t,
+                attr.span(),
+                "unknown attribute, expected `#![reflection]`",
+            );
+        }
+    }
+    if !has_reflection(list) {
+        return;
+    }
+    let body = quote(|t| {
+        for Rpc { name, index, .. } in &list.fns {
+            let panic_msg = quote(|t| {
+                let span = index.span();
+                let msg = format!("id of `{name}` is reserved for the reflection rpc");
+                quote_spanned!(span, t, { ::std::panic!(#msg) });
+            });
+            quote!(t, {
+                if #index == REFLECTION_RPC_ID {
+                    #panic_msg;
+                }
+            });
+        }
+    });
+    quote!(t, {
+        const _: () = {
+            use #crate_path::__private::setu_type_info::REFLECTION_RPC_ID;
+            #body
+        };
+    });
+}
+
+fn has_reflection(list: &FnList) -> bool {
+    list.attrs
+        .iter()
+        .any(|attr| matches!(&attr.meta, Meta::Path(path) if path.is_ident("reflection")))
+}
+
 fn interface_name(list: &FnList) -> Ident {
     match list.name {
         Some(ref name) => name.name.clone(),
@@ -22,6 +22,8 @@ impl Parse for AppName {
 }
 
 pub struct FnList {
+    /// Inner attributes, e.g. `#![reflection]`
+    pub attrs: Vec<Attribute>,
     pub name: Option<AppName>,
     pub fns: Punctuated<Rpc, Token![;]>,
 }
@@ -29,6 +31,7 @@ pub struct FnList {
 impl Parse for FnList {
     fn parse(input: ParseStream) -> Result<Self> {
         Ok(Self {
+            attrs: input.call(Attribute::parse_inner)?,
             name: input.peek(Token![as]).then(|| input.parse()).transpose()?,
             fns: Punctuated::parse_terminated(input)?,
         })
@@ -1,5 +1,6 @@
 use proc_macro2::{Literal, TokenStream};
 use quote2::*;
+use syn::{Attribute, Expr, ExprLit, Lit, Meta, MetaNameValue};
 
 pub fn add_compile_error(t: &mut TokenStream, span: proc_macro2::Span, msg: &str) {
     let mut msg = Literal::string(msg);
@@ -8,3 +9,21 @@ pub fn add_compile_error(t: &mut TokenStream, span: proc_macro2::Span, msg: &str
         ::core::compile_error! { #msg }
     });
 }
+
+/// Joins the lines of doc comments, without the leading space of each line. (Same as rustdoc)
+pub fn docs(attrs: &[Attribute]) -> String {
+    let mut docs = Vec::new();
+    for attr in attrs {
+        if let Meta::NameValue(MetaNameValue { path, value, .. }) = &attr.meta
+            && path.is_ident("doc")
+            && let Expr::Lit(ExprLit {
+                lit: Lit::Str(line),
+                ..
+            }) = value
+        {
+            let line = line.value();
+            docs.push(line.strip_prefix(' ').unwrap_or(&line).to_owned());
+        }
+    }
+    docs.join("\n")
+}
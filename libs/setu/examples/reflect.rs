@@ -0,0 +1,20 @@
+//! Prints the rpcs and types of a running service, exported with `#![reflection]`.
+//!
+//! ```text
+//! cargo run --example reflect -- 127.0.0.1:4433
+//! ```
+//!
+//! The service is called over plaintext HTTP/2 (h2c).
+use setu::client::Client;
+use std::{env, process};
+
+#[nio::main]
+async fn main() -> setu::Result<()> {
+    let Some(addr) = env::args().nth(1) else {
+        eprintln!("usage: reflect ADDR");
+        process::exit(2);
+    };
+    let info = Client::connect(addr).await?.reflect().await?;
+    print!("{}", info.to_schema());
+    Ok(())
+}
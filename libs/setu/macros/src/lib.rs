@@ -2,7 +2,7 @@ mod utils;
 
 use proc_macro::TokenStream;
 use quote2::*;
-use setu_derive::{check_fn_args_count, expend_export, expend_type_definition};
+use setu_derive::{check_fn_args_count, expend_export, expend_reflection, expend_type_definition};
 use syn::parse_macro_input;
 
 #[proc_macro]
@@ -14,10 +14,25 @@ pub fn export(input: TokenStream) -> TokenStream {
     expend_export(&crate_path, &list, &mut t);
     expend_type_definition(&crate_path, &list, &mut t);
     check_fn_args_count(&crate_path, &list, &mut t);
+    expend_reflection(&crate_path, &list, &mut t);
     t.into()
 }
 
-#[proc_macro_derive(Input, attributes(numeric, key))]
+#[proc_macro_derive(
+    Input,
+    attributes(
+        numeric,
+        deny_unknown_fields,
+        key,
+        default,
+        skip,
+        rename,
+        with,
+        encode_with,
+        decode_with,
+        flatten
+    )
+)]
 pub fn input(input: TokenStream) -> TokenStream {
     let Ok(input) = syn::parse(input) else {
         return TokenStream::new();
@@ -37,7 +52,20 @@ pub fn input(input: TokenStream) -> TokenStream {
     t.into()
 }
 
-#[proc_macro_derive(Output, attributes(numeric, key, default))]
+#[proc_macro_derive(
+    Output,
+    attributes(
+        numeric,
+        key,
+        default,
+        skip,
+        rename,
+        with,
+        encode_with,
+        decode_with,
+        flatten
+    )
+)]
 pub fn output(input: TokenStream) -> TokenStream {
     let Ok(input) = syn::parse(input) else {
         return TokenStream::new();
@@ -57,7 +85,21 @@ pub fn output(input: TokenStream) -> TokenStream {
     t.into()
 }
 
-#[proc_macro_derive(Message, attributes(numeric, key, default))]
+#[proc_macro_derive(
+    Message,
+    attributes(
+        numeric,
+        deny_unknown_fields,
+        key,
+        default,
+        skip,
+        rename,
+        with,
+        encode_with,
+        decode_with,
+        flatten
+    )
+)]
 pub fn message(input: TokenStream) -> TokenStream {
     let Ok(input) = syn::parse(input) else {
         return TokenStream::new();
@@ -3,13 +3,30 @@ pub use lipi;
 pub use setu_type_info;
 pub use setu_type_info::type_id;
 
-use crate::transport::http::HttpContext;
+use crate::{Output, RpcError, input::Input, output, transport::http::HttpContext};
+use lipi::encoder::OptionalField;
+use setu_type_info::{Reflection, TypeDefinition, TypeInfo};
+use std::{marker::PhantomData, sync::OnceLock};
+
+/// Runs server interceptors, `None` if the rpc was rejected.
+pub fn intercept(id: u32, ctx: HttpContext) -> Option<HttpContext> {
+    ctx.intercept(id, &[])
+}
 
 pub fn unknown_rpc(id: u32, mut ctx: HttpContext) {
+    ctx.call.status = crate::Status::Unimplemented;
     *ctx.res.status_mut() = http::StatusCode::NOT_IMPLEMENTED;
     let _ = ctx.res.write_unbound(format!("Unknown call id {id}"));
 }
 
+/// Reflection rpc, enabled by `#![reflection]`.
+///
+/// It's built once, into the `cache` of the application.
+pub fn reflection<T: TypeDefinition>(cache: &'static OnceLock<Reflection>, ctx: HttpContext) {
+    let reflection = cache.get_or_init(|| Reflection::from(&TypeInfo::from::<T>()));
+    output::process_data(async move || reflection, ctx)
+}
+
 pub const fn __fn_args_count<F, Args>(_: &F) -> u8
 where
     F: std_lib::FnOnce<Args>,
@@ -17,3 +34,46 @@ where
 {
     Args::LEN
 }
+
+/// Selects how a handler is processed, by its output type.
+///
+/// Handlers that return `Result<T, RpcError>` are preferred over [Output] (autoref specialization),
+/// as `Result<T, E>` is also a valid output.
+pub struct Rpc<F>(PhantomData<F>);
+
+impl<F> Rpc<F> {
+    pub fn of(_: &F) -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub trait ProcessResult<F, Args> {
+    fn processor(&self) -> fn(F, HttpContext);
+}
+
+impl<F, Args, T> ProcessResult<F, Args> for Rpc<F>
+where
+    F: std_lib::FnOnce<Args> + 'static,
+    F::Output: Future<Output = Result<T, RpcError>>,
+    T: OptionalField,
+    Args: Input,
+{
+    fn processor(&self) -> fn(F, HttpContext) {
+        output::process_result
+    }
+}
+
+pub trait ProcessOutput<F, Args> {
+    fn processor(&self) -> fn(F, HttpContext);
+}
+
+impl<F, Args> ProcessOutput<F, Args> for &Rpc<F>
+where
+    F: std_lib::FnOnce<Args> + 'static,
+    F::Output: Output,
+    Args: Input,
+{
+    fn processor(&self) -> fn(F, HttpContext) {
+        <F::Output as Output>::process
+    }
+}
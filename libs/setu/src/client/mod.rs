@@ -0,0 +1,297 @@
+//! HTTP/2 client, for calling setu services.
+//!
+//! ```no_run
+//! # async fn example() -> setu::Result<()> {
+//! use setu::client::Client;
+//!
+//! let client = Client::connect("127.0.0.1:4433").await?;
+//! let sum: i32 = client.call(2, (1, 2)).await?;
+//! # Ok(())
+//! # }
+//! ```
+mod sender;
+
+pub use sender::Sender;
+
+use crate::{
+    Encoding, Result, Stream, Timeout,
+    compression::{Compression, DEFAULT_THRESHOLD, RPC_ACCEPT_ENCODING, RPC_ENCODING},
+    frame::{FrameDecoder, FrameEncoder, Limits},
+    transport::http::{HttpBody, HttpWriter, SETU_CONTENT_TYPE},
+};
+use bytes::{Bytes, BytesMut};
+use h2::client::{ResponseFuture, SendRequest};
+use http::{Method, Request, Uri, header::CONTENT_TYPE};
+use lipi::{
+    Encode,
+    decoder::{FieldDecoderOwned, Optional},
+};
+use nio::net::TcpStream;
+use setu_type_info::{REFLECTION_RPC_ID, Reflection, TypeInfo};
+use std::{marker::PhantomData, net::ToSocketAddrs, ops::ControlFlow, sync::Arc};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::{
+    TlsConnector,
+    rustls::{ClientConfig, pki_types::ServerName},
+};
+
+/// A handle to a HTTP/2 connection.
+///
+/// The connection is driven by a background task, spawned on the current thread.
+/// Cloning a client is cheap, and all clones share the same connection.
+#[derive(Clone)]
+pub struct Client {
+    sender: SendRequest<Bytes>,
+    uri: Uri,
+    timeout: Option<Timeout>,
+    compression: Option<Encoding>,
+    limits: Limits,
+}
+
+impl Client {
+    /// Connect over plaintext HTTP/2 with prior knowledge (h2c).
+    pub async fn connect(addr: impl ToSocketAddrs) -> Result<Client> {
+        let tcp = TcpStream::connect(addr).await?;
+        let uri = Uri::try_from(format!("http://{}/", tcp.peer_addr()?))?;
+        Client::handshake(tcp, uri).await
+    }
+
+    /// Connect over TLS, `domain` is used to verify the server certificate.
+    ///
+    /// ALPN protocol of `config` is set to `h2`.
+    pub async fn connect_tls(
+        addr: impl ToSocketAddrs,
+        domain: &str,
+        mut config: ClientConfig,
+    ) -> Result<Client> {
+        config.alpn_protocols = vec!["h2".into()];
+
+        let server_name = ServerName::try_from(domain.to_owned())?;
+        let uri = Uri::try_from(format!("https://{domain}/"))?;
+
+        let tcp = TcpStream::connect(addr).await?;
+        let tls = TlsConnector::from(Arc::new(config))
+            .connect(server_name, tcp)
+            .await?;
+
+        Client::handshake(tls, uri).await
+    }
+
+    async fn handshake<IO>(io: IO, uri: Uri) -> Result<Client>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin + 'static,
+    {
+        let (sender, conn) = h2::client::handshake(io).await?;
+        nio::spawn_local(async move {
+            if let Err(_err) = conn.await {
+                // println!("http-error: {_err:?}");
+            }
+        });
+        Ok(Client {
+            sender,
+            uri,
+            timeout: None,
+            compression: None,
+            limits: Limits::default(),
+        })
+    }
+
+    /// Sets `rpc-timeout` of every call made by this client.
+    pub fn timeout(mut self, timeout: Timeout) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Compress request messages, larger then 1024 bytes, with `encoding`.
+    ///
+    /// Responses are always decompressed with any [supported encoding](Encoding::ALL),
+    /// regardless of this setting.
+    pub fn compression(mut self, encoding: Encoding) -> Self {
+        self.compression = Some(encoding);
+        self
+    }
+
+    /// Maximum length of a single message, in bytes. (Default: 16 MiB)
+    ///
+    /// Larger responses are rejected with [Status::ResourceExhausted](crate::Status::ResourceExhausted),
+    /// and requests larger then this limit can't be sent.
+    pub fn max_frame_size(mut self, size: usize) -> Self {
+        self.limits.max_frame_size = size;
+        self
+    }
+
+    /// Maximum total length of all messages of a response, in bytes. (Default: unlimited)
+    pub fn max_stream_size(mut self, size: usize) -> Self {
+        self.limits.max_stream_size = size;
+        self
+    }
+
+    /// Unary call.
+    pub async fn call<Args, O>(&self, id: u32, args: Args) -> Result<O>
+    where
+        Args: Encode,
+        O: Optional,
+        O::Value: FieldDecoderOwned,
+    {
+        let (res, mut writer) = self.request(id).await?;
+        let msg = writer.encode(&args)?;
+        writer.end(msg)?;
+        output(res, self.limits).await
+    }
+
+    /// Server stream (`SSE`) call.
+    pub async fn sse<Args, T, R>(&self, id: u32, args: Args) -> Result<Stream<T, R>>
+    where
+        Args: Encode,
+        T: Optional,
+        R: Optional,
+        T::Value: FieldDecoderOwned,
+        R::Value: FieldDecoderOwned,
+    {
+        let (res, mut writer) = self.request(id).await?;
+        let msg = writer.encode(&args)?;
+        writer.end(msg)?;
+        response(res, self.limits).await
+    }
+
+    /// Client stream call, `args` are sent before the stream.
+    ///
+    /// Pass `()` as `args`, if the rpc has no argument other then [Stream].
+    pub async fn client_stream<Args, T, R, O>(
+        &self,
+        id: u32,
+        args: Args,
+    ) -> Result<(Sender<T, R>, Response<O>)>
+    where
+        Args: Encode,
+        O: Optional,
+        O::Value: FieldDecoderOwned,
+    {
+        let (res, writer) = self.request(id).await?;
+        let sender = Sender::new(writer, args).await?;
+        Ok((
+            sender,
+            Response {
+                res,
+                limits: self.limits,
+                output: PhantomData,
+            },
+        ))
+    }
+
+    /// Bidirectional stream call, `args` are sent before the stream.
+    pub async fn bidi<Args, T, R, Y, Z>(
+        &self,
+        id: u32,
+        args: Args,
+    ) -> Result<(Sender<T, R>, Stream<Y, Z>)>
+    where
+        Args: Encode,
+        Y: Optional,
+        Z: Optional,
+        Y::Value: FieldDecoderOwned,
+        Z::Value: FieldDecoderOwned,
+    {
+        let (res, writer) = self.request(id).await?;
+        let sender = Sender::new(writer, args).await?;
+        Ok((sender, response(res, self.limits).await?))
+    }
+
+    /// Calls the reflection rpc, of a service exported with `#![reflection]`.
+    pub async fn reflect(&self) -> Result<TypeInfo> {
+        let reflection: Reflection = self.call(REFLECTION_RPC_ID.into(), ()).await?;
+        Ok(reflection.into())
+    }
+
+    async fn request(&self, id: u32) -> Result<(ResponseFuture, FrameEncoder)> {
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .uri(self.uri.clone())
+            .header(CONTENT_TYPE, SETU_CONTENT_TYPE)
+            .header("rpc-id", id);
+
+        if let Some(timeout) = self.timeout {
+            req = req.header("rpc-timeout", timeout.to_string());
+        }
+        if let Some(encoding) = self.compression {
+            req = req.header(RPC_ENCODING, encoding.name());
+        }
+        if let Some(accept) = Encoding::accept_header() {
+            req = req.header(RPC_ACCEPT_ENCODING, accept);
+        }
+
+        let mut sender = self.sender.clone().ready().await?;
+        let (res, stream) = sender.send_request(req.body(())?, false)?;
+
+        Ok((
+            res,
+            FrameEncoder {
+                stream: HttpWriter { stream },
+                compression: self.compression.map(|encoding| Compression {
+                    encoding,
+                    threshold: DEFAULT_THRESHOLD,
+                }),
+                max_frame_size: self.limits.max_frame_size,
+                call: None,
+                buf: BytesMut::new(),
+            },
+        ))
+    }
+}
+
+/// Pending output of a [client stream](Client::client_stream) call.
+pub struct Response<O> {
+    res: ResponseFuture,
+    limits: Limits,
+    output: PhantomData<O>,
+}
+
+impl<O> Response<O>
+where
+    O: Optional,
+    O::Value: FieldDecoderOwned,
+{
+    pub async fn output(self) -> Result<O> {
+        output(self.res, self.limits).await
+    }
+}
+
+async fn response<T, R>(res: ResponseFuture, limits: Limits) -> Result<Stream<T, R>>
+where
+    T: Optional,
+    R: Optional,
+    T::Value: FieldDecoderOwned,
+    R::Value: FieldDecoderOwned,
+{
+    let res = res.await?;
+    let status = res.status();
+    if !status.is_success() {
+        return Err(format!("unexpected http status: {status}").into());
+    }
+    let content_type = res.headers().get(CONTENT_TYPE);
+    if content_type.is_none_or(|v| v != SETU_CONTENT_TYPE) {
+        return Err(format!("unexpected content-type: {content_type:?}").into());
+    }
+    let encoding = match res.headers().get(RPC_ENCODING) {
+        Some(val) => Some(
+            val.to_str()
+                .ok()
+                .and_then(Encoding::from_name)
+                .ok_or_else(|| format!("unsupported rpc-encoding: {val:?}"))?,
+        ),
+        None => None,
+    };
+    let body = HttpBody::from(res.into_body());
+    Ok(Stream::new(FrameDecoder::new(encoding, limits), body))
+}
+
+async fn output<O>(res: ResponseFuture, limits: Limits) -> Result<O>
+where
+    O: Optional,
+    O::Value: FieldDecoderOwned,
+{
+    match response::<(), O>(res, limits).await?.next().await? {
+        ControlFlow::Break(output) => Ok(output),
+        ControlFlow::Continue(()) => Err("expected trailer frame".into()),
+    }
+}
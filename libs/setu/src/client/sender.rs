@@ -0,0 +1,43 @@
+use crate::{Result, RpcError, Status, frame::FrameEncoder, output::encode_data};
+use lipi::{Encode, encoder::OptionalField};
+use std::marker::PhantomData;
+
+/// Sending half of a client stream, the counterpart of server-side [Stream](crate::Stream).
+pub struct Sender<T, R = ()> {
+    encoder: FrameEncoder,
+    data: PhantomData<fn(T, R)>,
+}
+
+impl<T, R> Sender<T, R> {
+    pub(crate) async fn new(mut encoder: FrameEncoder, args: impl Encode) -> Result<Self> {
+        let msg = encoder.encode(&args)?;
+        encoder.send(msg).await?;
+        Ok(Self {
+            encoder,
+            data: PhantomData,
+        })
+    }
+
+    /// Ends the stream with an error.
+    pub fn send_error(self, status: Status, reason: impl Into<String>) -> Result<()> {
+        Ok(self.encoder.send_error(RpcError::new(status, reason))?)
+    }
+}
+
+impl<T, R> Sender<T, R>
+where
+    T: OptionalField,
+    R: OptionalField,
+{
+    pub async fn send(&mut self, msg: T) -> Result<()> {
+        let msg = encode_data(&mut self.encoder.buf, msg)?;
+        self.encoder.send(msg).await
+    }
+
+    /// Ends the stream with a final message.
+    pub fn end(self, msg: R) -> Result<()> {
+        let mut encoder = self.encoder;
+        let msg = encode_data(&mut encoder.buf, msg)?;
+        encoder.end(msg)
+    }
+}
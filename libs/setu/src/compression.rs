@@ -0,0 +1,170 @@
+//! Per-message compression.
+//!
+//! The encoding of a message stream is negotiated with `rpc-encoding` and `rpc-accept-encoding` headers.
+//! Each frame that carries a compressed message has its `is_compressed` bit set.
+use std::io::{self, Read};
+
+pub(crate) const RPC_ENCODING: &str = "rpc-encoding";
+pub(crate) const RPC_ACCEPT_ENCODING: &str = "rpc-accept-encoding";
+
+/// Messages smaller then this (in bytes) are sent uncompressed.
+pub(crate) const DEFAULT_THRESHOLD: usize = 1024;
+
+/// Compression algorithm, enabled by the cargo feature of the same name.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    #[cfg(feature = "zstd")]
+    Zstd,
+    #[cfg(feature = "gzip")]
+    Gzip,
+    #[cfg(feature = "deflate")]
+    Deflate,
+}
+
+impl Encoding {
+    /// All supported encodings, in order of preference.
+    pub const ALL: &[Encoding] = &[
+        #[cfg(feature = "zstd")]
+        Encoding::Zstd,
+        #[cfg(feature = "gzip")]
+        Encoding::Gzip,
+        #[cfg(feature = "deflate")]
+        Encoding::Deflate,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "zstd")]
+            Encoding::Zstd => "zstd",
+            #[cfg(feature = "gzip")]
+            Encoding::Gzip => "gzip",
+            #[cfg(feature = "deflate")]
+            Encoding::Deflate => "deflate",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Encoding> {
+        Encoding::ALL
+            .iter()
+            .copied()
+            .find(|encoding| encoding.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Picks the first supported encoding, from a comma separated list. (`rpc-accept-encoding` header value)
+    pub(crate) fn negotiate(accept: &str) -> Option<Encoding> {
+        accept
+            .split(',')
+            .find_map(|name| Encoding::from_name(name.trim()))
+    }
+
+    /// Value of `rpc-accept-encoding` header, `None` if no encoding is enabled.
+    pub(crate) fn accept_header() -> Option<String> {
+        let names: Vec<_> = Encoding::ALL.iter().map(|e| e.name()).collect();
+        (!names.is_empty()).then(|| names.join(", "))
+    }
+
+    #[allow(unused_variables)]
+    pub(crate) fn compress(self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "zstd")]
+            Encoding::Zstd => zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL),
+            #[cfg(feature = "gzip")]
+            Encoding::Gzip => {
+                use io::Write;
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "deflate")]
+            Encoding::Deflate => {
+                use io::Write;
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+        }
+    }
+
+    /// Decompress `data`, up to `limit` bytes.
+    #[allow(unused_variables, unreachable_code)]
+    pub(crate) fn decompress(self, data: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+        let reader: Box<dyn Read + '_> = match self {
+            #[cfg(feature = "zstd")]
+            Encoding::Zstd => Box::new(zstd::stream::read::Decoder::new(data)?),
+            #[cfg(feature = "gzip")]
+            Encoding::Gzip => Box::new(flate2::read::GzDecoder::new(data)),
+            #[cfg(feature = "deflate")]
+            Encoding::Deflate => Box::new(flate2::read::ZlibDecoder::new(data)),
+        };
+        let mut buf = Vec::new();
+        reader.take(limit as u64).read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// Compression applied by the sending side of a message stream.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Compression {
+    pub encoding: Encoding,
+    pub threshold: usize,
+}
+
+impl Compression {
+    /// Returns the compressed `msg`.
+    ///
+    /// `None` if `msg` is sent as it is, because it's smaller then the threshold,
+    /// or compression doesn't make it any smaller.
+    pub fn apply(&self, msg: &[u8]) -> Option<Vec<u8>> {
+        if msg.len() < self.threshold {
+            return None;
+        }
+        match self.encoding.compress(msg) {
+            Ok(compressed) if compressed.len() < msg.len() => Some(compressed),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let data = b"setu ".repeat(1024);
+        for &encoding in Encoding::ALL {
+            let compressed = encoding.compress(&data).unwrap();
+            assert!(compressed.len() < data.len());
+            assert_eq!(encoding.decompress(&compressed, usize::MAX).unwrap(), data);
+            assert_eq!(encoding.decompress(&compressed, 10).unwrap(), &data[..10]);
+        }
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(Encoding::negotiate(""), None);
+        assert_eq!(Encoding::negotiate("br, identity"), None);
+        for &encoding in Encoding::ALL {
+            let accept = format!("br, {}", encoding.name().to_uppercase());
+            assert_eq!(Encoding::negotiate(&accept), Some(encoding));
+        }
+    }
+
+    #[test]
+    fn test_threshold() {
+        let Some(&encoding) = Encoding::ALL.first() else {
+            return;
+        };
+        let compression = Compression {
+            encoding,
+            threshold: 16,
+        };
+        assert_eq!(compression.apply(&[0; 8]), None);
+
+        let msg = compression.apply(&[0; 64]).unwrap();
+        assert_eq!(encoding.decompress(&msg, 64).unwrap(), vec![0; 64]);
+    }
+}
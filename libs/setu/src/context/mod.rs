@@ -1,6 +1,9 @@
 mod store;
 
-use crate::Timeout;
+use crate::{
+    Timeout, compression::DEFAULT_THRESHOLD, frame::Limits, interceptor::Stack,
+    transport::http::ShutdownSignal,
+};
 use std::{
     cell::{RefCell, UnsafeCell},
     net::SocketAddr,
@@ -62,13 +65,55 @@ impl Context {
 pub struct State {
     pub addr: SocketAddr,
     pub state: RefCell<Store>,
+    pub(crate) shutdown: ShutdownSignal,
+    /// Minimum size of a response message to be compressed.
+    pub(crate) compression_threshold: usize,
+    /// Size limits of incoming messages.
+    pub(crate) limits: Limits,
+    /// Server interceptors.
+    pub(crate) interceptors: Stack,
+}
+
+/// Server options, shared by every connection.
+#[derive(Debug, Clone)]
+pub(crate) struct Options {
+    pub compression_threshold: usize,
+    pub limits: Limits,
+    pub interceptors: Stack,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            compression_threshold: DEFAULT_THRESHOLD,
+            limits: Limits::default(),
+            interceptors: Stack::from([]),
+        }
+    }
 }
 
 impl State {
     pub fn new(addr: SocketAddr) -> Rc<Self> {
+        State::with_options(addr, ShutdownSignal::default(), Options::default())
+    }
+
+    pub(crate) fn with_options(
+        addr: SocketAddr,
+        shutdown: ShutdownSignal,
+        options: Options,
+    ) -> Rc<Self> {
+        let Options {
+            compression_threshold,
+            limits,
+            interceptors,
+        } = options;
         Rc::new(State {
             addr,
             state: RefCell::new(Store::new()),
+            shutdown,
+            compression_threshold,
+            limits,
+            interceptors,
         })
     }
 }
@@ -1,15 +1,24 @@
 mod writer;
 
-use crate::{Result, Status};
+use crate::{Result, RpcError, Status, compression::Encoding};
 use bytes::{Buf, Bytes};
 use futures::{Stream, StreamExt};
-pub use writer::FrameEncoder;
+use lipi::decoder::{DecodeLimits, DecodeOptions};
+use std::{
+    borrow::Cow,
+    io, slice,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+pub use writer::{FrameEncoder, Message};
 
 type StreamData = Result<Bytes, h2::Error>;
 
 #[derive(Debug)]
 pub struct MaybeCompressed<T> {
-    #[allow(unused)]
+    #[allow(dead_code)]
     pub is_compressed: bool,
     pub data: T,
 }
@@ -41,14 +50,64 @@ impl Frame {
 pub enum RawBytes {
     Bytes(Bytes),
     Buf(Vec<u8>),
+    /// Frame received in multiple chunks, that aren't copied into a contiguous buffer.
+    Chunks(Vec<Bytes>),
+}
+
+/// Default maximum length of a single frame.
+pub const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// Size limits of incoming frames.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Maximum length of a single message, in bytes. (Default: 16 MiB)
+    pub max_frame_size: usize,
+    /// Maximum total length of all frames of a stream, in bytes. (Default: unlimited)
+    ///
+    /// Compressed frames are counted by their decompressed length.
+    pub max_stream_size: usize,
+    /// Resource limits of decoding a message. (Default: [DecodeLimits::default])
+    pub decode: DecodeLimits,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_frame_size: MAX_FRAME_SIZE,
+            max_stream_size: usize::MAX,
+            decode: DecodeLimits::default(),
+        }
+    }
+}
+
+impl Limits {
+    /// Decodes a message, within [Limits::decode].
+    pub fn decode<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let options = DecodeOptions::new().limits(self.decode);
+        Ok(options.scope(f)?.value)
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct FrameDecoder {
     data: bytes::Bytes,
+    /// Negotiated encoding of compressed frames.
+    encoding: Option<Encoding>,
+    pub limits: Limits,
+    /// Total length of received frames, shared with the rpc [Call](crate::interceptor::Call).
+    pub(crate) received: Arc<AtomicUsize>,
 }
 
 impl FrameDecoder {
+    pub fn new(encoding: Option<Encoding>, limits: Limits) -> Self {
+        Self {
+            data: Bytes::new(),
+            encoding,
+            limits,
+            received: Arc::default(),
+        }
+    }
+
     pub async fn parse<I>(&mut self, stream: &mut I) -> Result<MaybeCompressed<Frame>>
     where
         I: Stream<Item = StreamData> + Unpin,
@@ -56,9 +115,24 @@ impl FrameDecoder {
         let header = FrameHeader::parse(self.read_byte(stream).await?);
         let len = self.parse_len_big_endian(stream, header.len_size).await?;
 
-        // TODO: `len` should less then 16MB
+        self.reserve(len)?;
+        let mut bytes = self.read_bytes(stream, len).await?;
 
-        let bytes = self.read_bytes(stream, len).await?;
+        if header.is_compressed {
+            let encoding = self
+                .encoding
+                .ok_or("received compressed frame, without `rpc-encoding`")?;
+
+            let max = self.limits.max_frame_size;
+            let buf = encoding.decompress(&bytes.contiguous(), max.saturating_add(1))?;
+            if buf.len() > max {
+                return Err(frame_too_large(max).into());
+            }
+            // Counted by its decompressed length, instead.
+            self.received.fetch_sub(len, Ordering::Relaxed);
+            self.count(buf.len())?;
+            bytes = RawBytes::Buf(buf);
+        }
 
         Ok(MaybeCompressed {
             is_compressed: header.is_compressed,
@@ -73,6 +147,30 @@ impl FrameDecoder {
         })
     }
 
+    /// Checks `len` of the next frame against limits, before it's allocated.
+    fn reserve(&mut self, len: usize) -> Result<()> {
+        let max_frame_size = self.limits.max_frame_size;
+        if len > max_frame_size {
+            return Err(frame_too_large(max_frame_size).into());
+        }
+        self.count(len)
+    }
+
+    /// Adds `len` to the total length of the stream, and checks it against limits.
+    fn count(&mut self, len: usize) -> Result<()> {
+        let max_stream_size = self.limits.max_stream_size;
+        let received = self.received.load(Ordering::Relaxed).saturating_add(len);
+        self.received.store(received, Ordering::Relaxed);
+        if received > max_stream_size {
+            return Err(RpcError::new(
+                Status::ResourceExhausted,
+                format!("stream size exceeds limit of {max_stream_size} bytes"),
+            )
+            .into());
+        }
+        Ok(())
+    }
+
     async fn parse_len_big_endian<I>(&mut self, stream: &mut I, size: u8) -> Result<usize>
     where
         I: Stream<Item = StreamData> + Unpin,
@@ -98,17 +196,18 @@ impl FrameDecoder {
             return Ok(RawBytes::Bytes(data.split_to(len)));
         }
 
-        let mut buf = Vec::with_capacity(len);
+        let mut remaining = len;
+        let mut chunks = Vec::new();
 
-        while buf.len() < len {
+        while remaining > 0 {
             let data = self.read_data(stream).await?;
 
-            let remaining = len - buf.len();
             let take = remaining.min(data.len());
-            buf.extend_from_slice(&data.split_to(take));
+            chunks.push(data.split_to(take));
+            remaining -= take;
         }
 
-        Ok(RawBytes::Buf(buf))
+        Ok(RawBytes::Chunks(chunks))
     }
 
     async fn read_byte<I>(&mut self, stream: &mut I) -> Result<u8>
@@ -136,6 +235,13 @@ impl FrameDecoder {
     }
 }
 
+pub fn frame_too_large(limit: usize) -> RpcError {
+    RpcError::new(
+        Status::ResourceExhausted,
+        format!("frame size exceeds limit of {limit} bytes"),
+    )
+}
+
 pub struct LenBE {
     buf: [u8; 4],
     size: u8,
@@ -202,6 +308,12 @@ impl FrameHeader {
         }
     }
 
+    #[inline]
+    pub const fn compressed(mut self, is_compressed: bool) -> FrameHeader {
+        self.is_compressed = is_compressed;
+        self
+    }
+
     #[inline]
     pub const fn encode(self) -> u8 {
         (self.code << 4)
@@ -221,16 +333,52 @@ impl FrameHeader {
     }
 }
 
-impl std::ops::Deref for RawBytes {
-    type Target = [u8];
-
+impl RawBytes {
+    /// Returns `None`, if the frame is split into chunks.
     #[inline]
-    fn deref(&self) -> &Self::Target {
+    pub fn as_slice(&self) -> Option<&[u8]> {
+        match self {
+            RawBytes::Bytes(bytes) => Some(bytes),
+            RawBytes::Buf(buf) => Some(buf),
+            RawBytes::Chunks(_) => None,
+        }
+    }
+
+    /// Copies the chunks of the frame, if it's split.
+    pub fn contiguous(&self) -> Cow<'_, [u8]> {
         match self {
-            RawBytes::Bytes(bytes) => bytes,
-            RawBytes::Buf(buf) => buf,
+            RawBytes::Bytes(bytes) => Cow::Borrowed(bytes),
+            RawBytes::Buf(buf) => Cow::Borrowed(buf),
+            RawBytes::Chunks(chunks) => Cow::Owned(chunks.concat()),
         }
     }
+
+    pub fn reader(&self) -> ChunksReader<'_> {
+        let (chunk, chunks) = match self {
+            RawBytes::Bytes(bytes) => (&bytes[..], [].iter()),
+            RawBytes::Buf(buf) => (&buf[..], [].iter()),
+            RawBytes::Chunks(chunks) => (&[][..], chunks.iter()),
+        };
+        ChunksReader { chunk, chunks }
+    }
+}
+
+/// Reads the chunks of a frame, in order.
+pub struct ChunksReader<'a> {
+    chunk: &'a [u8],
+    chunks: slice::Iter<'a, Bytes>,
+}
+
+impl io::Read for ChunksReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.chunk.is_empty() {
+            match self.chunks.next() {
+                Some(chunk) => self.chunk = chunk,
+                None => return Ok(0),
+            }
+        }
+        self.chunk.read(buf)
+    }
 }
 
 #[cfg(test)]
@@ -238,6 +386,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::unusual_byte_groupings)]
     fn test_frame_header() {
         let raw = FrameHeader::new(Some(Status::Cancelled), 4).encode();
         assert_eq!(raw, 0b_1_11_1_0);
@@ -272,7 +421,12 @@ mod tests {
         assert!(matches!(data, RawBytes::Bytes(d) if *d == [1, 2]));
 
         let data = de.read_bytes(&mut stream, 3).await?;
-        assert!(matches!(data, RawBytes::Buf(d) if d == [3, 4, 5]));
+        assert!(matches!(&data, RawBytes::Chunks(c) if c.len() == 3));
+        assert_eq!(*data.contiguous(), [3, 4, 5]);
+
+        let mut buf = vec![];
+        io::Read::read_to_end(&mut data.reader(), &mut buf)?;
+        assert_eq!(buf, [3, 4, 5]);
         Ok(())
     }
 
@@ -284,6 +438,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_encode_message() -> Result<()> {
+        use lipi::Encode;
+
+        let mut buf = bytes::BytesMut::new();
+        let msg = Message::encode(&mut buf, &("Hello", 42u8))?;
+        assert_eq!(msg.payload(), ("Hello", 42u8).to_bytes()?);
+        let frame = msg.freeze(0, Some(&mut buf));
+        let ptr = frame.as_ptr();
+        drop(frame);
+
+        // Allocation of the previous frame is reused, once it's dropped.
+        let msg = Message::encode(&mut buf, &("World", 7u8))?;
+        assert_eq!(msg.payload().as_ptr(), ptr.wrapping_add(2));
+
+        // Last frame of the stream keeps its allocation.
+        msg.freeze(0, None);
+        assert_eq!(buf.capacity(), 0);
+        Ok(())
+    }
+
     #[test]
     fn test_len_be() {
         assert_eq!(&*LenBE::new(0x1234), [0x12, 0x34]);
@@ -297,19 +472,110 @@ mod tests {
 
         let mut de = FrameDecoder::default();
 
-        assert_eq!(
-            de.parse(&mut stream)
-                .await?
-                .data
-                .message()
-                .unwrap()
-                .as_ref(),
-            [54, 55]
-        );
+        let msg = de.parse(&mut stream).await?.data.message().unwrap();
+        assert_eq!(*msg.contiguous(), [54, 55]);
 
         let (status, data) = de.parse(&mut stream).await?.data.trailer().unwrap();
         assert_eq!(status, Status::Ok);
-        assert!(data.is_empty());
+        assert!(data.contiguous().is_empty());
+        Ok(())
+    }
+
+    #[nio::test]
+    async fn test_decode_compressed_frame() -> Result<()> {
+        let header = FrameHeader::new(None, 1).compressed(true).encode();
+        let mut stream = futures::stream::iter([Ok(Bytes::from(vec![header, 0]))]);
+        assert!(FrameDecoder::default().parse(&mut stream).await.is_err());
+
+        for &encoding in Encoding::ALL {
+            let msg = encoding.compress(&[42; 64])?;
+            let len = LenBE::new(msg.len());
+            let header = FrameHeader::new(None, len.size).compressed(true).encode();
+
+            let frame = [&[header], &*len, &msg].concat();
+            let mut stream = futures::stream::iter([Ok(Bytes::from(frame))]);
+
+            let mut de = FrameDecoder::new(Some(encoding), Limits::default());
+            let frame = de.parse(&mut stream).await?;
+            assert!(frame.is_compressed);
+            assert_eq!(*frame.data.message().unwrap().contiguous(), [42; 64]);
+        }
+        Ok(())
+    }
+
+    #[nio::test]
+    async fn test_frame_size_limit() -> Result<()> {
+        const HEADER: u8 = FrameHeader::new(None, 4).encode();
+        let mut stream = create_stream(&[&[HEADER, 0xFF, 0xFF, 0xFF, 0xFF]]);
+
+        // Rejected before reading (or allocating) the frame.
+        let err = FrameDecoder::default()
+            .parse(&mut stream)
+            .await
+            .unwrap_err();
+        let err = err.downcast::<RpcError>().unwrap();
+        assert_eq!(err.status, Status::ResourceExhausted);
+        Ok(())
+    }
+
+    #[nio::test]
+    async fn test_stream_size_limit() -> Result<()> {
+        let mut stream = create_stream(&[&[0, 2, 1, 2], &[0, 2, 3, 4], &[0, 1, 5]]);
+        let limits = Limits {
+            max_frame_size: 2,
+            max_stream_size: 4,
+            ..Limits::default()
+        };
+        let mut de = FrameDecoder::new(None, limits);
+        de.parse(&mut stream).await?;
+        de.parse(&mut stream).await?;
+
+        let err = de.parse(&mut stream).await.unwrap_err();
+        let err = err.downcast::<RpcError>().unwrap();
+        assert_eq!(err.status, Status::ResourceExhausted);
+
+        // Compressed frames are counted by their decompressed length.
+        for &encoding in Encoding::ALL {
+            let msg = encoding.compress(&[42; 64])?;
+            let len = LenBE::new(msg.len());
+            let header = FrameHeader::new(None, len.size).compressed(true).encode();
+
+            let frame = Bytes::from([&[header], &*len, &msg].concat());
+            let mut stream = futures::stream::iter([Ok(frame.clone()), Ok(frame)]);
+
+            let limits = Limits {
+                max_stream_size: 100,
+                ..Limits::default()
+            };
+            let mut de = FrameDecoder::new(Some(encoding), limits);
+            de.parse(&mut stream).await?;
+            assert_eq!(de.received.load(Ordering::Relaxed), 64);
+
+            let err = de.parse(&mut stream).await.unwrap_err();
+            let err = err.downcast::<RpcError>().unwrap();
+            assert_eq!(err.status, Status::ResourceExhausted);
+        }
+        Ok(())
+    }
+
+    #[nio::test]
+    async fn test_decompressed_size_limit() -> Result<()> {
+        for &encoding in Encoding::ALL {
+            let msg = encoding.compress(&[42; 64])?;
+            let len = LenBE::new(msg.len());
+            let header = FrameHeader::new(None, len.size).compressed(true).encode();
+
+            let frame = [&[header], &*len, &msg].concat();
+            let mut stream = futures::stream::iter([Ok(Bytes::from(frame))]);
+
+            let limits = Limits {
+                max_frame_size: 32,
+                ..Limits::default()
+            };
+            let mut de = FrameDecoder::new(Some(encoding), limits);
+            let err = de.parse(&mut stream).await.unwrap_err();
+            assert!(err.is::<RpcError>());
+        }
         Ok(())
     }
 }
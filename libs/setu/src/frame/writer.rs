@@ -1,56 +1,191 @@
-use bytes::Bytes;
+use bytes::{BufMut, Bytes, BytesMut};
+use http::HeaderValue;
 use lipi::Encode;
+use std::io;
 
-use crate::frame::{FrameHeader, LenBE};
+use crate::compression::{Compression, Encoding, RPC_ACCEPT_ENCODING, RPC_ENCODING};
+use crate::frame::{FrameHeader, LenBE, frame_too_large};
+use crate::interceptor::Call;
 use crate::transport::http::{HttpResponse, HttpWriter};
-use crate::{Status, Trailer};
+use crate::{Result, RpcError, Status, Trailer};
 
 impl HttpResponse {
-    pub fn create_setu_stream(mut self) -> Result<FrameEncoder, h2::Error> {
+    pub(crate) fn create_setu_stream(
+        mut self,
+        compression: Option<Compression>,
+        max_frame_size: usize,
+        call: Call,
+    ) -> Result<FrameEncoder, h2::Error> {
         self.add_setu_content_type_header();
+        let headers = self.headers_mut();
+        if let Some(accept) = Encoding::accept_header() {
+            headers.insert(RPC_ACCEPT_ENCODING, accept.try_into().unwrap());
+        }
+        if let Some(compression) = compression {
+            let encoding = HeaderValue::from_static(compression.encoding.name());
+            headers.insert(RPC_ENCODING, encoding);
+        }
         Ok(FrameEncoder {
             stream: self.create_stream()?,
+            compression,
+            max_frame_size,
+            call: Some(call),
+            buf: BytesMut::new(),
         })
     }
 }
 
+/// Encoded message, after the space reserved for its frame header.
+pub struct Message {
+    frame: BytesMut,
+    header_len: usize,
+}
+
+impl Message {
+    /// Encodes `msg` into the allocation of `buf`, sized with its [Encode::encoded_len].
+    pub fn encode(buf: &mut BytesMut, msg: &impl Encode) -> io::Result<Message> {
+        let len = msg.encoded_len();
+        let mut frame = std::mem::take(buf);
+        let header_len = Self::reserve(&mut frame, len);
+        msg.encode(&mut (&mut frame).writer())?;
+
+        if frame.len() != header_len + len {
+            return Err(io::Error::other("encoded length mismatch"));
+        }
+        Ok(Message { frame, header_len })
+    }
+
+    fn from_slice(buf: &mut BytesMut, msg: &[u8]) -> Message {
+        let mut frame = std::mem::take(buf);
+        let header_len = Self::reserve(&mut frame, msg.len());
+        frame.put_slice(msg);
+        Message { frame, header_len }
+    }
+
+    /// Writes the frame length, the header byte is written by [Message::freeze].
+    fn reserve(frame: &mut BytesMut, len: usize) -> usize {
+        let len_be = LenBE::new(len);
+        let header_len = 1 + len_be.len();
+
+        frame.clear();
+        // Reuses the allocation of the previous frame, if the peer no longer holds it.
+        if !frame.try_reclaim(header_len + len) {
+            *frame = BytesMut::with_capacity(header_len + len);
+        }
+        frame.put_u8(0);
+        frame.put_slice(&len_be);
+        header_len
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        &self.frame[self.header_len..]
+    }
+
+    /// Returns the frame, with its `header`.
+    ///
+    /// If `buf` is given, it keeps the allocation, so that the next frame can reclaim it.
+    /// Otherwise the frame owns it, without any extra allocation.
+    pub(crate) fn freeze(mut self, header: u8, buf: Option<&mut BytesMut>) -> Bytes {
+        self.frame[0] = header;
+        match buf {
+            Some(buf) => {
+                let frame = self.frame.split().freeze();
+                *buf = self.frame;
+                frame
+            }
+            None => self.frame.freeze(),
+        }
+    }
+}
+
 pub struct FrameEncoder {
     pub stream: HttpWriter,
+    /// Messages are compressed, if set.
+    pub(crate) compression: Option<Compression>,
+    /// Maximum length of a message, that the peer accepts.
+    pub(crate) max_frame_size: usize,
+    /// Server side rpc, that is notified on completion.
+    pub(crate) call: Option<Call>,
+    /// Allocation of the last frame, reused by the next one once it's sent.
+    pub(crate) buf: BytesMut,
 }
 
 impl FrameEncoder {
-    pub fn send_error(mut self, status: Status, reason: String) -> Result<(), h2::Error> {
-        debug_assert!(status != Status::Ok);
+    pub fn send_error(mut self, err: RpcError) -> Result<(), h2::Error> {
+        self.write_error(err)
+    }
 
-        let Ok(msg) = Trailer::from(reason).to_bytes() else {
-            return Ok(());
-        };
+    /// Encodes `msg` into the buffer of the stream.
+    pub fn encode(&mut self, msg: &impl Encode) -> io::Result<Message> {
+        Message::encode(&mut self.buf, msg)
+    }
 
-        self.stream
-            .write_unbound(encode_header(Some(status), &msg))?;
+    pub async fn send(&mut self, msg: Message) -> Result<()> {
+        let frame = self.frame(None, msg)?;
+        Ok(self.stream.write(frame).await?)
+    }
 
-        self.stream.end_write_unbound(msg)
+    pub fn end(mut self, msg: Message) -> Result<()> {
+        let frame = self.frame(Some(Status::Ok), msg)?;
+        Ok(self.stream.end_write_unbound(frame)?)
     }
 
-    pub async fn send(&mut self, msg: Vec<u8>) -> Result<(), h2::Error> {
-        self.stream.write_unbound(encode_header(None, &msg))?;
-        self.stream.write(msg).await
+    /// Writes the header of `msg`, after it's compressed.
+    fn frame(&mut self, status: Option<Status>, msg: Message) -> Result<Bytes> {
+        self.check_frame_size(msg.payload())?;
+        let (is_compressed, msg) = self.compress(msg);
+        Ok(self.write_header(status, is_compressed, msg))
     }
 
-    pub fn end(mut self, msg: Vec<u8>) -> Result<(), h2::Error> {
-        self.stream
-            .write_unbound(encode_header(Some(Status::Ok), &msg))?;
+    fn write_header(&mut self, status: Option<Status>, is_compressed: bool, msg: Message) -> Bytes {
+        let len = msg.payload().len();
+        let header = FrameHeader::new(status, LenBE::new(len).size)
+            .compressed(is_compressed)
+            .encode();
+        self.sent(status, len);
+        // Only a frame that doesn't end the stream, is followed by another one.
+        let buf = status.is_none().then_some(&mut self.buf);
+        msg.freeze(header, buf)
+    }
 
-        self.stream.end_write_unbound(msg)
+    /// Ends the stream with [Status::ResourceExhausted], if `msg` is larger then the peer accepts.
+    fn check_frame_size(&mut self, msg: &[u8]) -> Result<()> {
+        if msg.len() <= self.max_frame_size {
+            return Ok(());
+        }
+        let err = frame_too_large(self.max_frame_size);
+        self.write_error(err.clone())?;
+        Err(err.into())
     }
-}
 
-pub fn encode_header(status: Option<Status>, msg: &[u8]) -> Bytes {
-    let len = LenBE::new(msg.len());
+    fn write_error(&mut self, err: RpcError) -> Result<(), h2::Error> {
+        let status = err.status;
+        debug_assert!(status != Status::Ok);
+
+        let Ok(msg) = self.encode(&Trailer::from(err)) else {
+            return Ok(());
+        };
+        let frame = self.write_header(Some(status), false, msg);
+        self.stream.stream.send_data(frame, true)
+    }
 
-    let mut frame = Vec::with_capacity(1 + len.size as usize);
-    frame.push(FrameHeader::new(status, len.size).encode());
-    frame.extend_from_slice(&len);
+    fn sent(&mut self, status: Option<Status>, len: usize) {
+        if let Some(call) = &mut self.call {
+            call.sent += len;
+            if let Some(status) = status {
+                call.status = status;
+            }
+        }
+    }
 
-    Bytes::from(frame.into_boxed_slice())
+    fn compress(&mut self, msg: Message) -> (bool, Message) {
+        let compressed = match &self.compression {
+            Some(compression) => compression.apply(msg.payload()),
+            None => None,
+        };
+        match compressed {
+            Some(compressed) => (true, Message::from_slice(&mut self.buf, &compressed)),
+            None => (false, msg),
+        }
+    }
 }
@@ -1,17 +1,17 @@
 use crate::{
-    Result, Status,
-    frame::{Frame, FrameDecoder, RawBytes},
+    Result, RpcError, Status,
+    frame::{Frame, FrameDecoder, Limits, RawBytes},
     transport::http::HttpBody,
 };
 use lipi::{
     Decode,
-    decoder::{FieldDecoder, FieldDecoderOwned, FieldInfoDecoder, Optional},
+    decoder::{FieldDecoder, FieldDecoderOwned, FieldInfoDecoder, Optional, ReadDecoder},
 };
 use setu_type_info::{
     GeneratorType,
     type_id::{OtherType, Type, TypeId, TypeRegistry},
 };
-use std::{future::Future, marker::PhantomData};
+use std::{future::Future, io, marker::PhantomData};
 use std::{ops::ControlFlow, sync::Arc};
 
 pub struct Stream<T, R = ()> {
@@ -36,7 +36,7 @@ where
     T::Value: FieldDecoderOwned,
     R::Value: FieldDecoderOwned,
 {
-    fn new(frame_decoder: FrameDecoder, input: HttpBody) -> Self {
+    pub(crate) fn new(frame_decoder: FrameDecoder, input: HttpBody) -> Self {
         Self {
             input,
             frame_decoder,
@@ -44,19 +44,74 @@ where
         }
     }
 
+    /// Overrides maximum length of a single message, for this stream.
+    pub fn set_max_frame_size(&mut self, size: usize) {
+        self.frame_decoder.limits.max_frame_size = size;
+    }
+
+    /// Overrides maximum total length of all messages, for this stream.
+    ///
+    /// Messages that have already been received, are also counted.
+    pub fn set_max_stream_size(&mut self, size: usize) {
+        self.frame_decoder.limits.max_stream_size = size;
+    }
+
     pub async fn next(&mut self) -> Result<ControlFlow<R, T>> {
+        let limits = self.frame_decoder.limits;
         match self.frame_decoder.parse(&mut self.input).await?.data {
-            Frame::Message(bytes) => decode_optional_field(&mut &*bytes).map(ControlFlow::Continue),
+            Frame::Message(bytes) => decode_message(&limits, &bytes)
+                .map(|Data(data)| data)
+                .map(ControlFlow::Continue),
             Frame::Trailer { status, bytes } => {
                 if status != Status::Ok {
-                    return Err(format!("unexpected status: {status:?}").into());
+                    return Err(RpcError::from_trailer(status, &bytes.contiguous()).into());
                 }
-                decode_optional_field(&mut &*bytes).map(ControlFlow::Break)
+                decode_message(&limits, &bytes)
+                    .map(|Data(data)| data)
+                    .map(ControlFlow::Break)
             }
         }
     }
 }
 
+/// Decoded from a message frame.
+///
+/// Frames that are split into chunks, are decoded field by field with a [ReadDecoder],
+/// instead of being copied into a contiguous buffer.
+trait Message: Sized {
+    fn decode(reader: &mut &[u8]) -> Result<Self>;
+    fn decode_fields(decoder: &mut ReadDecoder<impl io::Read>) -> Result<Self>;
+}
+
+fn decode_message<T: Message>(limits: &Limits, bytes: &RawBytes) -> Result<T> {
+    limits.decode(|| match bytes.as_slice() {
+        Some(mut reader) => T::decode(&mut reader),
+        None => T::decode_fields(&mut ReadDecoder::new(bytes.reader())),
+    })
+}
+
+/// Stream message, encoded as field `0`, without struct end.
+struct Data<T>(T);
+
+impl<T> Message for Data<T>
+where
+    T: Optional,
+    T::Value: FieldDecoderOwned,
+{
+    fn decode(reader: &mut &[u8]) -> Result<Self> {
+        decode_optional_field(reader).map(Data)
+    }
+
+    fn decode_fields(decoder: &mut ReadDecoder<impl io::Read>) -> Result<Self> {
+        let mut val = None;
+        if let Some((key, ty)) = decoder.next_field_id_and_ty()? {
+            expect_first_field(key)?;
+            val = Some(decoder.decode_field(ty, "tuple 0")?);
+        }
+        Ok(Data(Optional::convert(val, "tuple 0")?))
+    }
+}
+
 fn decode_optional_field<'de, T>(reader: &mut &'de [u8]) -> Result<T>
 where
     T: Optional,
@@ -65,21 +120,31 @@ where
     let mut val = None;
     let mut fd = FieldInfoDecoder::new(reader);
     if let Some((key, ty)) = fd.next_field_id_and_ty()? {
-        assert_eq!(key, 0);
+        expect_first_field(key)?;
         val = fd.decode(ty, "tuple 0")?;
     }
     Ok(Optional::convert(val, "tuple 0")?)
 }
 
+fn expect_first_field(key: u64) -> Result<()> {
+    if key != 0 {
+        return Err(format!("invalid field id: expected `0`, found {key}").into());
+    }
+    Ok(())
+}
+
 // =======================================================================
 
 pub trait Input: Sized {
     const LEN: u8 = 0;
-    fn unmarshal(input: HttpBody) -> impl Future<Output = Result<Self>> + Send;
+    fn unmarshal(
+        frame_decoder: FrameDecoder,
+        input: HttpBody,
+    ) -> impl Future<Output = Result<Self>> + Send;
 }
 
 impl Input for () {
-    async fn unmarshal(_: HttpBody) -> Result<Self> {
+    async fn unmarshal(_: FrameDecoder, _: HttpBody) -> Result<Self> {
         Ok(())
     }
 }
@@ -91,25 +156,68 @@ where
     T::Value: FieldDecoderOwned,
     R::Value: FieldDecoderOwned,
 {
-    async fn unmarshal(input: HttpBody) -> Result<Self> {
-        let frame_decoder = FrameDecoder::default();
+    async fn unmarshal(mut frame_decoder: FrameDecoder, mut input: HttpBody) -> Result<Self> {
+        // Arguments are always sent as the first message, even if there is none.
+        let bytes = decode_first_msg(&mut frame_decoder, &mut input).await?;
+        decode_message::<()>(&frame_decoder.limits, &bytes)?;
+
         Ok((Stream::new(frame_decoder, input),))
     }
 }
 
 // =======================================================================
 
+impl Message for () {
+    fn decode(reader: &mut &[u8]) -> Result<Self> {
+        <() as Decode>::decode(reader)
+    }
+
+    fn decode_fields(decoder: &mut ReadDecoder<impl io::Read>) -> Result<Self> {
+        while let Some((key, ty)) = decoder.next_field_id_and_ty()? {
+            decoder.unknown_field(key, ty)?;
+        }
+        Ok(())
+    }
+}
+
 macro_rules! tuples {
     [Len: $len:tt $($name:tt : $idx:tt)*] => {
+        impl<$($name,)*> Message for ($($name,)*)
+        where
+            $($name: Optional,)*
+            $($name::Value: FieldDecoderOwned,)*
+        {
+            fn decode(reader: &mut &[u8]) -> Result<Self> {
+                <Self as Decode>::decode(reader)
+            }
+
+            #[allow(non_snake_case)]
+            fn decode_fields(decoder: &mut ReadDecoder<impl io::Read>) -> Result<Self> {
+                $(let mut $name = None;)*
+
+                while let Some((key, ty)) = decoder.next_field_id_and_ty()? {
+                    match key {
+                        $($idx => $name = Some(decoder.decode_field(ty, concat!("tuple ", $idx))?),)*
+                        _ => decoder.unknown_field(key, ty)?,
+                    }
+                }
+
+                Ok((
+                    $(Optional::convert($name, concat!("tuple ", $idx))?,)*
+                ))
+            }
+        }
+
         impl<$($name,)*> Input for ($($name,)*)
         where
             $($name: Optional,)*
             $($name::Value: FieldDecoderOwned,)*
         {
             const LEN: u8 = $len;
-            async fn unmarshal(input: HttpBody) -> Result<Self> {
-                let bytes = decode_last_msg(input).await?;
-                Self::decode(&mut &*bytes)
+            async fn unmarshal(frame_decoder: FrameDecoder, input: HttpBody) -> Result<Self> {
+                let limits = frame_decoder.limits;
+                let bytes = decode_last_msg(frame_decoder, input).await?;
+                decode_message(&limits, &bytes)
             }
         }
 
@@ -123,11 +231,9 @@ macro_rules! tuples {
             R::Value: FieldDecoderOwned,
         {
             const LEN: u8 = $len;
-            async fn unmarshal(mut input: HttpBody) -> Result<Self> {
-                let mut frame_decoder = FrameDecoder::default();
-
+            async fn unmarshal(mut frame_decoder: FrameDecoder, mut input: HttpBody) -> Result<Self> {
                 let bytes = decode_first_msg(&mut frame_decoder, &mut input).await?;
-                let args = <($($name,)*)>::decode(&mut &*bytes)?;
+                let args = decode_message::<($($name,)*)>(&frame_decoder.limits, &bytes)?;
 
                 Ok(( $(args.$idx,)* Stream::new(frame_decoder, input)))
             }
@@ -165,9 +271,10 @@ async fn decode_first_msg(
     Ok(bytes)
 }
 
-async fn decode_last_msg(mut stream: HttpBody) -> Result<RawBytes> {
-    let mut frame_decoder = FrameDecoder::default();
-
+async fn decode_last_msg(
+    mut frame_decoder: FrameDecoder,
+    mut stream: HttpBody,
+) -> Result<RawBytes> {
     let (status, bytes) = frame_decoder
         .parse(&mut stream)
         .await?
@@ -176,7 +283,32 @@ async fn decode_last_msg(mut stream: HttpBody) -> Result<RawBytes> {
         .ok_or("expected trailer frame")?;
 
     if status != Status::Ok {
-        return Err(format!("unexpected status: {status:?}").into());
+        return Err(RpcError::from_trailer(status, &bytes.contiguous()).into());
     }
     Ok(bytes)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    #[test]
+    fn test_invalid_field_id() {
+        let limits = Limits::default();
+        // Field `1` of `u8`, without struct end.
+        let bytes = [0x12, 42];
+
+        let msg = RawBytes::Buf(bytes.to_vec());
+        let err = decode_message::<Data<u8>>(&limits, &msg).err().unwrap();
+        assert_eq!(err.to_string(), "invalid field id: expected `0`, found 1");
+
+        let chunks = bytes.iter().map(|b| Bytes::copy_from_slice(&[*b]));
+        let msg = RawBytes::Chunks(chunks.collect());
+        let err = decode_message::<Data<u8>>(&limits, &msg).err().unwrap();
+        assert_eq!(err.to_string(), "invalid field id: expected `0`, found 1");
+
+        let msg = RawBytes::Buf(vec![0x02, 42]);
+        assert_eq!(decode_message::<Data<u8>>(&limits, &msg).unwrap().0, 42);
+    }
+}
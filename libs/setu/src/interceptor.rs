@@ -0,0 +1,172 @@
+//! Interceptors run around every rpc, for cross-cutting concerns (auth, logging, metrics).
+//!
+//! They are composed as a stack, on [HttpServer](crate::transport::HttpServer::interceptor)
+//! and on an [Application](crate::Application::intercept). Server interceptors run first.
+use crate::{Application, RpcError, Status, transport::http::HttpContext};
+use std::{
+    marker::PhantomData,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+pub trait Interceptor: Send + Sync + 'static {
+    /// Called before the rpc is dispatched.
+    ///
+    /// Returning an error rejects the rpc with an error trailer, the handler is never called.
+    fn intercept(&self, _id: u32, _ctx: &mut HttpContext) -> Result<(), RpcError> {
+        Ok(())
+    }
+
+    /// Called once the rpc is completed, if [intercept](Self::intercept) was successful.
+    fn complete(&self, _id: u32, _outcome: &Outcome) {}
+}
+
+/// Outcome of a completed rpc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// [Status::Cancelled], if the rpc was reset or timed out.
+    pub status: Status,
+    pub duration: Duration,
+    /// Total length of received messages, in bytes.
+    pub received: usize,
+    /// Total length of sent messages, in bytes.
+    pub sent: usize,
+}
+
+impl std::fmt::Debug for dyn Interceptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Interceptor")
+    }
+}
+
+pub(crate) type Stack = Arc<[Arc<dyn Interceptor>]>;
+
+/// Interceptors of an rpc, that are notified on completion.
+pub(crate) struct Call {
+    id: u32,
+    start: Instant,
+    /// `true`, once server interceptors have run.
+    intercepted: bool,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    pub status: Status,
+    pub received: Arc<AtomicUsize>,
+    pub sent: usize,
+}
+
+impl Call {
+    pub fn new() -> Self {
+        Self {
+            id: 0,
+            start: Instant::now(),
+            intercepted: false,
+            interceptors: Vec::new(),
+            status: Status::Cancelled,
+            received: Arc::default(),
+            sent: 0,
+        }
+    }
+}
+
+impl Drop for Call {
+    fn drop(&mut self) {
+        let outcome = Outcome {
+            status: self.status,
+            duration: self.start.elapsed(),
+            received: self.received.load(Ordering::Relaxed),
+            sent: self.sent,
+        };
+        for interceptor in self.interceptors.iter().rev() {
+            interceptor.complete(self.id, &outcome);
+        }
+    }
+}
+
+impl HttpContext {
+    /// Runs server interceptors (only once), then `interceptors`.
+    ///
+    /// Returns `None`, if the rpc was rejected.
+    pub(crate) fn intercept(
+        mut self,
+        id: u32,
+        interceptors: &[Arc<dyn Interceptor>],
+    ) -> Option<Self> {
+        self.call.id = id;
+        let server = match self.call.intercepted {
+            true => None,
+            false => Some(self.state.interceptors.clone()),
+        };
+        self.call.intercepted = true;
+
+        for interceptor in server.iter().flat_map(|s| s.iter()).chain(interceptors) {
+            if let Err(err) = interceptor.intercept(id, &mut self) {
+                self.res.reject(err.into(), self.call);
+                return None;
+            }
+            self.call.interceptors.push(interceptor.clone());
+        }
+        Some(self)
+    }
+}
+
+/// An [Application] with an interceptor stack.
+///
+/// ```no_run
+/// use setu::{Application, RpcError, Status, interceptor::Interceptor, transport::http::HttpContext};
+///
+/// struct Auth;
+///
+/// impl Interceptor for Auth {
+///     fn intercept(&self, _: u32, ctx: &mut HttpContext) -> Result<(), RpcError> {
+///         match ctx.req.meta.headers.get("authorization") {
+///             Some(_) => Ok(()),
+///             None => Err(RpcError::new(Status::PermissionDenied, "missing token")),
+///         }
+///     }
+/// }
+///
+/// fn handler<App: Application>() -> impl Fn(HttpContext) + Clone {
+///     let app = App::intercept(Auth);
+///     move |mut ctx| {
+///         if let Some(id) = ctx.req.get_rpc_key() {
+///             app.execute(id, ctx);
+///         }
+///     }
+/// }
+/// ```
+pub struct Intercepted<A> {
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    app: PhantomData<fn() -> A>,
+}
+
+impl<A> Clone for Intercepted<A> {
+    fn clone(&self) -> Self {
+        Self {
+            interceptors: self.interceptors.clone(),
+            app: PhantomData,
+        }
+    }
+}
+
+impl<A: Application> Intercepted<A> {
+    pub(crate) fn new(interceptor: impl Interceptor) -> Self {
+        Self {
+            interceptors: vec![Arc::new(interceptor)],
+            app: PhantomData,
+        }
+    }
+
+    /// Push an interceptor, that runs after the previous ones.
+    pub fn intercept(mut self, interceptor: impl Interceptor) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    pub fn execute(&self, id: u32, ctx: HttpContext) {
+        if let Some(ctx) = ctx.intercept(id, &self.interceptors) {
+            A::execute(id, ctx);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub(crate) mod frame;
 
+mod compression;
 mod context;
 mod input;
 mod status_code;
@@ -10,9 +11,14 @@ mod utils;
 
 #[doc(hidden)]
 pub mod __private;
+pub mod client;
+pub mod interceptor;
 pub mod transport;
+pub use compression::Encoding;
 pub use context::Context;
-pub use status_code::Status;
+pub use lipi::Columns;
+pub use lipi::decoder::DecodeLimits;
+pub use status_code::{RpcError, Status};
 pub use timeout::Timeout;
 pub use trailer::Trailer;
 
@@ -27,6 +33,14 @@ pub use output::Output;
 
 pub trait Application {
     fn execute(id: u32, ctx: transport::http::HttpContext);
+
+    /// Wraps the application with an interceptor stack.
+    fn intercept(interceptor: impl interceptor::Interceptor) -> interceptor::Intercepted<Self>
+    where
+        Self: Sized,
+    {
+        interceptor::Intercepted::new(interceptor)
+    }
 }
 
 pub struct SSE<S>(pub S);
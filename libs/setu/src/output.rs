@@ -1,18 +1,20 @@
 use crate::{
-    Context, Result, SSE, Status, Timeout,
-    frame::FrameEncoder,
+    Context, Encoding, Error, Result, RpcError, SSE, Status, Timeout,
+    compression::{Compression, RPC_ACCEPT_ENCODING, RPC_ENCODING},
+    frame::{FrameDecoder, FrameEncoder, Message},
     input::Input,
+    interceptor::Call,
     transport::http::{HttpBody, HttpContext, HttpRequest, HttpResponse, HttpWriter},
 };
 use async_gen::{AsyncGenerator, GeneratorState};
+use bytes::BytesMut;
 use futures::FutureExt;
 use lipi::encoder::OptionalField;
 use nio::Sleep;
 use setu_type_info::{FnOutputType, type_id::TypeId};
 use std::{
     future::poll_fn,
-    io,
-    pin::pin,
+    pin::{Pin, pin},
     str::FromStr,
     task::{self, Poll},
 };
@@ -34,39 +36,101 @@ where
         F: std_lib::FnOnce<Args, Output = Self> + 'static,
         Args: Input,
     {
-        nio::spawn_local(async move {
-            let Ok((context, mut timer, input, output)) = ctx.parts() else {
-                return;
-            };
+        process_future::<F, Args, EncodeData>(func, ctx)
+    }
+}
 
-            let args = match Args::unmarshal(input).await {
-                Err(err) => return output.send_error(http::StatusCode::BAD_REQUEST, err),
-                Ok(args) => args,
-            };
+/// Handler output of `Result<T, RpcError>`, the error ends the rpc with an error trailer.
+pub(crate) fn process_result<F, Args, T>(func: F, ctx: HttpContext)
+where
+    F: std_lib::FnOnce<Args> + 'static,
+    F::Output: Future<Output = Result<T, RpcError>>,
+    T: OptionalField,
+    Args: Input,
+{
+    process_future::<F, Args, EncodeResult>(func, ctx)
+}
 
-            let Ok(mut output) = output.create_setu_stream() else {
-                return;
-            };
+/// Handler output, that isn't part of the [TypeInfo](setu_type_info::TypeInfo) of a service.
+pub(crate) fn process_data<F, Args>(func: F, ctx: HttpContext)
+where
+    F: std_lib::FnOnce<Args> + 'static,
+    F::Output: Future<Output: OptionalField>,
+    Args: Input,
+{
+    process_future::<F, Args, EncodeData>(func, ctx)
+}
 
-            let mut fut = pin!(func.call_once(args));
-            let mut ctx = context.boxed();
+trait EncodeOutput<T> {
+    fn encode(buf: &mut BytesMut, output: T) -> Result<Message, RpcError>;
+}
 
-            let result = poll_fn(|cx| {
-                if timeout_or_cancellation(cx, timer.as_mut(), &mut output.stream).is_ready() {
-                    return Poll::Ready(None);
-                }
+struct EncodeData;
+impl<T: OptionalField> EncodeOutput<T> for EncodeData {
+    fn encode(buf: &mut BytesMut, output: T) -> Result<Message, RpcError> {
+        encode_data(buf, output)
+    }
+}
+
+struct EncodeResult;
+impl<T: OptionalField> EncodeOutput<Result<T, RpcError>> for EncodeResult {
+    fn encode(buf: &mut BytesMut, output: Result<T, RpcError>) -> Result<Message, RpcError> {
+        output.and_then(|output| encode_data(buf, output))
+    }
+}
+
+fn process_future<F, Args, E>(func: F, ctx: HttpContext)
+where
+    F: std_lib::FnOnce<Args> + 'static,
+    F::Output: Future,
+    E: EncodeOutput<<F::Output as Future>::Output>,
+    Args: Input,
+{
+    nio::spawn_local(async move {
+        let Ok(Parts {
+            context,
+            mut timer,
+            decoder,
+            input,
+            output,
+            compression,
+            call,
+        }) = ctx.parts()
+        else {
+            return;
+        };
+
+        let args = match Args::unmarshal(decoder, input).await {
+            Err(err) => return output.reject(err, call),
+            Ok(args) => args,
+        };
 
-                Context::swap(&mut ctx);
-                let poll = fut.as_mut().poll(cx);
-                Context::swap(&mut ctx);
+        let max_frame_size = context.state.limits.max_frame_size;
+        let Ok(mut output) = output.create_setu_stream(compression, max_frame_size, call) else {
+            return;
+        };
 
-                poll.map(encode_data).map(Some)
-            })
-            .await;
+        let mut shutdown = pin!(context.state.shutdown.clone().cancelled());
+        let mut fut = pin!(func.call_once(args));
+        let mut ctx = context.boxed();
 
-            send_output(output, result)
-        });
-    }
+        let result = poll_fn(|cx| {
+            if let Poll::Ready(status) =
+                interrupted(cx, timer.as_mut(), shutdown.as_mut(), &mut output.stream)
+            {
+                return Poll::Ready(Err(status));
+            }
+
+            Context::swap(&mut ctx);
+            let poll = fut.as_mut().poll(cx);
+            Context::swap(&mut ctx);
+
+            poll.map(|val| E::encode(&mut output.buf, val)).map(Ok)
+        })
+        .await;
+
+        send_output(output, result)
+    });
 }
 
 impl<S> Output for SSE<S>
@@ -81,25 +145,39 @@ where
         Args: Input,
     {
         nio::spawn_local(async {
-            let Ok((context, mut timer, input, output)) = ctx.parts() else {
+            let Ok(Parts {
+                context,
+                mut timer,
+                decoder,
+                input,
+                output,
+                compression,
+                call,
+            }) = ctx.parts()
+            else {
                 return;
             };
 
-            let args = match Args::unmarshal(input).await {
-                Err(err) => return output.send_error(http::StatusCode::BAD_REQUEST, err),
+            let args = match Args::unmarshal(decoder, input).await {
+                Err(err) => return output.reject(err, call),
                 Ok(args) => args,
             };
 
-            let Ok(mut output) = output.create_setu_stream() else {
+            let max_frame_size = context.state.limits.max_frame_size;
+            let Ok(mut output) = output.create_setu_stream(compression, max_frame_size, call)
+            else {
                 return;
             };
 
+            let mut shutdown = pin!(context.state.shutdown.clone().cancelled());
             let mut stream = pin!(func.call_once(args).0);
             let mut ctx = context.boxed();
             loop {
                 let resume = poll_fn(|cx| {
-                    if timeout_or_cancellation(cx, timer.as_mut(), &mut output.stream).is_ready() {
-                        return Poll::Ready(None);
+                    if let Poll::Ready(status) =
+                        interrupted(cx, timer.as_mut(), shutdown.as_mut(), &mut output.stream)
+                    {
+                        return Poll::Ready(Err(status));
                     }
 
                     Context::swap(&mut ctx);
@@ -107,14 +185,16 @@ where
                     Context::swap(&mut ctx);
 
                     poll.map(|state| match state {
-                        GeneratorState::Yielded(data) => {
-                            (encode_data(data), GeneratorState::Yielded(()))
-                        }
-                        GeneratorState::Complete(data) => {
-                            (encode_data(data), GeneratorState::Complete(()))
-                        }
+                        GeneratorState::Yielded(data) => (
+                            encode_data(&mut output.buf, data),
+                            GeneratorState::Yielded(()),
+                        ),
+                        GeneratorState::Complete(data) => (
+                            encode_data(&mut output.buf, data),
+                            GeneratorState::Complete(()),
+                        ),
                     })
-                    .map(Some)
+                    .map(Ok)
                 })
                 .await;
 
@@ -128,14 +208,23 @@ where
     }
 }
 
-type MaybeResumed = Option<(io::Result<Vec<u8>>, GeneratorState<(), ()>)>;
+/// `Err(None)`: The stream was reset, `Err(Some(status))`: The rpc was interrupted with `status`.
+type Interrupted = Option<Status>;
+
+type MaybeResumed = Result<(Result<Message, RpcError>, GeneratorState<(), ()>), Interrupted>;
 async fn send_stream(mut output: FrameEncoder, resume: MaybeResumed) -> Option<FrameEncoder> {
-    let (result, state) = resume?;
+    let (result, state) = match resume {
+        Ok(resumed) => resumed,
+        Err(status) => {
+            send_interrupted(output, status);
+            return None;
+        }
+    };
 
     let data = match result {
         Ok(data) => data,
         Err(err) => {
-            let _ = output.send_error(Status::Internal, err.to_string());
+            let _ = output.send_error(err);
             return None;
         }
     };
@@ -152,20 +241,46 @@ async fn send_stream(mut output: FrameEncoder, resume: MaybeResumed) -> Option<F
     }
 }
 
+struct Parts {
+    context: Context,
+    timer: Option<Sleep>,
+    decoder: FrameDecoder,
+    input: HttpBody,
+    output: HttpResponse,
+    compression: Option<Compression>,
+    call: Call,
+}
+
 impl HttpContext {
-    fn parts(self) -> Result<(Context, Option<Sleep>, HttpBody, HttpResponse), ()> {
+    fn parts(self) -> Result<Parts, ()> {
         let HttpContext {
             state,
             mut req,
             res,
+            mut call,
         } = self;
         let timeout = match req.get_timeout() {
             Err(err) => {
                 res.send_error(http::StatusCode::BAD_REQUEST, err);
+                call.status = Status::InvalidArgument;
                 return Err(());
             }
             Ok(timeout) => timeout,
         };
+        let encoding = match req.get_encoding() {
+            Err(err) => {
+                res.send_error(http::StatusCode::BAD_REQUEST, err);
+                call.status = Status::InvalidArgument;
+                return Err(());
+            }
+            Ok(encoding) => encoding,
+        };
+        let compression = req.accept_encoding().map(|encoding| Compression {
+            encoding,
+            threshold: state.compression_threshold,
+        });
+        let mut decoder = FrameDecoder::new(encoding, state.limits);
+        decoder.received = call.received.clone();
         let HttpRequest { meta, body } = req;
         let context = Context {
             state,
@@ -173,7 +288,15 @@ impl HttpContext {
             http_headers: meta.headers,
         };
         let timer = timeout.map(Timeout::duration).map(nio::sleep);
-        Ok((context, timer, body, res))
+        Ok(Parts {
+            context,
+            timer,
+            decoder,
+            input: body,
+            output: res,
+            compression,
+            call,
+        })
     }
 }
 
@@ -186,48 +309,92 @@ impl HttpRequest {
         let timeout = Timeout::from_str(input)?;
         Ok(Some(timeout))
     }
+
+    fn get_encoding(&mut self) -> Result<Option<Encoding>, &'static str> {
+        let Some(val) = self.meta.headers.remove(RPC_ENCODING) else {
+            return Ok(None);
+        };
+        let name = val.to_str().map_err(|_| "invalid ascii header")?;
+        let encoding = Encoding::from_name(name).ok_or("unsupported `rpc-encoding`")?;
+        Ok(Some(encoding))
+    }
+
+    fn accept_encoding(&mut self) -> Option<Encoding> {
+        let val = self.meta.headers.remove(RPC_ACCEPT_ENCODING)?;
+        Encoding::negotiate(val.to_str().ok()?)
+    }
 }
 
-fn timeout_or_cancellation(
+fn interrupted(
     cx: &mut task::Context,
     timer: Option<&mut Sleep>,
+    shutdown: Pin<&mut impl Future<Output = ()>>,
     output: &mut HttpWriter,
-) -> Poll<()> {
+) -> Poll<Interrupted> {
     if let Some(timer) = timer
         && timer.poll_unpin(cx).is_ready()
     {
         output.send_reset(h2::Reason::CANCEL);
-        return Poll::Ready(());
+        return Poll::Ready(None);
     }
     if output.poll_reset(cx).is_ready() {
-        return Poll::Ready(());
+        return Poll::Ready(None);
+    }
+    if shutdown.poll(cx).is_ready() {
+        return Poll::Ready(Some(Status::Unavailable));
     }
 
     Poll::Pending
 }
 
-fn encode_data(data: impl OptionalField) -> io::Result<Vec<u8>> {
-    let mut buf: Vec<u8> = Vec::new();
-    data.encode(&mut buf, 0)?;
-    buf.push(lipi::DataType::StructEnd.code());
-    Ok(buf)
+fn send_interrupted(output: FrameEncoder, status: Interrupted) {
+    if let Some(status) = status {
+        let _ = output.send_error(RpcError::new(status, "server is shutting down"));
+    }
+}
+
+/// Encodes `data` as field `0`, same as `(data,)`.
+pub(crate) fn encode_data(
+    buf: &mut BytesMut,
+    data: impl OptionalField,
+) -> Result<Message, RpcError> {
+    Ok(Message::encode(buf, &(data,))?)
 }
 
-fn send_output(output: FrameEncoder, result: Option<io::Result<Vec<u8>>>) {
-    let Some(result) = result else {
-        return;
+fn send_output(output: FrameEncoder, result: Result<Result<Message, RpcError>, Interrupted>) {
+    let result = match result {
+        Ok(result) => result,
+        Err(status) => return send_interrupted(output, status),
     };
     match result {
         Err(err) => {
-            let _ = output.send_error(Status::Internal, err.to_string());
+            let _ = output.send_error(err);
         }
-        Ok(buf) => {
-            let _ = output.end(buf);
+        Ok(msg) => {
+            let _ = output.end(msg);
         }
     }
 }
 
 impl HttpResponse {
+    /// Rejects the rpc, before the handler is called.
+    ///
+    /// Invalid requests are rejected with `400 Bad Request`,
+    /// and [RpcError] (e.g. oversized messages) with an error trailer.
+    pub(crate) fn reject(self, err: Error, mut call: Call) {
+        match err.downcast::<RpcError>() {
+            Ok(err) => {
+                if let Ok(output) = self.create_setu_stream(None, usize::MAX, call) {
+                    let _ = output.send_error(*err);
+                }
+            }
+            Err(err) => {
+                self.send_error(http::StatusCode::BAD_REQUEST, err);
+                call.status = Status::InvalidArgument;
+            }
+        }
+    }
+
     fn send_error(mut self, code: http::StatusCode, _err: impl ToString) {
         *self.status_mut() = code;
         if cfg!(debug_assertions) {
@@ -1,3 +1,7 @@
+use crate::Trailer;
+use lipi::{Decode, Encode};
+use std::io;
+
 #[repr(u8)]
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Status {
@@ -35,3 +39,142 @@ impl Status {
         self as u8
     }
 }
+
+/// Error with a [Status], that ends an rpc with an error trailer.
+///
+/// Handlers can return `Result<T, RpcError>`, the error is sent to the peer as a trailer
+/// instead of the output. It's also returned when the peer ends a stream with a non-[Ok](Status::Ok) status.
+///
+/// ```
+/// use setu::{RpcError, Status};
+///
+/// async fn divide(a: i32, b: i32) -> Result<i32, RpcError> {
+///     if b == 0 {
+///         return Err(RpcError::new(Status::InvalidArgument, "division by zero"));
+///     }
+///     Ok(a / b)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    pub status: Status,
+    pub message: Option<String>,
+    /// Structured details, encoded with lipi.
+    pub details: Option<Vec<u8>>,
+}
+
+impl RpcError {
+    pub fn new(status: Status, message: impl Into<String>) -> Self {
+        debug_assert!(status != Status::Ok);
+        Self {
+            status,
+            message: Some(message.into()),
+            details: None,
+        }
+    }
+
+    /// Attach structured `details` to the error, fails if they can't be encoded.
+    pub fn with_details(mut self, details: &impl Encode) -> io::Result<Self> {
+        self.details = Some(details.to_bytes()?);
+        Ok(self)
+    }
+
+    /// Decode structured details, `None` if there is no details.
+    pub fn decode_details<'de, T: Decode<'de>>(&'de self) -> Option<lipi::Result<T>> {
+        let details = self.details.as_deref()?;
+        Some(T::decode(&mut &*details))
+    }
+
+    pub(crate) fn from_trailer(status: Status, bytes: &[u8]) -> Self {
+        let trailer = Trailer::decode(&mut &*bytes).unwrap_or_default();
+        Self {
+            status,
+            message: trailer.error,
+            details: trailer.details,
+        }
+    }
+}
+
+impl From<Status> for RpcError {
+    fn from(status: Status) -> Self {
+        debug_assert!(status != Status::Ok);
+        Self {
+            status,
+            message: None,
+            details: None,
+        }
+    }
+}
+
+impl From<RpcError> for Trailer {
+    fn from(err: RpcError) -> Self {
+        Trailer {
+            error: err.message,
+            details: err.details,
+        }
+    }
+}
+
+/// Encoding failures are [Internal](Status::Internal) errors.
+///
+/// The client only receives a generic message, the source is logged on the server.
+impl From<io::Error> for RpcError {
+    fn from(err: io::Error) -> Self {
+        eprintln!("rpc internal error: {err}");
+        RpcError::new(Status::Internal, "internal error")
+    }
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unexpected status: {:?}", self.status)?;
+        if let Some(message) = &self.message {
+            write!(f, " ({message})")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_error_trailer() {
+        let err = RpcError::new(Status::NotFound, "user not found")
+            .with_details(&(7u32, "id"))
+            .unwrap();
+        let bytes = Trailer::from(err.clone()).to_bytes().unwrap();
+        assert_eq!(RpcError::from_trailer(Status::NotFound, &bytes), err);
+
+        let details = err.decode_details::<(u32, String)>().unwrap().unwrap();
+        assert_eq!(details, (7, "id".into()));
+    }
+
+    #[test]
+    fn test_rpc_error_details_encode_error() {
+        struct Invalid;
+
+        impl Encode for Invalid {
+            const TY: lipi::DataType = lipi::DataType::U8;
+
+            fn encode(&self, _: &mut (impl io::Write + ?Sized)) -> io::Result<()> {
+                Err(io::Error::other("invalid details"))
+            }
+        }
+
+        let err = RpcError::new(Status::Internal, "failed")
+            .with_details(&Invalid)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "invalid details");
+    }
+
+    #[test]
+    fn test_rpc_error_from_io_error() {
+        let err = RpcError::from(io::Error::other("/srv/data/users.db: permission denied"));
+        assert_eq!(err.status, Status::Internal);
+        assert_eq!(err.message.as_deref(), Some("internal error"));
+    }
+}
@@ -4,16 +4,24 @@ use lipi::{Decode, Encode};
 pub struct Trailer {
     #[key = 1]
     pub error: Option<String>,
+    #[key = 2]
+    pub details: Option<Vec<u8>>,
 }
 
 impl From<String> for Trailer {
     fn from(error: String) -> Self {
-        Self { error: Some(error) }
+        Self {
+            error: Some(error),
+            details: None,
+        }
     }
 }
 
 impl Trailer {
     pub fn new() -> Self {
-        Self { error: None }
+        Self {
+            error: None,
+            details: None,
+        }
     }
 }
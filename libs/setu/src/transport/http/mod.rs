@@ -1,14 +1,28 @@
 mod request;
 mod response;
 mod rpc_utils;
+mod shutdown;
 
 pub use request::{HttpBody, HttpRequest};
 pub use response::{HttpResponse, HttpWriter};
 
-use crate::{Result, context::State, transport::tls};
-use std::{env, net::SocketAddr, rc::Rc, sync::Arc};
+pub(crate) use rpc_utils::SETU_CONTENT_TYPE;
+pub(crate) use shutdown::ShutdownSignal;
+
+use crate::{
+    DecodeLimits, Result,
+    compression::DEFAULT_THRESHOLD,
+    context::{Options, State},
+    frame::Limits,
+    interceptor::{Call, Interceptor, Stack},
+    transport::tls,
+};
+use futures::future::{Either, select};
+use shutdown::{Shutdown, ShutdownFuture};
+use std::{env, future::poll_fn, net::SocketAddr, pin::pin, rc::Rc, sync::Arc, time::Duration};
 
 use nio::net::{TcpConnection, TcpListener};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_rustls::{TlsAcceptor, rustls};
 
 pub trait HttpHandler: 'static + Send {
@@ -29,6 +43,7 @@ pub struct HttpContext {
     pub state: Rc<State>,
     pub req: HttpRequest,
     pub res: HttpResponse,
+    pub(crate) call: Call,
 }
 
 #[derive(Default)]
@@ -36,6 +51,12 @@ pub struct HttpServer {
     addr: Option<SocketAddr>,
     certs: Option<String>,
     private_key: Option<String>,
+    h2c: bool,
+    shutdown: Option<ShutdownFuture>,
+    grace_period: Option<Duration>,
+    compression_threshold: Option<usize>,
+    limits: Limits,
+    interceptors: Vec<Arc<dyn Interceptor>>,
 }
 
 impl HttpServer {
@@ -58,6 +79,71 @@ impl HttpServer {
         self
     }
 
+    /// Serve plaintext HTTP/2 with prior knowledge (h2c), without TLS.
+    ///
+    /// Useful behind a TLS-terminating proxy, or in local test setups.
+    pub fn h2c(mut self) -> Self {
+        self.h2c = true;
+        self
+    }
+
+    /// Gracefully shutdown the server, once `signal` is resolved.
+    ///
+    /// The server stops accepting new connections, sends `GOAWAY` on every live connection,
+    /// and waits for in-flight rpc to finish, up to the [grace period](Self::grace_period).
+    /// Remaining rpc are then cancelled with [Status::Unavailable](crate::Status::Unavailable).
+    pub fn graceful_shutdown(mut self, signal: impl Future<Output = ()> + 'static) -> Self {
+        self.shutdown = Some(Box::pin(signal));
+        self
+    }
+
+    /// Maximum time to wait for in-flight rpc on shutdown. (Default: 30 seconds)
+    pub fn grace_period(mut self, duration: Duration) -> Self {
+        self.grace_period = Some(duration);
+        self
+    }
+
+    /// Responses are compressed, only if a message is larger then `threshold` (in bytes). (Default: 1024 bytes)
+    ///
+    /// Compression is negotiated with `rpc-accept-encoding` header,
+    /// from encodings enabled by cargo features: `gzip`, `deflate` and `zstd`.
+    pub fn compression_threshold(mut self, threshold: usize) -> Self {
+        self.compression_threshold = Some(threshold);
+        self
+    }
+
+    /// Maximum length of a single message, in bytes. (Default: 16 MiB)
+    ///
+    /// Larger incoming messages are rejected with [Status::ResourceExhausted](crate::Status::ResourceExhausted),
+    /// before they are allocated. Rpc can't send a message, larger then this limit.
+    pub fn max_frame_size(mut self, size: usize) -> Self {
+        self.limits.max_frame_size = size;
+        self
+    }
+
+    /// Maximum total length of all incoming messages of an rpc, in bytes. (Default: unlimited)
+    ///
+    /// Can be overridden per rpc, with [Stream::set_max_stream_size](crate::Stream::set_max_stream_size).
+    pub fn max_stream_size(mut self, size: usize) -> Self {
+        self.limits.max_stream_size = size;
+        self
+    }
+
+    /// Resource limits of decoding incoming messages. (Default: [DecodeLimits::default])
+    ///
+    /// Messages that exceed them, such as deeply nested or huge collections,
+    /// are rejected with [Status::InvalidArgument](crate::Status::InvalidArgument).
+    pub fn decode_limits(mut self, limits: DecodeLimits) -> Self {
+        self.limits.decode = limits;
+        self
+    }
+
+    /// Push an interceptor, that runs on every rpc, before application interceptors.
+    pub fn interceptor(mut self, interceptor: impl Interceptor) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
     pub async fn run(self, h: impl HttpHandler + Clone) -> Result<()> {
         let addr = self.addr.unwrap_or_else(|| {
             env::var("SERVER_ADDR")
@@ -66,6 +152,20 @@ impl HttpServer {
                 .unwrap_or(SocketAddr::from(([0, 0, 0, 0], 0)))
         });
 
+        let shutdown = self
+            .shutdown
+            .unwrap_or_else(|| Box::pin(std::future::pending()));
+        let grace_period = self.grace_period.unwrap_or(Duration::from_secs(30));
+        let options = Options {
+            compression_threshold: self.compression_threshold.unwrap_or(DEFAULT_THRESHOLD),
+            limits: self.limits,
+            interceptors: Stack::from(self.interceptors),
+        };
+
+        if self.h2c {
+            return HttpServer::_run(addr, None, h, shutdown, grace_period, options).await;
+        }
+
         let certs = self
             .certs
             .unwrap_or_else(|| env::var("TLS_CERTS").expect(""));
@@ -83,45 +183,95 @@ impl HttpServer {
 
         let tls = TlsAcceptor::from(Arc::new(tls_config));
 
-        HttpServer::_run(addr, tls, h).await
+        HttpServer::_run(addr, Some(tls), h, shutdown, grace_period, options).await
     }
 
-    async fn _run(addr: SocketAddr, tls: TlsAcceptor, h: impl HttpHandler + Clone) -> Result<()> {
+    async fn _run(
+        addr: SocketAddr,
+        tls: Option<TlsAcceptor>,
+        h: impl HttpHandler + Clone,
+        mut signal: ShutdownFuture,
+        grace_period: Duration,
+        options: Options,
+    ) -> Result<()> {
         let mut listener = TcpListener::bind(addr).await?;
 
-        println!("Runing HTTP server: https://{}", listener.local_addr()?);
+        let scheme = if tls.is_some() { "https" } else { "http" };
+        println!("Runing HTTP server: {scheme}://{}", listener.local_addr()?);
 
+        let shutdown = Shutdown::new();
         loop {
-            let Ok(tcp) = listener.accept().await else {
-                continue;
+            let tcp = match select(pin!(listener.accept()), signal.as_mut()).await {
+                Either::Left((Ok(tcp), _)) => tcp,
+                Either::Left((Err(_), _)) => continue,
+                Either::Right(_) => break,
             };
 
             let tls = tls.clone();
             let h = h.clone();
+            let signal = shutdown.signal();
+            let options = options.clone();
 
             nio::spawn_pinned(|| async move {
-                if let Err(_err) = HttpServer::serve(tls, tcp, h).await {
+                if let Err(_err) = HttpServer::serve(tls, tcp, h, signal, options).await {
                     // println!("http-error: {_err:?}");
                 }
             });
         }
+        drop(listener);
+
+        shutdown.drain();
+        if let Either::Right(_) =
+            select(pin!(shutdown.idle()), pin!(nio::sleep(grace_period))).await
+        {
+            shutdown.cancel();
+            shutdown.idle().await;
+        }
+        Ok(())
     }
 
-    async fn serve(tls: TlsAcceptor, tcp: TcpConnection, h: impl HttpHandler) -> Result<()> {
+    async fn serve(
+        tls: Option<TlsAcceptor>,
+        tcp: TcpConnection,
+        h: impl HttpHandler,
+        signal: ShutdownSignal,
+        options: Options,
+    ) -> Result<()> {
         let addr = tcp.peer_addr()?;
-        let conn = tls.accept(tcp.connect().await?).await?;
-        let mut conn = h2::server::handshake(conn).await?;
+        let tcp = tcp.connect().await?;
+        let state = State::with_options(addr, signal, options);
+        match tls {
+            Some(tls) => HttpServer::serve_h2(tls.accept(tcp).await?, h, state).await,
+            None => HttpServer::serve_h2(tcp, h, state).await,
+        }
+    }
+
+    async fn serve_h2<IO>(io: IO, h: impl HttpHandler, session: Rc<State>) -> Result<()>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        let mut conn = h2::server::handshake(io).await?;
 
-        println!("H2 connection: {addr}");
+        println!("H2 connection: {}", session.addr);
 
-        let session = State::new(addr);
+        let mut draining = pin!(session.shutdown.clone().draining());
+        let mut is_draining = false;
 
-        while let Some(stream) = conn.accept().await {
+        while let Some(stream) = poll_fn(|cx| {
+            if !is_draining && draining.as_mut().poll(cx).is_ready() {
+                is_draining = true;
+                conn.graceful_shutdown();
+            }
+            conn.poll_accept(cx)
+        })
+        .await
+        {
             let (req, res) = stream?;
             h.handler(HttpContext {
                 state: session.clone(),
                 req: HttpRequest::from(req),
                 res: HttpResponse::from(res),
+                call: Call::new(),
             });
         }
         Ok(())
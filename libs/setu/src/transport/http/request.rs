@@ -27,6 +27,12 @@ pub struct HttpBody {
     reader: RecvStream,
 }
 
+impl From<RecvStream> for HttpBody {
+    fn from(reader: RecvStream) -> Self {
+        Self { reader }
+    }
+}
+
 impl HttpBody {
     #[inline]
     /// Retrieve the next chunk of data from the request body.
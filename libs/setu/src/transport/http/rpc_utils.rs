@@ -2,7 +2,7 @@ use super::*;
 use http::HeaderValue;
 use http::{Method, header::CONTENT_TYPE};
 
-const SETU_CONTENT_TYPE: &str = "application/setu";
+pub(crate) const SETU_CONTENT_TYPE: &str = "application/setu";
 
 impl HttpRequest {
     fn is_rpc_call(&self) -> bool {
@@ -0,0 +1,99 @@
+use std::{future::pending, pin::Pin};
+use tokio::sync::watch;
+
+pub(crate) type ShutdownFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum Phase {
+    Running,
+    /// Stop accepting new connections and streams, let in-flight rpc finish.
+    Draining,
+    /// Grace period is over, in-flight rpc are cancelled.
+    Cancelled,
+}
+
+/// Owned by the server, drives the shutdown of every live connection.
+pub(crate) struct Shutdown {
+    tx: watch::Sender<Phase>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self {
+            tx: watch::Sender::new(Phase::Running),
+        }
+    }
+
+    pub fn signal(&self) -> ShutdownSignal {
+        ShutdownSignal(self.tx.subscribe())
+    }
+
+    pub fn drain(&self) {
+        self.tx.send_replace(Phase::Draining);
+    }
+
+    pub fn cancel(&self) {
+        self.tx.send_replace(Phase::Cancelled);
+    }
+
+    /// Resolves once every [ShutdownSignal] is dropped,
+    /// That is, when all connections and their rpc are finished.
+    pub async fn idle(&self) {
+        self.tx.closed().await
+    }
+}
+
+/// Shared by a connection and its rpc.
+#[derive(Debug, Clone)]
+pub(crate) struct ShutdownSignal(watch::Receiver<Phase>);
+
+impl Default for ShutdownSignal {
+    /// A signal that never fires.
+    fn default() -> Self {
+        Self(watch::channel(Phase::Running).1)
+    }
+}
+
+impl ShutdownSignal {
+    pub async fn draining(self) {
+        self.wait(Phase::Draining).await
+    }
+
+    pub async fn cancelled(self) {
+        self.wait(Phase::Cancelled).await
+    }
+
+    async fn wait(mut self, phase: Phase) {
+        if self.0.wait_for(|p| *p >= phase).await.is_err() {
+            pending().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    #[test]
+    fn test_shutdown_phases() {
+        let shutdown = Shutdown::new();
+
+        let mut draining = Box::pin(shutdown.signal().draining());
+        let mut cancelled = Box::pin(shutdown.signal().cancelled());
+        let mut never = Box::pin(ShutdownSignal::default().cancelled());
+
+        assert!(draining.as_mut().now_or_never().is_none());
+        assert!(cancelled.as_mut().now_or_never().is_none());
+
+        shutdown.drain();
+        assert!(draining.as_mut().now_or_never().is_some());
+        assert!(cancelled.as_mut().now_or_never().is_none());
+
+        shutdown.cancel();
+        assert!(cancelled.as_mut().now_or_never().is_some());
+        assert!(never.as_mut().now_or_never().is_none());
+
+        assert!(Box::pin(shutdown.idle()).now_or_never().is_some());
+    }
+}
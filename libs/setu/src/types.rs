@@ -1,11 +1,12 @@
 use async_gen::AsyncGenerator;
 use lipi::encoder::OptionalField;
 use setu_type_info::{
-    FnOutputTy, FnOutputType, GeneratorType,
-    type_id::{TypeId, TypeRegistry},
+    ErrorType, FnOutputTy, FnOutputType, GeneratorType,
+    type_id::{OtherType, Type, TypeId, TypeRegistry},
 };
+use std::sync::Arc;
 
-use crate::SSE;
+use crate::{RpcError, SSE};
 
 impl<S> FnOutputType for SSE<S>
 where
@@ -20,3 +21,9 @@ where
         })
     }
 }
+
+impl TypeId for RpcError {
+    fn ty(_: &mut TypeRegistry) -> Type {
+        Type::Other(OtherType(Arc::new(ErrorType)))
+    }
+}
@@ -7,7 +7,7 @@ pub use quote2::proc_macro2;
 pub use syn;
 
 use proc_macro2::TokenStream;
-use quote2::{Quote, QuoteFn, quote};
+use quote2::{Quote, QuoteFn, ToTokens, quote};
 use syn::*;
 
 pub fn expand(
@@ -26,7 +26,7 @@ pub fn expand(
 
     let fields = quote(|t| match data {
         Data::Struct(DataStruct { fields, .. }) => {
-            if write_fields(t, fields, key_attr).is_none() {
+            if write_fields(t, fields, Some(key_attr)).is_none() {
                 panic!("`{ident}` struct needs at most one field")
             }
         }
@@ -47,7 +47,7 @@ pub fn expand(
                     let discriminant = get_discriminant(discriminant, enum_repr);
 
                     let field_ty = quote(|t| {
-                        if write_fields(t, fields, key_attr).is_none() {
+                        if write_fields(t, fields, variant_key_attr(fields, key_attr)).is_none() {
                             quote!(t, { Unit });
                         }
                     });
@@ -74,6 +74,18 @@ pub fn expand(
     let fmt_args = format!("{{}}::{ident}");
     let attrs = get_attrs(attrs);
 
+    let struct_fields = quote(|t| {
+        if let Data::Struct(DataStruct {
+            fields: Fields::Named(_),
+            ..
+        }) = data
+        {
+            quote!(t, {
+                impl #impl_generics __crate::StructFields for #ident #ty_generics #where_clause {}
+            });
+        }
+    });
+
     quote!(output, {
         const _: () = {
             use #crate_path as __crate;
@@ -88,6 +100,7 @@ pub fn expand(
                     )
                 }
             }
+            #struct_fields
         };
     });
 }
@@ -100,53 +113,108 @@ fn enum_repr(attrs: &[Attribute]) -> Option<&TokenStream> {
 }
 
 pub fn is_numeric(attrs: &[Attribute]) -> bool {
-    attrs
-        .iter()
-        .any(|attr| matches!(&attr.meta, Meta::Path(path) if path.is_ident("numeric")))
+    utils::has_attr(attrs, "numeric")
 }
 
-fn write_fields(t: &mut TokenStream, fields: &Fields, key_attr: &str) -> Option<()> {
+/// Fields of a tuple variant are keyed by their position.
+fn variant_key_attr<'a>(fields: &Fields, key_attr: &'a str) -> Option<&'a str> {
     match fields {
-        Fields::Named(FieldsNamed { named, .. }) => {
-            let fields = quote(|t| {
-                for Field {
-                    attrs, ident, ty, ..
-                } in named
-                {
-                    if let Some(key) = utils::get_attr(attrs, key_attr) {
-                        let attrs = get_attrs(attrs);
-                        let field_name = ident.as_ref().map(Ident::to_string);
-                        quote!(t, {
-                            (
-                                #attrs,
-                                __crate::StructField {
-                                    key: #key,
-                                    name: __crate::Ident::from(#field_name),
-                                    ty: <#ty as __crate::TypeId>::ty(__r)
-                                }
-                            ),
-                        });
-                    }
+        Fields::Unnamed(_) => None,
+        _ => Some(key_attr),
+    }
+}
+
+/// Fields are keyed by `key_attr` (fields without it are skipped), or by their position if `None`.
+fn write_fields(t: &mut TokenStream, fields: &Fields, key_attr: Option<&str>) -> Option<()> {
+    let ctor = match fields {
+        Fields::Named(_) => "as_struct",
+        Fields::Unnamed(_) => "as_tuple",
+        Fields::Unit => return None,
+    };
+    let ctor = Ident::new(ctor, proc_macro2::Span::call_site());
+
+    let items = quote(|t| {
+        for (
+            idx,
+            Field {
+                attrs, ident, ty, ..
+            },
+        ) in fields.iter().enumerate()
+        {
+            if utils::has_attr(attrs, "skip") || utils::has_attr(attrs, "flatten") {
+                continue;
+            }
+            let key = match key_attr {
+                Some(key_attr) => match utils::get_attr(attrs, key_attr) {
+                    Some(key) => key.to_token_stream(),
+                    None => continue,
+                },
+                None => {
+                    proc_macro2::TokenTree::from(proc_macro2::Literal::usize_unsuffixed(idx)).into()
                 }
+            };
+            let field_name = match utils::get_attr(attrs, "rename") {
+                Some(Expr::Lit(ExprLit {
+                    lit: Lit::Str(name),
+                    ..
+                })) => name.value(),
+                _ => match ident {
+                    Some(ident) => ident.to_string(),
+                    None => idx.to_string(),
+                },
+            };
+            let field_ty = field_ty(attrs, ty);
+            let attrs = get_attrs(attrs);
+            quote!(t, {
+                (
+                    #attrs,
+                    __crate::StructField {
+                        key: #key,
+                        name: __crate::Ident::from(#field_name),
+                        ty: #field_ty
+                    }
+                ),
             });
-            quote!(t, { as_struct(::std::vec![#fields]) });
         }
-        Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
-            let fields = quote(|t| {
-                for Field { attrs, ty, .. } in unnamed {
-                    let attrs = get_attrs(attrs);
-                    quote!(t, {
-                        (#attrs, <#ty as __crate::TypeId>::ty(__r)),
-                    });
-                }
-            });
-            quote!(t, { as_tuple(::std::vec![#fields]) });
+    });
+
+    let flatten = quote(|t| {
+        for Field { attrs, ty, .. } in fields {
+            if utils::has_attr(attrs, "flatten") {
+                quote!(t, { __fields.extend(__r.flatten::<#ty>()); });
+            }
         }
-        Fields::Unit => return None,
+    });
+
+    if fields.iter().any(|f| utils::has_attr(&f.attrs, "flatten")) {
+        quote!(t, {
+            #ctor({
+                let mut __fields = ::std::vec![#items];
+                #flatten
+                __fields
+            })
+        });
+    } else {
+        quote!(t, { #ctor(::std::vec![#items]) });
     }
     Some(())
 }
 
+/// Type of the field, as seen by the clients.
+fn field_ty<'a>(attrs: &'a [Attribute], ty: &'a Type) -> QuoteFn<impl Fn(&mut TokenStream) + 'a> {
+    quote(move |t| {
+        if let Some(path) = utils::get_path_attr(attrs, "encode_with") {
+            quote!(t, { __crate::encode_with_ty(#path, __r) });
+        } else if let Some(path) = utils::get_path_attr(attrs, "with") {
+            quote!(t, { __crate::encode_with_ty(#path::encode, __r) });
+        } else if let Some(path) = utils::get_path_attr(attrs, "decode_with") {
+            quote!(t, { __crate::decode_with_ty(#path, __r) });
+        } else {
+            quote!(t, { <#ty as __crate::TypeId>::ty(__r) });
+        }
+    })
+}
+
 fn get_discriminant(
     discriminant: &Option<(Token![=], Expr)>,
     enum_repr: Option<&TokenStream>,
@@ -178,7 +246,9 @@ fn get_attrs(attrs: &[Attribute]) -> QuoteFn<impl Fn(&mut TokenStream)> {
                 if !mem::take(&mut is_first) {
                     string.push('\n');
                 }
-                string += &data.value();
+                // Leading space of each line is stripped, same as rustdoc.
+                let line = data.value();
+                string += line.strip_prefix(' ').unwrap_or(&line);
             }
         }
 
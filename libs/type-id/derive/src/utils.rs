@@ -6,3 +6,20 @@ pub fn get_attr<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Expr> {
         _ => None,
     })
 }
+
+pub fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .any(|attr| matches!(&attr.meta, Meta::Path(path) if path.is_ident(name)))
+}
+
+/// Path of `#[name = "path"]` attribute.
+pub fn get_path_attr(attrs: &[Attribute], name: &str) -> Option<Path> {
+    match get_attr(attrs, name)? {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(path),
+            ..
+        }) => path.parse().ok(),
+        _ => None,
+    }
+}
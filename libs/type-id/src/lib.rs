@@ -14,6 +14,25 @@ pub trait TypeId {
     fn ty(_: &mut TypeRegistry) -> Type;
 }
 
+/// Struct with named fields, that can be inlined by `#[flatten]`.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`#[flatten]` field must be a struct with named fields, found `{Self}`"
+)]
+pub trait StructFields: TypeId {}
+
+/// Type of a `#[encode_with]` field, as returned by the function.
+#[doc(hidden)]
+pub fn encode_with_ty<T: ?Sized, U: TypeId>(_: fn(&T) -> U, r: &mut TypeRegistry) -> Type {
+    U::ty(r)
+}
+
+/// Type of a `#[decode_with]` field, as accepted by the function.
+#[doc(hidden)]
+pub fn decode_with_ty<U: TypeId, T, E>(_: fn(U) -> Result<T, E>, r: &mut TypeRegistry) -> Type {
+    U::ty(r)
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Type {
@@ -106,6 +125,8 @@ pub enum ListVariant {
     LinkedList,
     VecDeque,
     Vec,
+    /// `lipi::Columns`, list of structs encoded as a table, with a column per field.
+    Columns,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -198,7 +219,7 @@ pub enum ComplexDataType {
         fields: Vec<(Attributes, StructField)>,
     },
     Tuple {
-        fields: Vec<(Attributes, Type)>,
+        fields: Vec<(Attributes, StructField)>,
     },
 }
 
@@ -207,7 +228,7 @@ impl ComplexDataType {
         Self::Struct { fields }
     }
 
-    pub fn as_tuple(fields: Vec<(Attributes, Type)>) -> Self {
+    pub fn as_tuple(fields: Vec<(Attributes, StructField)>) -> Self {
         Self::Tuple { fields }
     }
 
@@ -229,7 +250,7 @@ pub struct EnumField {
 pub enum EnumFieldType {
     Unit,
     Struct(Vec<(Attributes, StructField)>),
-    Tuple(Vec<(Attributes, Type)>),
+    Tuple(Vec<(Attributes, StructField)>),
 }
 
 impl EnumFieldType {
@@ -237,11 +258,12 @@ impl EnumFieldType {
         Self::Struct(fields)
     }
 
-    pub fn as_tuple(fields: Vec<(Attributes, Type)>) -> Self {
+    pub fn as_tuple(fields: Vec<(Attributes, StructField)>) -> Self {
         Self::Tuple(fields)
     }
 }
 
+/// Field of a tuple is named by its position. (e.g. `0`)
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct StructField {
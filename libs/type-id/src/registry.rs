@@ -1,4 +1,4 @@
-use crate::{ComplexData, ComplexDataType, PathIdent, Type};
+use crate::{Attributes, ComplexData, ComplexDataType, PathIdent, StructField, StructFields, Type};
 
 use std::collections::{BTreeMap, btree_map::Entry};
 
@@ -36,6 +36,19 @@ impl TypeRegistry {
         }
         Type::Complex(path_ident)
     }
+
+    /// Fields of struct `T`, inlined by `#[flatten]`.
+    #[doc(hidden)]
+    pub fn flatten<T: StructFields + ?Sized>(&mut self) -> Vec<(Attributes, StructField)> {
+        let ty = T::ty(self);
+        match ty.complex().and_then(|path| self.registry.get(path)) {
+            Some(ComplexData {
+                ty: ComplexDataType::Struct { fields },
+                ..
+            }) => fields.clone(),
+            _ => panic!("`#[flatten]` field must be a struct, found: {ty:?}"),
+        }
+    }
 }
 
 impl std::ops::Deref for TypeRegistry {
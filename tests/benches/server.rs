@@ -0,0 +1,116 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use futures::channel::oneshot;
+use nio::LocalRuntime;
+use setu::{
+    Application,
+    client::Client,
+    transport::{HttpServer, http::HttpContext},
+};
+use std::{io, net::SocketAddr, ops::ControlFlow, time::Duration};
+use test_suite::TestSuite;
+
+#[rustfmt::skip]
+#[allow(dead_code)]
+#[path = "../build/rust/mod.rs"]
+mod api;
+
+/// Counts allocations of both the client and the server.
+#[path = "../../libs/lipi/benches/utils/counting_alloc.rs"]
+mod counting_alloc;
+
+struct Server {
+    shutdown: oneshot::Sender<()>,
+    handle: nio::JoinHandle<setu::Result<()>>,
+}
+
+async fn connect() -> (Server, Client) {
+    // The port can be taken by another process, before the server binds it.
+    // Then the server exits early, and it's retried with another port.
+    loop {
+        let addr: SocketAddr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let (shutdown, signal) = oneshot::channel();
+        let handle = nio::spawn_local(
+            HttpServer::new()
+                .h2c()
+                .addr(addr)
+                .graceful_shutdown(async { signal.await.unwrap() })
+                .run(|mut ctx: HttpContext| {
+                    if let Some(id) = ctx.req.get_rpc_key() {
+                        TestSuite::execute(id, ctx);
+                    }
+                }),
+        );
+
+        for _ in 0..50 {
+            nio::sleep(Duration::from_millis(20)).await;
+            if handle.is_finished() {
+                break;
+            }
+            if let Ok(client) = Client::connect(addr).await {
+                return (Server { shutdown, handle }, client);
+            }
+        }
+        if !handle.is_finished() {
+            panic!("failed to connect: {addr}");
+        }
+        let err = handle.await.unwrap().unwrap_err();
+        match err.downcast_ref::<io::Error>() {
+            Some(err) if err.kind() == io::ErrorKind::AddrInUse => continue,
+            _ => panic!("failed to run server: {addr}: {err}"),
+        }
+    }
+}
+
+async fn chat(api: &api::TestSuite, messages: usize) {
+    let (mut tx, mut rx) = api.chat("> ".into()).await.unwrap();
+    for _ in 0..messages {
+        tx.send("Hello".into()).await.unwrap();
+        assert!(matches!(rx.next().await.unwrap(), ControlFlow::Continue(_)));
+    }
+    tx.end(()).unwrap();
+    assert!(matches!(rx.next().await.unwrap(), ControlFlow::Break(_)));
+}
+
+fn run_bench<F: Future>(c: &mut Criterion, rt: &mut LocalRuntime, name: &str, f: impl Fn() -> F) {
+    const CALLS: usize = 100;
+    // Warms up the connection, before counting.
+    rt.block_on(f());
+    let start = counting_alloc::count();
+    for _ in 0..CALLS {
+        rt.block_on(f());
+    }
+    let count = (counting_alloc::count() - start) as f64 / CALLS as f64;
+    println!("{name}: {count:.1} allocations per call");
+
+    c.bench_function(name, |b| b.iter(|| rt.block_on(f())));
+}
+
+fn benchmark(c: &mut Criterion) {
+    let mut rt = nio::RuntimeBuilder::new()
+        .worker_threads(1)
+        .build()
+        .unwrap();
+    let (server, client) = rt.block_on(connect());
+    let api = api::TestSuite::new(client);
+    let data = rt.block_on(api.random_data()).unwrap();
+
+    run_bench(c, &mut rt, "add", || async {
+        assert_eq!(api.add(40, 2).await.unwrap(), 42);
+    });
+    run_bench(c, &mut rt, "echo_data", || async {
+        assert!(api.echo_data(data.clone()).await.is_ok());
+    });
+    run_bench(c, &mut rt, "chat (4 messages)", || chat(&api, 4));
+
+    drop(api);
+    server.shutdown.send(()).unwrap();
+    rt.block_on(server.handle).unwrap().unwrap();
+}
+
+criterion_group!(benches, benchmark);
+
+criterion_main!(benches);
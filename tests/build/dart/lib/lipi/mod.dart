@@ -0,0 +1,6 @@
+export 'decoder.dart';
+export 'encoder.dart';
+export 'result.dart';
+export 'type.dart';
+export 'varint.dart';
+export 'zigzag.dart';
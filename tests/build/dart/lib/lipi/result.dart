@@ -0,0 +1,32 @@
+/// `Result<T, E>` of rust, encoded as struct with field `0` as [Ok], or `1` as [Err].
+sealed class Result<T, E> {
+  const Result();
+}
+
+final class Ok<T, E> extends Result<T, E> {
+  final T value;
+  const Ok(this.value);
+
+  @override
+  bool operator ==(Object other) => other is Ok<T, E> && other.value == value;
+
+  @override
+  int get hashCode => value.hashCode;
+
+  @override
+  String toString() => 'Ok($value)';
+}
+
+final class Err<T, E> extends Result<T, E> {
+  final E error;
+  const Err(this.error);
+
+  @override
+  bool operator ==(Object other) => other is Err<T, E> && other.error == error;
+
+  @override
+  int get hashCode => error.hashCode;
+
+  @override
+  String toString() => 'Err($error)';
+}
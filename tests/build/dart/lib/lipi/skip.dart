@@ -0,0 +1,115 @@
+import '../bitset.dart';
+import '../errors.dart';
+import '../utils/common.dart';
+import 'decoder.dart';
+import 'type.dart';
+
+void skipField(Deserialize d, int id, DataType ty) {
+  try {
+    skipFieldValue(d, ty);
+  } catch (error) {
+    throw ProtocolError('failed to skip field $id of type ${ty.name}: $error');
+  }
+}
+
+void skipFieldValue(Deserialize d, DataType ty) {
+  switch (ty) {
+    case DataType.boolFalse || DataType.boolTrue:
+      break;
+
+    case DataType.u8 || DataType.i8:
+      d.buf.nextByte();
+    case DataType.f32:
+      d.buf.take(4);
+    case DataType.f64:
+      d.buf.take(8);
+    case DataType.uint || DataType.sint:
+      d.readVarint(128);
+
+    case DataType.str || DataType.unknownI || DataType.unknownII:
+      d.readBytes();
+
+    case DataType.structEnd:
+      throw const ProtocolError('unexpected StructEnd');
+    case DataType.struct:
+      skipStruct(d);
+    case DataType.union:
+      skipUnion(d);
+    case DataType.list:
+      skipList(d);
+    case DataType.table:
+      skipTable(d);
+  }
+}
+
+void skipStruct(Deserialize d) {
+  for (var header = d.nextFieldIdAndTy(); header != null; header = d.nextFieldIdAndTy()) {
+    final (id, ty) = header;
+    skipField(d, id, ty);
+  }
+}
+
+void skipUnion(Deserialize d) {
+  final (id, ty) = d.readFieldIdAndTy();
+  skipField(d, id, ty);
+}
+
+void skipList(Deserialize d) {
+  final (len, ty) = d.readLenAndTy();
+  skipListValues(d, len, ty);
+}
+
+void skipListValues(Deserialize d, int len, DataType ty) {
+  ensure(len >= 0, () => RangeError('invalid list length: $len'));
+
+  void repeat(void Function() skip) {
+    for (var i = 0; i < len; i++) {
+      skip();
+    }
+  }
+
+  switch (ty) {
+    case DataType.boolFalse:
+      throw const ProtocolError('unexpected bool packed in list');
+    // Ignore packed_bools
+    case DataType.boolTrue:
+      d.buf.take(boolPackedLen(len));
+
+    case DataType.u8 || DataType.i8:
+      d.buf.take(len);
+    case DataType.f32:
+      d.buf.take(len * 4);
+    case DataType.f64:
+      d.buf.take(len * 8);
+    case DataType.uint || DataType.sint:
+      repeat(() => d.readVarint(128));
+
+    case DataType.str || DataType.unknownI || DataType.unknownII:
+      repeat(d.readBytes);
+
+    case DataType.structEnd:
+      throw const ProtocolError('unexpected StructEnd');
+
+    case DataType.struct:
+      repeat(() => skipStruct(d));
+    case DataType.union:
+      repeat(() => skipUnion(d));
+    case DataType.list:
+      repeat(() => skipList(d));
+    case DataType.table:
+      repeat(() => skipTable(d));
+  }
+}
+
+void skipTable(Deserialize d) {
+  final cols = d.readLen();
+  final len = d.readLen();
+  for (var i = 0; i < cols; i++) {
+    final (colId, colTy) = d.readFieldIdAndTy();
+    try {
+      skipListValues(d, len, colTy);
+    } catch (error) {
+      throw ProtocolError('failed to skip column $colId of type ${colTy.name}: $error');
+    }
+  }
+}
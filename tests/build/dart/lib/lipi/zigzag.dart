@@ -0,0 +1,3 @@
+BigInt zigzagEncode(BigInt num, [int bits = 64]) => (num << 1) ^ (num >> (bits - 1));
+
+BigInt zigzagDecode(BigInt num) => (num >> 1) ^ -(num & BigInt.one);
@@ -0,0 +1,11 @@
+/// Runtime of generated setu client, requires `http2` package.
+library;
+
+export 'bitset.dart';
+export 'errors.dart';
+export 'lipi/mod.dart';
+export 'setu/mod.dart';
+export 'status.dart';
+export 'timeout.dart';
+export 'transport/http.dart';
+export 'transport/output.dart';
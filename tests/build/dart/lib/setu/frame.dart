@@ -0,0 +1,86 @@
+import 'dart:typed_data';
+
+import '../status.dart';
+import '../utils/common.dart';
+import '../utils/stream.dart';
+
+final class MaybeCompressed<T> {
+  final bool isCompressed;
+  final T data;
+  const MaybeCompressed(this.isCompressed, this.data);
+}
+
+sealed class Frame {
+  final Uint8List bytes;
+  const Frame(this.bytes);
+}
+
+final class MessageFrame extends Frame {
+  const MessageFrame(super.bytes);
+}
+
+final class TrailerFrame extends Frame {
+  final Status status;
+  const TrailerFrame(this.status, super.bytes);
+}
+
+final class FrameHeader {
+  final bool isCompressed;
+  final bool isTrailer;
+  final int lenSize;
+  final int code;
+
+  const FrameHeader(this.isCompressed, this.isTrailer, this.lenSize, this.code);
+
+  factory FrameHeader.create({required int lenSize, bool isCompressed = false, Status? status}) =>
+      FrameHeader(isCompressed, status != null, lenSize, status?.code ?? 0);
+
+  factory FrameHeader.parse(int byte) => FrameHeader(
+    byte & 0x1 == 0x1,
+    byte & 0x2 == 0x2,
+    ((byte >> 2) & 0x3) + 1,
+    byte >> 4,
+  );
+
+  int encode() =>
+      (code << 4) | ((lenSize - 1) << 2) | ((isTrailer ? 1 : 0) << 1) | (isCompressed ? 1 : 0);
+}
+
+/// Big-endian length, in the fewest (1..=4) bytes.
+Uint8List lenBE(int len) {
+  ensure(len >= 0 && len <= 0xFFFFFFFF, () => RangeError('len: $len must fit in u32'));
+
+  final size = len <= 0xFF
+      ? 1
+      : len <= 0xFFFF
+      ? 2
+      : len <= 0xFFFFFF
+      ? 3
+      : 4;
+
+  final buf = Uint8List(4)..buffer.asByteData().setUint32(0, len, Endian.big);
+  return Uint8List.sublistView(buf, 4 - size);
+}
+
+class FrameDecoder extends StreamReader {
+  FrameDecoder(super.stream);
+
+  Future<MaybeCompressed<Frame>> parseFrame() async {
+    final header = FrameHeader.parse(await readByte());
+    final len = await parseLenBigEndian(header.lenSize);
+    final bytes = await readBytes(len);
+
+    return MaybeCompressed(
+      header.isCompressed,
+      header.isTrailer ? TrailerFrame(Status.from(header.code), bytes) : MessageFrame(bytes),
+    );
+  }
+
+  Future<int> parseLenBigEndian(int size) async {
+    var len = 0;
+    for (var i = 0; i < size; i++) {
+      len = (len << 8) | await readByte();
+    }
+    return len;
+  }
+}
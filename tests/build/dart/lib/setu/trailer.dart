@@ -0,0 +1,23 @@
+import '../lipi/decoder.dart';
+import '../lipi/encoder.dart';
+import '../lipi/type.dart';
+
+final class Trailer {
+  final String? error;
+  const Trailer([this.error]);
+
+  static final $encoder = Encoder<Trailer>(
+    DataType.struct,
+    (e, z) => structEncoder(e, [Field(1, z.error, Encoders.str)]),
+  );
+
+  static final $decoder = Decoder<Trailer>(DataType.struct, (d) {
+    String? error;
+    structDecoder(d, (id, ty) {
+      if (id != 1) return false;
+      error = d.field(ty, Decoders.str);
+      return true;
+    });
+    return Trailer(error);
+  });
+}
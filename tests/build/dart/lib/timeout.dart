@@ -0,0 +1,53 @@
+import 'errors.dart';
+
+enum TimeoutUnit {
+  hour('H'),
+  minute('M'),
+  second('S'),
+  millisecond('m');
+
+  final String symbol;
+  const TimeoutUnit(this.symbol);
+}
+
+final class Timeout {
+  final TimeoutUnit unit;
+  final int value;
+
+  const Timeout.hour(this.value) : unit = TimeoutUnit.hour;
+  const Timeout.minute(this.value) : unit = TimeoutUnit.minute;
+  const Timeout.second(this.value) : unit = TimeoutUnit.second;
+  const Timeout.millisecond(this.value) : unit = TimeoutUnit.millisecond;
+
+  Duration get duration => switch (unit) {
+    TimeoutUnit.hour => Duration(hours: value),
+    TimeoutUnit.minute => Duration(minutes: value),
+    TimeoutUnit.second => Duration(seconds: value),
+    TimeoutUnit.millisecond => Duration(milliseconds: value),
+  };
+
+  static Timeout parse(String input) {
+    if (input.length < 2) throw const FormatException('timeout: invalid format');
+
+    final value = int.tryParse(input.substring(0, input.length - 1));
+    if (value == null) throw const FormatException('timeout: invalid number');
+
+    return switch (input[input.length - 1]) {
+      'H' => Timeout.hour(value),
+      'M' => Timeout.minute(value),
+      'S' => Timeout.second(value),
+      'm' => Timeout.millisecond(value),
+      _ => throw const ProtocolError('timeout: unknown unit'),
+    };
+  }
+
+  @override
+  String toString() => '$value${unit.symbol}';
+
+  @override
+  bool operator ==(Object other) =>
+      other is Timeout && unit == other.unit && value == other.value;
+
+  @override
+  int get hashCode => Object.hash(unit, value);
+}
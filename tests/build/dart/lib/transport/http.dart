@@ -0,0 +1,207 @@
+import 'dart:async';
+import 'dart:convert' show ascii;
+import 'dart:io';
+
+import 'package:http2/transport.dart';
+
+import '../errors.dart';
+import '../lipi/decoder.dart';
+import '../lipi/encoder.dart';
+import '../setu/frame_writer.dart';
+import '../timeout.dart';
+import 'output.dart';
+
+const setuContentType = 'application/setu';
+
+final class AbortController {
+  final _aborted = Completer<Object?>();
+
+  void abort([Object? reason]) {
+    if (!_aborted.isCompleted) _aborted.complete(reason);
+  }
+
+  bool get isAborted => _aborted.isCompleted;
+
+  Future<Object?> get signal => _aborted.future;
+}
+
+/// Options of a single call.
+final class Context {
+  /// `null` disables the timeout.
+  final Timeout? timeout;
+  final AbortController? abort;
+
+  const Context({this.timeout = Client.defaultTimeout, this.abort});
+}
+
+/// A handle to a HTTP/2 connection.
+final class Client {
+  static const defaultTimeout = Timeout.minute(2);
+
+  final ClientTransportConnection _conn;
+  final String _scheme;
+  final String _authority;
+
+  Client._(this._conn, this._scheme, this._authority);
+
+  /// Connect over plaintext HTTP/2 with prior knowledge (h2c).
+  static Future<Client> connect(String host, int port) async {
+    final socket = await Socket.connect(host, port);
+    return Client._(ClientTransportConnection.viaSocket(socket), 'http', '$host:$port');
+  }
+
+  /// Connect over TLS, ALPN protocol is set to `h2`.
+  static Future<Client> connectTls(String host, int port, {SecurityContext? context}) async {
+    final socket = await SecureSocket.connect(
+      host,
+      port,
+      context: context,
+      supportedProtocols: ['h2'],
+    );
+    if (socket.selectedProtocol != 'h2') {
+      socket.destroy();
+      throw ProtocolError('unexpected ALPN protocol: ${socket.selectedProtocol}');
+    }
+    return Client._(ClientTransportConnection.viaSocket(socket), 'https', '$host:$port');
+  }
+
+  /// Gracefully closes the connection, after all active calls are finished.
+  Future<void> close() => _conn.finish();
+
+  /// Unary call.
+  Future<T> rpc<T>(
+    int id,
+    Context ctx,
+    void Function(Encode e) input,
+    T Function(Decode d) decoder,
+  ) {
+    final stream = _request(id, ctx);
+    stream.sendData(encodeLastFrame(input), endStream: true);
+    return output(_response(stream, ctx), decoder);
+  }
+
+  /// Server stream (`SSE`) call.
+  Sse<T, R> sse<T, R>(
+    int id,
+    Context ctx,
+    void Function(Encode e) input,
+    T Function(Decode d) yielder,
+    R Function(Decode d) decoder,
+  ) {
+    final stream = _request(id, ctx);
+    stream.sendData(encodeLastFrame(input), endStream: true);
+    return Sse(_response(stream, ctx), yielder, decoder);
+  }
+
+  /// Client stream call, `input` is sent as the first message.
+  Uni<T, R, O> uni<T, R, O>(
+    int id,
+    Context ctx,
+    void Function(Encode e) input,
+    void Function(Encode e, T value) send,
+    void Function(Encode e, R value) last,
+    O Function(Decode d) decoder,
+  ) {
+    final stream = _request(id, ctx);
+    stream.sendData(encodeFrame(input));
+    return Uni(stream, send, last, output(_response(stream, ctx), decoder));
+  }
+
+  /// Bidirectional stream call, `input` is sent as the first message.
+  Bidi<T, R, Y, Z> bidi<T, R, Y, Z>(
+    int id,
+    Context ctx,
+    void Function(Encode e) input,
+    void Function(Encode e, T value) send,
+    void Function(Encode e, R value) last,
+    Y Function(Decode d) yielder,
+    Z Function(Decode d) decoder,
+  ) {
+    final stream = _request(id, ctx);
+    stream.sendData(encodeFrame(input));
+    return Bidi(stream, send, last, Sse(_response(stream, ctx), yielder, decoder));
+  }
+
+  ClientTransportStream _request(int id, Context ctx) {
+    final stream = _conn.makeRequest([
+      Header.ascii(':method', 'POST'),
+      Header.ascii(':scheme', _scheme),
+      Header.ascii(':path', '/'),
+      Header.ascii(':authority', _authority),
+      Header.ascii('content-type', setuContentType),
+      Header.ascii('rpc-id', '$id'),
+      if (ctx.timeout case final timeout?) Header.ascii('rpc-timeout', '$timeout'),
+    ]);
+    ctx.abort?.signal.then((_) => stream.terminate());
+    return stream;
+  }
+
+  /// Resolves to the response body, once response headers are received.
+  Future<Stream<List<int>>> _response(ClientTransportStream stream, Context ctx) {
+    final response = Completer<Stream<List<int>>>();
+    final body = StreamController<List<int>>();
+
+    final timer = switch (ctx.timeout) {
+      final timeout? => Timer(timeout.duration, stream.terminate),
+      null => null,
+    };
+
+    void fail(Object error, [StackTrace? st]) {
+      timer?.cancel();
+      if (response.isCompleted) return body.addError(error, st);
+      response.completeError(error, st);
+      body.close();
+    }
+
+    final sub = stream.incomingMessages.listen(
+      (msg) {
+        switch (msg) {
+          case HeadersStreamMessage(:final headers) when !response.isCompleted:
+            timer?.cancel();
+            try {
+              _checkHeaders(headers);
+              response.complete(body.stream);
+            } catch (error, st) {
+              stream.terminate();
+              fail(error, st);
+            }
+          case DataStreamMessage(:final bytes):
+            body.add(bytes);
+          default: // Trailers
+        }
+      },
+      onError: fail,
+      onDone: () {
+        if (!response.isCompleted) {
+          return fail(const ProtocolError('stream closed before response headers'));
+        }
+        body.close();
+      },
+    );
+
+    body.onCancel = () {
+      stream.terminate();
+      return sub.cancel();
+    };
+    return response.future;
+  }
+}
+
+void _checkHeaders(List<Header> headers) {
+  String? status;
+  String? contentType;
+  for (final header in headers) {
+    switch (ascii.decode(header.name)) {
+      case ':status':
+        status = ascii.decode(header.value);
+      case 'content-type':
+        contentType = ascii.decode(header.value);
+    }
+  }
+  if (status != '200') {
+    throw ProtocolError('unexpected http status: $status');
+  }
+  if (contentType != setuContentType) {
+    throw ProtocolError('unexpected content-type: ${contentType ?? 'none'}');
+  }
+}
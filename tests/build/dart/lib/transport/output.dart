@@ -0,0 +1,122 @@
+import 'dart:async';
+import 'dart:typed_data';
+
+import 'package:http2/transport.dart';
+
+import '../errors.dart';
+import '../lipi/decoder.dart';
+import '../lipi/encoder.dart';
+import '../setu/frame.dart';
+import '../setu/frame_writer.dart';
+import '../setu/trailer.dart';
+import '../status.dart';
+import '../utils/bytes.dart';
+
+Future<T> output<T>(Future<Stream<List<int>>> body, T Function(Decode d) decoder) async {
+  final reader = FrameDecoder(await body);
+  try {
+    switch ((await reader.parseFrame()).data) {
+      case TrailerFrame(:final status, :final bytes):
+        return decoder(_trailer(status, bytes));
+      case MessageFrame():
+        throw const ProtocolError('expected trailer');
+    }
+  } finally {
+    await reader.cancel();
+  }
+}
+
+/// Server stream (`SSE`), cancelling the subscription aborts the call.
+final class Sse<T, R> extends Stream<T> {
+  final Stream<T> _items;
+  final Future<R?> _output;
+
+  Sse._(this._items, this._output);
+
+  factory Sse(
+    Future<Stream<List<int>>> body,
+    T Function(Decode d) yielder,
+    R Function(Decode d) output,
+  ) {
+    final fut = Completer<R?>();
+
+    Stream<T> items() async* {
+      final reader = FrameDecoder(await body);
+      try {
+        while (true) {
+          switch ((await reader.parseFrame()).data) {
+            case TrailerFrame(:final status, :final bytes):
+              fut.complete(output(_trailer(status, bytes)));
+              return;
+            case MessageFrame(:final bytes):
+              yield yielder(Decode(Bytes(bytes)));
+          }
+        }
+      } finally {
+        if (!fut.isCompleted) fut.complete(null);
+        await reader.cancel();
+      }
+    }
+
+    return Sse._(items(), fut.future);
+  }
+
+  /// Final value of the stream, `null` if the stream was not completed.
+  Future<R?> output() => _output;
+
+  @override
+  StreamSubscription<T> listen(
+    void Function(T event)? onData, {
+    Function? onError,
+    void Function()? onDone,
+    bool? cancelOnError,
+  }) => _items.listen(onData, onError: onError, onDone: onDone, cancelOnError: cancelOnError);
+}
+
+/// Sending half of a client stream.
+base class Sender<T, R> {
+  final ClientTransportStream _stream;
+  final void Function(Encode e, T value) _send;
+  final void Function(Encode e, R value) _final;
+
+  Sender(this._stream, this._send, this._final);
+
+  void send(T value) => _stream.sendData(encodeFrame((e) => _send(e, value)));
+
+  void sendFinal(R value) {
+    _stream.sendData(encodeLastFrame((e) => _final(e, value)), endStream: true);
+  }
+
+  void sendError(Status status, [String? reason]) {
+    _stream.sendData(encodeErrorFrame(status, reason), endStream: true);
+  }
+
+  void cancel() => _stream.terminate();
+}
+
+/// Client stream, `args` are sent as the first message.
+final class Uni<T, R, O> extends Sender<T, R> {
+  final Future<O> _output;
+
+  Uni(super._stream, super._send, super._final, this._output);
+
+  Future<O> output() => _output;
+}
+
+/// Bidirectional stream, `args` are sent as the first message.
+final class Bidi<T, R, Y, Z> extends Sender<T, R> {
+  final Sse<Y, Z> _output;
+
+  Bidi(super._stream, super._send, super._final, this._output);
+
+  /// Server stream, that is received concurrently.
+  Sse<Y, Z> output() => _output;
+}
+
+Decode _trailer(Status status, Uint8List bytes) {
+  final de = Decode(Bytes(bytes));
+  if (status != Status.ok) {
+    throw StatusError(status, Trailer.$decoder(de).error);
+  }
+  return de;
+}
@@ -0,0 +1,27 @@
+import 'dart:typed_data';
+
+import 'common.dart';
+
+final class Bytes {
+  Uint8List _data;
+
+  Bytes(this._data);
+  Bytes.empty() : _data = Uint8List(0);
+
+  int get length => _data.length;
+  bool get isEmpty => _data.isEmpty;
+
+  int nextByte() => take(1)[0];
+
+  Uint8List take(int len) {
+    ensure(
+      len <= _data.length,
+      () => RangeError('take($len) exceeds buffer length ${_data.length}'),
+    );
+    final bytes = Uint8List.sublistView(_data, 0, len);
+    _data = Uint8List.sublistView(_data, len);
+    return bytes;
+  }
+
+  Uint8List remaining() => _data;
+}
@@ -0,0 +1,31 @@
+void ensure(bool expr, Object Function() err) {
+  if (!expr) throw err();
+}
+
+int checkOverflowInt(int num, int bit) {
+  final max = (1 << (bit - 1)) - 1;
+  final min = -(1 << (bit - 1));
+
+  ensure(num >= min && num <= max, () => RangeError('Int$bit overflow: $num'));
+  return num;
+}
+
+int checkOverflowUint(int num, int bit) {
+  final max = (1 << bit) - 1;
+  ensure(num >= 0 && num <= max, () => RangeError('Uint$bit overflow: $num'));
+  return num;
+}
+
+BigInt checkOverflowBigInt(BigInt num, int bit) {
+  final max = (BigInt.one << (bit - 1)) - BigInt.one;
+  final min = -(BigInt.one << (bit - 1));
+
+  ensure(num >= min && num <= max, () => RangeError('Int$bit overflow: $num'));
+  return num;
+}
+
+BigInt checkOverflowBigUint(BigInt num, int bit) {
+  final max = (BigInt.one << bit) - BigInt.one;
+  ensure(!num.isNegative && num <= max, () => RangeError('Uint$bit overflow: $num'));
+  return num;
+}
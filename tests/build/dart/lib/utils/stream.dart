@@ -0,0 +1,43 @@
+import 'dart:async';
+import 'dart:typed_data';
+
+import '../errors.dart';
+import 'buffer.dart';
+import 'bytes.dart';
+
+class StreamReader {
+  Bytes _data = Bytes.empty();
+  final StreamIterator<List<int>> _chunks;
+
+  StreamReader(Stream<List<int>> stream) : _chunks = StreamIterator(stream);
+
+  Future<Uint8List> readBytes(int len) async {
+    if (len == 0) return Uint8List(0);
+
+    final data = await _read();
+    if (len <= data.length) return data.take(len);
+
+    final buf = Buffer();
+    while (buf.len < len) {
+      final data = await _read();
+      final remaining = len - buf.len;
+      buf.push(data.take(remaining < data.length ? remaining : data.length));
+    }
+    return buf.data();
+  }
+
+  Future<int> readByte() async => (await _read()).nextByte();
+
+  Future<void> cancel() => _chunks.cancel();
+
+  Future<Bytes> _read() async {
+    while (_data.isEmpty) {
+      if (!await _chunks.moveNext()) {
+        throw const EndOfData('unexpected end of message');
+      }
+      final chunk = _chunks.current;
+      _data = Bytes(chunk is Uint8List ? chunk : Uint8List.fromList(chunk));
+    }
+    return _data;
+  }
+}
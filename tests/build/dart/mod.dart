@@ -0,0 +1,733 @@
+// AUTO-GENERATED FILE. DO NOT EDIT.
+// ignore_for_file: type=lint, unused_import
+import 'dart:typed_data';
+
+import 'lib/mod.dart' as $;
+export 'lib/mod.dart' show AbortController, Client, Context, Err, Ok, Result, Status, StatusError, Timeout;
+
+final class Note {
+	final String text;
+	final String author;
+	final BigInt edited_at;
+
+	const Note({
+		required this.text,
+		required this.author,
+		required this.edited_at,
+	});
+
+	static final $encoder = $.Encoder<Note>($.DataType.struct, (e, z) {
+		$.structEncoder(e, [
+			$.Field(0, z.text, $.Encoders.str),
+			$.Field(10, z.author, $.Encoders.str),
+			$.Field(11, z.edited_at, $.Encoders.u64),
+		]);
+	});
+
+	static final $decoder = $.Decoder<Note>($.DataType.struct, (d) {
+		String? $text;
+		String? $author;
+		BigInt? $edited_at;
+		$.structDecoder(d, (id, ty) {
+			switch (id) {
+				case 0: $text = d.field(ty, $.Decoders.str);
+				case 10: $author = d.field(ty, $.Decoders.str);
+				case 11: $edited_at = d.field(ty, $.Decoders.u64);
+				default: return false;
+			}
+			return true;
+		});
+		return Note(
+			text: $.requiredField($text, 'text', 0),
+			author: $.requiredField($author, 'author', 10),
+			edited_at: $.requiredField($edited_at, 'edited_at', 11),
+		);
+	});
+}
+
+sealed class ParseError {
+	const ParseError();
+
+	static final $decoder = $.Decoder<ParseError>($.DataType.union, (d) {
+		final (id, ty) = d.readFieldIdAndTy();
+		return switch (id) {
+			0 => $.unitVariant(d, id, ty, const ParseErrorEmpty()),
+			1 => ParseErrorInvalid(d.field(ty, $.Decoders.str)),
+			_ => throw $.ProtocolError('unknown variant: $id'),
+		};
+	});
+}
+
+final class ParseErrorEmpty extends ParseError {
+	const ParseErrorEmpty();
+}
+
+final class ParseErrorInvalid extends ParseError {
+	final String value;
+	const ParseErrorInvalid(this.value);
+}
+
+final class Point {
+	final int $0;
+	final int $1;
+
+	const Point(this.$0, this.$1, );
+
+	static final $encoder = $.Encoder<Point>($.DataType.struct, (e, z) {
+		$.structEncoder(e, [
+			$.Field(0, z.$0, $.Encoders.i32),
+			$.Field(1, z.$1, $.Encoders.i32),
+		]);
+	});
+
+	static final $decoder = $.Decoder<Point>($.DataType.struct, (d) {
+		final z = $.tupleDecoder(d, [(0, $.Decoders.i32), (1, $.Decoders.i32), ]);
+		return Point(
+			$.requiredField(z[0] as int?, '0', 0),
+			$.requiredField(z[1] as int?, '1', 1),
+		);
+	});
+}
+
+final class Revision {
+	final String author;
+	final BigInt edited_at;
+
+	const Revision({
+		required this.author,
+		required this.edited_at,
+	});
+}
+
+final class Score {
+	final String player;
+	final int points;
+	final bool online;
+	final String? team;
+
+	const Score({
+		required this.player,
+		required this.points,
+		required this.online,
+		this.team,
+	});
+
+	static final $encoder = $.Encoder<Score>($.DataType.struct, (e, z) {
+		$.structEncoder(e, [
+			$.Field(0, z.player, $.Encoders.str),
+			$.Field(1, z.points, $.Encoders.u32),
+			$.Field(2, z.online, $.Encoders.boolean),
+			$.Field(3, z.team, $.Encoders.str),
+		]);
+	});
+
+	static final $decoder = $.Decoder<Score>($.DataType.struct, (d) {
+		String? $player;
+		int? $points;
+		bool? $online;
+		String? $team;
+		$.structDecoder(d, (id, ty) {
+			switch (id) {
+				case 0: $player = d.field(ty, $.Decoders.str);
+				case 1: $points = d.field(ty, $.Decoders.u32);
+				case 2: $online = d.field(ty, $.Decoders.boolean);
+				case 3: $team = d.field(ty, $.Decoders.str);
+				default: return false;
+			}
+			return true;
+		});
+		return Score(
+			player: $.requiredField($player, 'player', 0),
+			points: $.requiredField($points, 'points', 1),
+			online: $.requiredField($online, 'online', 2),
+			team: $team,
+		);
+	});
+}
+
+sealed class Shape {
+	const Shape();
+
+	static final $encoder = $.Encoder<Shape>($.DataType.union, (e, z) {
+		switch (z) {
+			case ShapeDot(): e.field(0, false, $.Encoders.boolean);
+			case final ShapeCircle variant: e.field(1, variant, ShapeCircle.$encoder);
+			case final ShapeLine variant: e.field(2, variant, ShapeLine.$encoder);
+		}
+	});
+
+	static final $decoder = $.Decoder<Shape>($.DataType.union, (d) {
+		final (id, ty) = d.readFieldIdAndTy();
+		return switch (id) {
+			0 => $.unitVariant(d, id, ty, const ShapeDot()),
+			1 => d.field(ty, ShapeCircle.$decoder),
+			2 => d.field(ty, ShapeLine.$decoder),
+			_ => throw $.ProtocolError('unknown variant: $id'),
+		};
+	});
+}
+
+final class ShapeDot extends Shape {
+	const ShapeDot();
+}
+
+final class ShapeCircle extends Shape {
+	final Point center;
+	final double radius;
+	/// Fill color, as `0xRRGGBB`.
+	final int? fill;
+
+	const ShapeCircle({
+		required this.center,
+		required this.radius,
+		this.fill,
+	});
+
+	static final $encoder = $.Encoder<ShapeCircle>($.DataType.struct, (e, z) {
+		$.structEncoder(e, [
+			$.Field(0, z.center, Point.$encoder),
+			$.Field(1, z.radius, $.Encoders.f64),
+			$.Field(2, z.fill, $.Encoders.u32),
+		]);
+	});
+
+	static final $decoder = $.Decoder<ShapeCircle>($.DataType.struct, (d) {
+		Point? $center;
+		double? $radius;
+		int? $fill;
+		$.structDecoder(d, (id, ty) {
+			switch (id) {
+				case 0: $center = d.field(ty, Point.$decoder);
+				case 1: $radius = d.field(ty, $.Decoders.f64);
+				case 2: $fill = d.field(ty, $.Decoders.u32);
+				default: return false;
+			}
+			return true;
+		});
+		return ShapeCircle(
+			center: $.requiredField($center, 'center', 0),
+			radius: $.requiredField($radius, 'radius', 1),
+			fill: $fill,
+		);
+	});
+}
+
+final class ShapeLine extends Shape {
+	final Point $0;
+	final Point $1;
+
+	const ShapeLine(this.$0, this.$1, );
+
+	static final $encoder = $.Encoder<ShapeLine>($.DataType.struct, (e, z) {
+		$.structEncoder(e, [
+			$.Field(0, z.$0, Point.$encoder),
+			$.Field(1, z.$1, Point.$encoder),
+		]);
+	});
+
+	static final $decoder = $.Decoder<ShapeLine>($.DataType.struct, (d) {
+		final z = $.tupleDecoder(d, [(0, Point.$decoder), (1, Point.$decoder), ]);
+		return ShapeLine(
+			$.requiredField(z[0] as Point?, '0', 0),
+			$.requiredField(z[1] as Point?, '1', 1),
+		);
+	});
+}
+
+final class Data {
+	final int u8;
+	final int u16;
+	final int u32;
+	final BigInt u64;
+	final int i8;
+	final int i16;
+	final int i32;
+	final BigInt i64;
+	final double f32;
+	final double f64;
+	final bool bool$;
+	final String string;
+	final Numerical numeric;
+
+	const Data({
+		required this.u8,
+		required this.u16,
+		required this.u32,
+		required this.u64,
+		required this.i8,
+		required this.i16,
+		required this.i32,
+		required this.i64,
+		required this.f32,
+		required this.f64,
+		required this.bool$,
+		required this.string,
+		required this.numeric,
+	});
+
+	static final $encoder = $.Encoder<Data>($.DataType.struct, (e, z) {
+		$.structEncoder(e, [
+			$.Field(1, z.u8, $.Encoders.u8),
+			$.Field(2, z.u16, $.Encoders.u16),
+			$.Field(3, z.u32, $.Encoders.u32),
+			$.Field(4, z.u64, $.Encoders.u64),
+			$.Field(5, z.i8, $.Encoders.i8),
+			$.Field(6, z.i16, $.Encoders.i16),
+			$.Field(7, z.i32, $.Encoders.i32),
+			$.Field(8, z.i64, $.Encoders.i64),
+			$.Field(9, z.f32, $.Encoders.f32),
+			$.Field(10, z.f64, $.Encoders.f64),
+			$.Field(11, z.bool$, $.Encoders.boolean),
+			$.Field(12, z.string, $.Encoders.str),
+			$.Field(13, z.numeric, Numerical.$encoder),
+		]);
+	});
+
+	static final $decoder = $.Decoder<Data>($.DataType.struct, (d) {
+		int? $u8;
+		int? $u16;
+		int? $u32;
+		BigInt? $u64;
+		int? $i8;
+		int? $i16;
+		int? $i32;
+		BigInt? $i64;
+		double? $f32;
+		double? $f64;
+		bool? $bool$;
+		String? $string;
+		Numerical? $numeric;
+		$.structDecoder(d, (id, ty) {
+			switch (id) {
+				case 1: $u8 = d.field(ty, $.Decoders.u8);
+				case 2: $u16 = d.field(ty, $.Decoders.u16);
+				case 3: $u32 = d.field(ty, $.Decoders.u32);
+				case 4: $u64 = d.field(ty, $.Decoders.u64);
+				case 5: $i8 = d.field(ty, $.Decoders.i8);
+				case 6: $i16 = d.field(ty, $.Decoders.i16);
+				case 7: $i32 = d.field(ty, $.Decoders.i32);
+				case 8: $i64 = d.field(ty, $.Decoders.i64);
+				case 9: $f32 = d.field(ty, $.Decoders.f32);
+				case 10: $f64 = d.field(ty, $.Decoders.f64);
+				case 11: $bool$ = d.field(ty, $.Decoders.boolean);
+				case 12: $string = d.field(ty, $.Decoders.str);
+				case 13: $numeric = d.field(ty, Numerical.$decoder);
+				default: return false;
+			}
+			return true;
+		});
+		return Data(
+			u8: $.requiredField($u8, 'u8', 1),
+			u16: $.requiredField($u16, 'u16', 2),
+			u32: $.requiredField($u32, 'u32', 3),
+			u64: $.requiredField($u64, 'u64', 4),
+			i8: $.requiredField($i8, 'i8', 5),
+			i16: $.requiredField($i16, 'i16', 6),
+			i32: $.requiredField($i32, 'i32', 7),
+			i64: $.requiredField($i64, 'i64', 8),
+			f32: $.requiredField($f32, 'f32', 9),
+			f64: $.requiredField($f64, 'f64', 10),
+			bool$: $.requiredField($bool$, 'bool', 11),
+			string: $.requiredField($string, 'string', 12),
+			numeric: $.requiredField($numeric, 'numeric', 13),
+		);
+	});
+}
+
+sealed class JsValue {
+	const JsValue();
+
+	static final $encoder = $.Encoder<JsValue>($.DataType.union, (e, z) {
+		switch (z) {
+			case JsValueNull(): e.field(0, false, $.Encoders.boolean);
+			case JsValueBool(:final value): e.field(1, value, $.Encoders.boolean);
+			case JsValueNumber(:final value): e.field(2, value, $.Encoders.f64);
+			case JsValueString(:final value): e.field(3, value, $.Encoders.str);
+			case JsValueArray(:final value): e.field(4, value, $.Encoders.list(JsValue.$encoder));
+			case JsValueObject(:final value): e.field(5, value, $.Encoders.table($.Encoders.str, JsValue.$encoder));
+		}
+	});
+
+	static final $decoder = $.Decoder<JsValue>($.DataType.union, (d) {
+		final (id, ty) = d.readFieldIdAndTy();
+		return switch (id) {
+			0 => $.unitVariant(d, id, ty, const JsValueNull()),
+			1 => JsValueBool(d.field(ty, $.Decoders.boolean)),
+			2 => JsValueNumber(d.field(ty, $.Decoders.f64)),
+			3 => JsValueString(d.field(ty, $.Decoders.str)),
+			4 => JsValueArray(d.field(ty, $.Decoders.list(JsValue.$decoder))),
+			5 => JsValueObject(d.field(ty, $.Decoders.table($.Decoders.str, JsValue.$decoder))),
+			_ => throw $.ProtocolError('unknown variant: $id'),
+		};
+	});
+}
+
+final class JsValueNull extends JsValue {
+	const JsValueNull();
+}
+
+final class JsValueBool extends JsValue {
+	final bool value;
+	const JsValueBool(this.value);
+}
+
+final class JsValueNumber extends JsValue {
+	final double value;
+	const JsValueNumber(this.value);
+}
+
+final class JsValueString extends JsValue {
+	final String value;
+	const JsValueString(this.value);
+}
+
+final class JsValueArray extends JsValue {
+	final List<JsValue> value;
+	const JsValueArray(this.value);
+}
+
+final class JsValueObject extends JsValue {
+	final Map<String, JsValue> value;
+	const JsValueObject(this.value);
+}
+
+enum Numerical {
+	A(1),
+	B(2),
+	C(3);
+
+	final int value;
+	const Numerical(this.value);
+
+	static final $encoder = $.Encoder<Numerical>($.DataType.u8, (e, z) {
+		$.Encoders.u8(e, z.value);
+	});
+
+	static final $decoder = $.Decoder<Numerical>($.DataType.u8, (d) {
+		return switch ($.Decoders.u8(d)) {
+			1 => Numerical.A,
+			2 => Numerical.B,
+			3 => Numerical.C,
+			final tag => throw $.ProtocolError('unknown tag: $tag'),
+		};
+	});
+}
+
+/// The response message containing the greetings.
+final class HelloReply {
+	final String message;
+
+	const HelloReply({
+		required this.message,
+	});
+
+	static final $decoder = $.Decoder<HelloReply>($.DataType.struct, (d) {
+		String? $message;
+		$.structDecoder(d, (id, ty) {
+			switch (id) {
+				case 1: $message = d.field(ty, $.Decoders.str);
+				default: return false;
+			}
+			return true;
+		});
+		return HelloReply(
+			message: $.requiredField($message, 'message', 1),
+		);
+	});
+}
+
+/// The request message containing the user's name.
+final class HelloRequest {
+	final String name;
+
+	const HelloRequest({
+		required this.name,
+	});
+
+	static final $encoder = $.Encoder<HelloRequest>($.DataType.struct, (e, z) {
+		$.structEncoder(e, [
+			$.Field(1, z.name, $.Encoders.str),
+		]);
+	});
+}
+
+final class TestSuite {
+	final $.Client client;
+	const TestSuite(this.client);
+
+	Future<HelloReply> say_hello(HelloRequest input, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			1, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, input, HelloRequest.$encoder),
+			]),
+			($d) => $.outputDecoder($d, HelloReply.$decoder, true)!,
+		);
+	}
+
+	/// Returns `a + b`.
+	Future<int> add(int a, int b, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			2, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, a, $.Encoders.i32),
+				$.Field(1, b, $.Encoders.i32),
+			]),
+			($d) => $.outputDecoder($d, $.Decoders.i32, true)!,
+		);
+	}
+
+	Future<int?> find_in_string(String input, String pat, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			3, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, input, $.Encoders.str),
+				$.Field(1, pat, $.Encoders.str),
+			]),
+			($d) => $.outputDecoder($d, $.Decoders.u32, false),
+		);
+	}
+
+	Future<void> print(String msg, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			4, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, msg, $.Encoders.str),
+			]),
+			($d) {},
+		);
+	}
+
+	Future<int> divide(int a, int b, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			10, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, a, $.Encoders.i32),
+				$.Field(1, b, $.Encoders.i32),
+			]),
+			($d) => $.outputDecoder($d, $.Decoders.i32, true)!,
+		);
+	}
+
+	Future<$.Result<int, ParseError>> parse_int(String input, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			13, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, input, $.Encoders.str),
+			]),
+			($d) => $.outputDecoder($d, $.Decoders.result<int, ParseError>($.Decoders.i32, ParseError.$decoder, true), true)!,
+		);
+	}
+
+	Future<Point> mirror(Point point, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			14, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, point, Point.$encoder),
+			]),
+			($d) => $.outputDecoder($d, Point.$decoder, true)!,
+		);
+	}
+
+	Future<(String, String, )> swap((String, String, ) pair, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			15, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, pair, $.Encoder<(String, String, )>($.DataType.struct, (e, z) => $.structEncoder(e, [$.Field(0, z.$1, $.Encoders.char), $.Field(1, z.$2, $.Encoders.str), ]))),
+			]),
+			($d) => $.outputDecoder($d, $.Decoder<(String, String, )>($.DataType.struct, (d) { final z = $.tupleDecoder(d, [(0, $.Decoders.str), (1, $.Decoders.char), ]); return ($.requiredField(z[0] as String?, '0', 0), $.requiredField(z[1] as String?, '1', 1), ); }), true)!,
+		);
+	}
+
+	Future<Shape> scale(Shape shape, int factor, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			16, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, shape, Shape.$encoder),
+				$.Field(1, factor, $.Encoders.i32),
+			]),
+			($d) => $.outputDecoder($d, Shape.$decoder, true)!,
+		);
+	}
+
+	Future<Note> edit(Note note, String editor, String text, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			17, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, note, Note.$encoder),
+				$.Field(1, editor, $.Encoders.str),
+				$.Field(2, text, $.Encoders.str),
+			]),
+			($d) => $.outputDecoder($d, Note.$decoder, true)!,
+		);
+	}
+
+	Future<List<Score>> rank(List<Score> scores, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			18, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, scores, $.Encoders.columns<Score>([(0, $.Encoders.str, false), (1, $.Encoders.u32, false), (2, $.Encoders.boolean, false), (3, $.Encoders.str, true), ], (z) => [z.player, z.points, z.online, z.team, ])),
+			]),
+			($d) => $.outputDecoder($d, $.Decoders.columns<Score>([(0, $.Decoders.str, false), (1, $.Decoders.u32, false), (2, $.Decoders.boolean, false), (3, $.Decoders.str, true), ], (z) => Score(player: $.requiredField(z[0] as String?, 'player', 0), points: $.requiredField(z[1] as int?, 'points', 1), online: $.requiredField(z[2] as bool?, 'online', 2), team: z[3] as String?, )), true)!,
+		);
+	}
+
+	Future<List<Point>> mirror_all(List<Point> points, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			19, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, points, $.Encoders.columns<Point>([(0, $.Encoders.i32, false), (1, $.Encoders.i32, false), ], (z) => [z.$0, z.$1, ])),
+			]),
+			($d) => $.outputDecoder($d, $.Decoders.columns<Point>([(0, $.Decoders.i32, false), (1, $.Decoders.i32, false), ], (z) => Point($.requiredField(z[0] as int?, '0', 0), $.requiredField(z[1] as int?, '1', 1), )), true)!,
+		);
+	}
+
+	Future<(BigInt, BigInt, )> echo_wide(BigInt unsigned, BigInt signed, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			20, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, unsigned, $.Encoders.u128),
+				$.Field(1, signed, $.Encoders.i128),
+			]),
+			($d) => $.outputDecoder($d, $.Decoder<(BigInt, BigInt, )>($.DataType.struct, (d) { final z = $.tupleDecoder(d, [(0, $.Decoders.u128), (1, $.Decoders.i128), ]); return ($.requiredField(z[0] as BigInt?, '0', 0), $.requiredField(z[1] as BigInt?, '1', 1), ); }), true)!,
+		);
+	}
+
+	Future<BigInt> delay(BigInt millis, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			21, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, millis, $.Encoders.u64),
+			]),
+			($d) => $.outputDecoder($d, $.Decoders.u64, true)!,
+		);
+	}
+
+	Future<void> store(String msg, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			5, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, msg, $.Encoders.str),
+			]),
+			($d) {},
+		);
+	}
+
+	Future<String?> load([$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			6, ctx,
+			($e) => $.structEncoder($e, []),
+			($d) => $.outputDecoder($d, $.Decoders.str, false),
+		);
+	}
+
+	Future<String> what_is_my_ip([$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			7, ctx,
+			($e) => $.structEncoder($e, []),
+			($d) => $.outputDecoder($d, $.Decoders.str, true)!,
+		);
+	}
+
+	$.Sse<int, String> fetch_user_ids(int count, [$.Context ctx = const $.Context()]) {
+		return client.sse(
+			8, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, count, $.Encoders.u8),
+			]),
+			($d) => $.outputDecoder($d, $.Decoders.u8, true)!,
+			($d) => $.outputDecoder($d, $.Decoders.str, true)!,
+		);
+	}
+
+	$.Uni<String, int, void> process_msg([$.Context ctx = const $.Context()]) {
+		return client.uni(
+			9, ctx,
+			($e) => $.structEncoder($e, []),
+			($e, z) => $.structEncoder($e, [$.Field(0, z, $.Encoders.str)]),
+			($e, z) => $.structEncoder($e, [$.Field(0, z, $.Encoders.u8)]),
+			($d) {},
+		);
+	}
+
+	$.Uni<int, int, int> sum_numbers([$.Context ctx = const $.Context()]) {
+		return client.uni(
+			11, ctx,
+			($e) => $.structEncoder($e, []),
+			($e, z) => $.structEncoder($e, [$.Field(0, z, $.Encoders.i32)]),
+			($e, z) => $.structEncoder($e, [$.Field(0, z, $.Encoders.i32)]),
+			($d) => $.outputDecoder($d, $.Decoders.i32, true)!,
+		);
+	}
+
+	$.Bidi<String, void, String, int> chat(String prefix, [$.Context ctx = const $.Context()]) {
+		return client.bidi(
+			12, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, prefix, $.Encoders.str),
+			]),
+			($e, z) => $.structEncoder($e, [$.Field(0, z, $.Encoders.str)]),
+			($e, z) => $.structEncoder($e, []),
+			($d) => $.outputDecoder($d, $.Decoders.str, true)!,
+			($d) => $.outputDecoder($d, $.Decoders.u32, true)!,
+		);
+	}
+
+	Future<Data> random_data([$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			101, ctx,
+			($e) => $.structEncoder($e, []),
+			($d) => $.outputDecoder($d, Data.$decoder, true)!,
+		);
+	}
+
+	Future<Data> echo_data(Data input, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			102, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, input, Data.$encoder),
+			]),
+			($d) => $.outputDecoder($d, Data.$decoder, true)!,
+		);
+	}
+
+	Future<bool> compare_data(Data left, Data right, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			103, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, left, Data.$encoder),
+				$.Field(1, right, Data.$encoder),
+			]),
+			($d) => $.outputDecoder($d, $.Decoders.boolean, true)!,
+		);
+	}
+
+	Future<JsValue> random_js_value([$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			104, ctx,
+			($e) => $.structEncoder($e, []),
+			($d) => $.outputDecoder($d, JsValue.$decoder, true)!,
+		);
+	}
+
+	Future<JsValue> echo_js_value(JsValue input, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			105, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, input, JsValue.$encoder),
+			]),
+			($d) => $.outputDecoder($d, JsValue.$decoder, true)!,
+		);
+	}
+
+	Future<bool> compare_js_value(JsValue left, JsValue right, [$.Context ctx = const $.Context()]) {
+		return client.rpc(
+			106, ctx,
+			($e) => $.structEncoder($e, [
+				$.Field(0, left, JsValue.$encoder),
+				$.Field(1, right, JsValue.$encoder),
+			]),
+			($d) => $.outputDecoder($d, $.Decoders.boolean, true)!,
+		);
+	}
+}
@@ -0,0 +1,257 @@
+// AUTO-GENERATED FILE. DO NOT EDIT.
+
+#[derive(Debug, Clone, PartialEq, ::setu::Message)]
+pub struct Note {
+	#[key = 0]
+	pub text: ::std::string::String,
+	#[key = 10]
+	pub author: ::std::string::String,
+	#[key = 11]
+	pub edited_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, ::setu::Message)]
+#[repr(u8)]
+pub enum ParseError {
+	Empty = 0,
+	Invalid(
+		::std::string::String,
+	) = 1,
+}
+
+#[derive(Debug, Clone, PartialEq, ::setu::Message)]
+pub struct Point(
+	#[key = 0] pub i32,
+	#[key = 1] pub i32,
+);
+
+#[derive(Debug, Clone, PartialEq, ::setu::Message)]
+pub struct Revision {
+	#[key = 10]
+	pub author: ::std::string::String,
+	#[key = 11]
+	pub edited_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, ::setu::Message)]
+pub struct Score {
+	#[key = 0]
+	pub player: ::std::string::String,
+	#[key = 1]
+	pub points: u32,
+	#[key = 2]
+	pub online: bool,
+	#[key = 3]
+	pub team: ::std::option::Option<::std::string::String>,
+}
+
+#[derive(Debug, Clone, PartialEq, ::setu::Message)]
+#[repr(u8)]
+pub enum Shape {
+	Dot = 0,
+	Circle {
+		#[key = 0]
+		center: Point,
+		#[key = 1]
+		radius: f64,
+		/// Fill color, as `0xRRGGBB`.
+		#[key = 2]
+		fill: ::std::option::Option<u32>,
+	} = 1,
+	Line(
+		Point,
+		Point,
+	) = 2,
+}
+
+#[derive(Debug, Clone, PartialEq, ::setu::Message)]
+pub struct Data {
+	#[key = 1]
+	pub u8: u8,
+	#[key = 2]
+	pub u16: u16,
+	#[key = 3]
+	pub u32: u32,
+	#[key = 4]
+	pub u64: u64,
+	#[key = 5]
+	pub i8: i8,
+	#[key = 6]
+	pub i16: i16,
+	#[key = 7]
+	pub i32: i32,
+	#[key = 8]
+	pub i64: i64,
+	#[key = 9]
+	pub f32: f32,
+	#[key = 10]
+	pub f64: f64,
+	#[key = 11]
+	pub bool: bool,
+	#[key = 12]
+	pub string: ::std::string::String,
+	#[key = 13]
+	pub numeric: Numerical,
+}
+
+#[derive(Debug, Clone, PartialEq, ::setu::Message)]
+#[repr(u8)]
+pub enum JsValue {
+	Null = 0,
+	Bool(
+		bool,
+	) = 1,
+	Number(
+		f64,
+	) = 2,
+	String(
+		::std::string::String,
+	) = 3,
+	Array(
+		::std::vec::Vec<JsValue>,
+	) = 4,
+	Object(
+		::std::collections::HashMap<::std::string::String, JsValue>,
+	) = 5,
+}
+
+#[derive(Debug, Clone, PartialEq, ::setu::Message)]
+#[numeric]
+#[repr(u8)]
+pub enum Numerical {
+	A = 1,
+	B = 2,
+	C = 3,
+}
+
+/// The response message containing the greetings.
+#[derive(Debug, Clone, PartialEq, ::setu::Message)]
+pub struct HelloReply {
+	#[key = 1]
+	pub message: ::std::string::String,
+}
+
+/// The request message containing the user's name.
+#[derive(Debug, Clone, PartialEq, ::setu::Message)]
+pub struct HelloRequest {
+	#[key = 1]
+	pub name: ::std::string::String,
+}
+
+#[derive(Clone)]
+pub struct TestSuite(pub ::setu::client::Client);
+
+impl TestSuite {
+	pub fn new(client: ::setu::client::Client) -> Self {
+		Self(client)
+	}
+
+	pub async fn say_hello(&self, input: HelloRequest) -> ::setu::Result<HelloReply> {
+		self.0.call(1, (input,)).await
+	}
+
+	/// Returns `a + b`.
+	pub async fn add(&self, a: i32, b: i32) -> ::setu::Result<i32> {
+		self.0.call(2, (a, b)).await
+	}
+
+	pub async fn find_in_string(&self, input: ::std::string::String, pat: ::std::string::String) -> ::setu::Result<::std::option::Option<u32>> {
+		self.0.call(3, (input, pat)).await
+	}
+
+	pub async fn print(&self, msg: ::std::string::String) -> ::setu::Result<()> {
+		self.0.call(4, (msg,)).await
+	}
+
+	pub async fn divide(&self, a: i32, b: i32) -> ::setu::Result<i32> {
+		self.0.call(10, (a, b)).await
+	}
+
+	pub async fn parse_int(&self, input: ::std::string::String) -> ::setu::Result<::std::result::Result<i32, ParseError>> {
+		self.0.call(13, (input,)).await
+	}
+
+	pub async fn mirror(&self, point: Point) -> ::setu::Result<Point> {
+		self.0.call(14, (point,)).await
+	}
+
+	pub async fn swap(&self, pair: (char, ::std::string::String, )) -> ::setu::Result<(::std::string::String, char, )> {
+		self.0.call(15, (pair,)).await
+	}
+
+	pub async fn scale(&self, shape: Shape, factor: i32) -> ::setu::Result<Shape> {
+		self.0.call(16, (shape, factor)).await
+	}
+
+	pub async fn edit(&self, note: Note, editor: ::std::string::String, text: ::std::string::String) -> ::setu::Result<Note> {
+		self.0.call(17, (note, editor, text)).await
+	}
+
+	pub async fn rank(&self, scores: ::setu::Columns<Score>) -> ::setu::Result<::setu::Columns<Score>> {
+		self.0.call(18, (scores,)).await
+	}
+
+	pub async fn mirror_all(&self, points: ::setu::Columns<Point>) -> ::setu::Result<::setu::Columns<Point>> {
+		self.0.call(19, (points,)).await
+	}
+
+	pub async fn echo_wide(&self, unsigned: u128, signed: i128) -> ::setu::Result<(u128, i128, )> {
+		self.0.call(20, (unsigned, signed)).await
+	}
+
+	pub async fn delay(&self, millis: u64) -> ::setu::Result<u64> {
+		self.0.call(21, (millis,)).await
+	}
+
+	pub async fn store(&self, msg: ::std::string::String) -> ::setu::Result<()> {
+		self.0.call(5, (msg,)).await
+	}
+
+	pub async fn load(&self) -> ::setu::Result<::std::option::Option<::std::string::String>> {
+		self.0.call(6, ()).await
+	}
+
+	pub async fn what_is_my_ip(&self) -> ::setu::Result<::std::string::String> {
+		self.0.call(7, ()).await
+	}
+
+	pub async fn fetch_user_ids(&self, count: u8) -> ::setu::Result<::setu::Stream<u8, ::std::string::String>> {
+		self.0.sse(8, (count,)).await
+	}
+
+	pub async fn process_msg(&self) -> ::setu::Result<(::setu::client::Sender<::std::string::String, u8>, ::setu::client::Response<()>)> {
+		self.0.client_stream(9, ()).await
+	}
+
+	pub async fn sum_numbers(&self) -> ::setu::Result<(::setu::client::Sender<i32, i32>, ::setu::client::Response<i32>)> {
+		self.0.client_stream(11, ()).await
+	}
+
+	pub async fn chat(&self, prefix: ::std::string::String) -> ::setu::Result<(::setu::client::Sender<::std::string::String, ()>, ::setu::Stream<::std::string::String, u32>)> {
+		self.0.bidi(12, (prefix,)).await
+	}
+
+	pub async fn random_data(&self) -> ::setu::Result<Data> {
+		self.0.call(101, ()).await
+	}
+
+	pub async fn echo_data(&self, input: Data) -> ::setu::Result<Data> {
+		self.0.call(102, (input,)).await
+	}
+
+	pub async fn compare_data(&self, left: Data, right: Data) -> ::setu::Result<bool> {
+		self.0.call(103, (left, right)).await
+	}
+
+	pub async fn random_js_value(&self) -> ::setu::Result<JsValue> {
+		self.0.call(104, ()).await
+	}
+
+	pub async fn echo_js_value(&self, input: JsValue) -> ::setu::Result<JsValue> {
+		self.0.call(105, (input,)).await
+	}
+
+	pub async fn compare_js_value(&self, left: JsValue, right: JsValue) -> ::setu::Result<bool> {
+		self.0.call(106, (left, right)).await
+	}
+}
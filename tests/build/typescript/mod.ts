@@ -5,10 +5,41 @@ export const $etu = { RPC: $.RPC };
 const $FE = $.lipi.FieldEncoder;
 const $SE = $.lipi.StructEncoder;
 const $SD = $.lipi.StructDecoder;
+const $TE = $.lipi.TupleEncoder;
+const $TD = $.lipi.TupleDecoder;
 const $OD = $.lipi.OutputDecoder;
 const $ED = $.lipi.EnumDecoder;
 
 const $E = {
+	Note: function Struct(this: $.lipi.Encode, z: Note) {
+		let _ = this;
+		$SE(_, [
+			[0, z.text, _.Str],
+			[10, z.author, _.Str],
+			[11, z.edited_at, _.U64],
+		]);
+	},
+	Point: function Struct(this: $.lipi.Encode, z: Point) {
+		let _ = this;
+		$TE(_, z, [[0, _.I32], [1, _.I32], ]);
+	},
+	Score: function Struct(this: $.lipi.Encode, z: Score) {
+		let _ = this;
+		$SE(_, [
+			[0, z.player, _.Str],
+			[1, z.points, _.U32],
+			[2, z.online, _.Bool],
+			[3, z.team, _.Str],
+		]);
+	},
+	Shape: function Union(this: $.lipi.Encode, z: Shape) {
+		let _ = this;
+		switch (z.type) {
+			case "Dot": return $FE(_, [0, false, _.Bool]);
+			case "Circle": return $FE(_, [1, z, _.Struct([[0, "center", $E.Point], [1, "radius", _.F64], [2, "fill", _.U32], ])]);
+			case "Line": return $FE(_, [2, z.value, _.Tuple([[0, $E.Point], [1, $E.Point], ])]);
+		}
+	},
 	Data: function Struct(this: $.lipi.Encode, z: Data) {
 		let _ = this;
 		$SE(_, [
@@ -49,6 +80,42 @@ const $E = {
 	},
 }
 const $D = {
+	Note: function Struct(this: $.lipi.Decode): Note {
+		let _ = this;
+		return $SD(_, [
+			[0, "text", _.Str, 1],
+			[10, "author", _.Str, 1],
+			[11, "edited_at", _.U64, 1],
+		]);
+	},
+	ParseError: function Union(this: $.lipi.Decode): ParseError {
+		let _ = this;
+		return $ED(_, [
+			[0, "Empty", _.Bool, 0],
+			[1, "Invalid", _.Str, 1],
+		]);
+	},
+	Point: function Struct(this: $.lipi.Decode): Point {
+		let _ = this;
+		return $TD(_, [[0, _.I32, 1], [1, _.I32, 1], ]);
+	},
+	Score: function Struct(this: $.lipi.Decode): Score {
+		let _ = this;
+		return $SD(_, [
+			[0, "player", _.Str, 1],
+			[1, "points", _.U32, 1],
+			[2, "online", _.Bool, 1],
+			[3, "team", _.Str, 0],
+		]);
+	},
+	Shape: function Union(this: $.lipi.Decode): Shape {
+		let _ = this;
+		return $ED(_, [
+			[0, "Dot", _.Bool, 0],
+			[1, "Circle", _.Struct([[0, "center", $D.Point, 1], [1, "radius", _.F64, 1], [2, "fill", _.U32, 0], ]), 2],
+			[2, "Line", _.Tuple([[0, $D.Point, 1], [1, $D.Point, 1], ]), 1],
+		]);
+	},
 	Data: function Struct(this: $.lipi.Decode): Data {
 		let _ = this;
 		return $SD(_, [
@@ -94,6 +161,34 @@ const $D = {
 		]);
 	},
 }
+export interface Note {
+	text: string;
+	author: string;
+	edited_at: bigint;
+}
+export type ParseError =
+	| { type: "Empty" }
+	| { type: "Invalid"; value: string }
+
+export type Point = [
+	number,
+	number,
+];
+export interface Revision {
+	author: string;
+	edited_at: bigint;
+}
+export interface Score {
+	player: string;
+	points: number;
+	online: boolean;
+	team?: string;
+}
+export type Shape =
+	| { type: "Dot" }
+	| { type: "Circle"; center: Point; radius: number; fill?: number }
+	| { type: "Line"; value: [Point, Point, ] }
+
 export interface Data {
 	u8: number;
 	u16: number;
@@ -171,7 +266,117 @@ export function print(msg: string, ctx: $.Context = {}) {
 	return $.rpc(
 		4, ctx,
 		_ => $SE(_, [[0, msg, _.Str]]),
-		_ => { }
+		_ => { },
+	);
+}
+
+export interface divide {
+	a: number,
+	b: number,
+}
+export function divide(z: divide, ctx: $.Context = {}) {
+	return $.rpc(
+		10, ctx,
+		_ => $SE(_, [
+			[0, z.a, _.I32],
+			[1, z.b, _.I32],
+		]),
+		_ => $OD(_, _.I32, true),
+	);
+}
+
+export function parse_int(input: string, ctx: $.Context = {}) {
+	return $.rpc(
+		13, ctx,
+		_ => $SE(_, [[0, input, _.Str]]),
+		_ => $OD(_, _.Result(_.I32, $D.ParseError, true), true),
+	);
+}
+
+export function mirror(point: Point, ctx: $.Context = {}) {
+	return $.rpc(
+		14, ctx,
+		_ => $SE(_, [[0, point, $E.Point]]),
+		_ => $OD(_, $D.Point, true),
+	);
+}
+
+export function swap(pair: [string, string, ], ctx: $.Context = {}) {
+	return $.rpc(
+		15, ctx,
+		_ => $SE(_, [[0, pair, _.Tuple([[0, _.Char], [1, _.Str], ])]]),
+		_ => $OD(_, _.Tuple([[0, _.Str, 1], [1, _.Char, 1], ]), true),
+	);
+}
+
+export interface scale {
+	shape: Shape,
+	factor: number,
+}
+export function scale(z: scale, ctx: $.Context = {}) {
+	return $.rpc(
+		16, ctx,
+		_ => $SE(_, [
+			[0, z.shape, $E.Shape],
+			[1, z.factor, _.I32],
+		]),
+		_ => $OD(_, $D.Shape, true),
+	);
+}
+
+export interface edit {
+	note: Note,
+	editor: string,
+	text: string,
+}
+export function edit(z: edit, ctx: $.Context = {}) {
+	return $.rpc(
+		17, ctx,
+		_ => $SE(_, [
+			[0, z.note, $E.Note],
+			[1, z.editor, _.Str],
+			[2, z.text, _.Str],
+		]),
+		_ => $OD(_, $D.Note, true),
+	);
+}
+
+export function rank(scores: Array<Score>, ctx: $.Context = {}) {
+	return $.rpc(
+		18, ctx,
+		_ => $SE(_, [[0, scores, _.Columns([[0, "player", _.Str, 1], [1, "points", _.U32, 1], [2, "online", _.Bool, 1], [3, "team", _.Str, 0], ])]]),
+		_ => $OD(_, _.Columns([[0, "player", _.Str, 1], [1, "points", _.U32, 1], [2, "online", _.Bool, 1], [3, "team", _.Str, 0], ]), true),
+	);
+}
+
+export function mirror_all(points: Array<Point>, ctx: $.Context = {}) {
+	return $.rpc(
+		19, ctx,
+		_ => $SE(_, [[0, points, _.TupleColumns([[0, "0", _.I32, 1], [1, "1", _.I32, 1], ])]]),
+		_ => $OD(_, _.TupleColumns([[0, "0", _.I32, 1], [1, "1", _.I32, 1], ]), true),
+	);
+}
+
+export interface echo_wide {
+	unsigned: bigint,
+	signed: bigint,
+}
+export function echo_wide(z: echo_wide, ctx: $.Context = {}) {
+	return $.rpc(
+		20, ctx,
+		_ => $SE(_, [
+			[0, z.unsigned, _.U128],
+			[1, z.signed, _.I128],
+		]),
+		_ => $OD(_, _.Tuple([[0, _.U128, 1], [1, _.I128, 1], ]), true),
+	);
+}
+
+export function delay(millis: bigint, ctx: $.Context = {}) {
+	return $.rpc(
+		21, ctx,
+		_ => $SE(_, [[0, millis, _.U64]]),
+		_ => $OD(_, _.U64, true),
 	);
 }
 
@@ -179,7 +384,7 @@ export function store(msg: string, ctx: $.Context = {}) {
 	return $.rpc(
 		5, ctx,
 		_ => $SE(_, [[0, msg, _.Str]]),
-		_ => { }
+		_ => { },
 	);
 }
 
@@ -212,12 +417,33 @@ export function process_msg(ctx: $.Context = {}) {
 	return $.uni(
 		9, ctx,
 		_ => $SE(_, []),
-		(_, z: number) => $SE(_, [[0, z, _.F32]]),
-		(_, z: number) => $SE(_, [[0, z, _.F32]]),
+		(_, z: string) => $SE(_, [[0, z, _.Str]]),
+		(_, z: number) => $SE(_, [[0, z, _.U8]]),
 		_ => { },
 	);
 }
 
+export function sum_numbers(ctx: $.Context = {}) {
+	return $.uni(
+		11, ctx,
+		_ => $SE(_, []),
+		(_, z: number) => $SE(_, [[0, z, _.I32]]),
+		(_, z: number) => $SE(_, [[0, z, _.I32]]),
+		_ => $OD(_, _.I32, true),
+	);
+}
+
+export function chat(prefix: string, ctx: $.Context = {}) {
+	return $.bi(
+		12, ctx,
+		_ => $SE(_, [[0, prefix, _.Str]]),
+		(_, z: string) => $SE(_, [[0, z, _.Str]]),
+		(_, _z: void) => $SE(_, []),
+		_ => $OD(_, _.Str, true),
+		_ => $OD(_, _.U32, true),
+	);
+}
+
 export function random_data(ctx: $.Context = {}) {
 	return $.rpc(
 		101, ctx,
@@ -1,12 +1,24 @@
-use setu_codegen::{Context, type_info::TypeInfo, typescript};
-use std::path::PathBuf;
+use setu_codegen::{Context, dart, rust, type_info::TypeInfo, typescript};
+use std::{fs, path::PathBuf};
 use test_suite::TestSuite;
 
 fn main() {
     let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     let info = Context::new(TypeInfo::from::<TestSuite>());
 
+    fs::write(dir.join("build/test_suite.schema"), info.info.to_schema()).unwrap();
+
     typescript::Config::out_dir(dir.join("build/typescript"))
         .generate(&info)
         .unwrap();
+
+    rust::Config::out_dir(dir.join("build/rust"))
+        .client_name("TestSuite")
+        .generate(&info)
+        .unwrap();
+
+    dart::Config::out_dir(dir.join("build/dart"))
+        .client_name("TestSuite")
+        .generate(&info)
+        .unwrap();
 }
@@ -1,9 +1,167 @@
-use setu::Context;
+use setu::{Columns, Context, Message, RpcError, Status};
+use std::{
+    cmp::Reverse,
+    time::{Duration, SystemTime},
+};
 
 pub async fn add(a: i32, b: i32) -> i32 {
     a + b
 }
 
+pub async fn divide(a: i32, b: i32) -> Result<i32, RpcError> {
+    if b == 0 {
+        return Err(RpcError::new(Status::InvalidArgument, "division by zero").with_details(&a)?);
+    }
+    Ok(a / b)
+}
+
+#[derive(Debug, Message, PartialEq)]
+#[repr(u8)]
+pub enum ParseError {
+    Empty = 0,
+    Invalid(String) = 1,
+}
+
+/// Unlike [divide], the error is part of the output.
+pub async fn parse_int(input: String) -> Result<i32, ParseError> {
+    if input.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    input.parse().map_err(|_| ParseError::Invalid(input))
+}
+
+#[derive(Debug, Message, PartialEq)]
+pub struct Point(#[key = 0] pub i32, #[key = 1] pub i32);
+
+pub async fn mirror(point: Point) -> Point {
+    Point(-point.0, -point.1)
+}
+
+pub async fn swap(pair: (char, String)) -> (String, char) {
+    (pair.1, pair.0)
+}
+
+/// Returns the arguments as they are, to check 128-bit integers over the wire.
+pub async fn echo_wide(unsigned: u128, signed: i128) -> (u128, i128) {
+    (unsigned, signed)
+}
+
+#[derive(Debug, Message, PartialEq)]
+#[repr(u8)]
+pub enum Shape {
+    Dot = 0,
+    Circle {
+        #[key = 0]
+        center: Point,
+        #[key = 1]
+        radius: f64,
+        /// Fill color, as `0xRRGGBB`.
+        #[key = 2]
+        fill: Option<u32>,
+    } = 1,
+    Line(Point, Point) = 2,
+}
+
+/// Scales the shape by `factor`, around the origin.
+pub async fn scale(shape: Shape, factor: i32) -> Shape {
+    let scale = |Point(x, y)| Point(x * factor, y * factor);
+    match shape {
+        Shape::Dot => Shape::Dot,
+        Shape::Circle {
+            center,
+            radius,
+            fill,
+        } => Shape::Circle {
+            center: scale(center),
+            radius: radius * factor as f64,
+            fill,
+        },
+        Shape::Line(from, to) => Shape::Line(scale(from), scale(to)),
+    }
+}
+
+/// Time as seconds since the unix epoch.
+mod unix_time {
+    use std::{
+        convert::Infallible,
+        time::{Duration, SystemTime},
+    };
+
+    pub fn encode(time: &SystemTime) -> u64 {
+        let since = time.duration_since(SystemTime::UNIX_EPOCH);
+        since.unwrap_or_default().as_secs()
+    }
+
+    pub fn decode(secs: u64) -> Result<SystemTime, Infallible> {
+        Ok(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+    }
+}
+
+#[derive(Debug, Message, PartialEq)]
+pub struct Revision {
+    #[key = 10]
+    #[rename = "author"]
+    pub edited_by: String,
+    #[key = 11]
+    #[with = "unix_time"]
+    pub edited_at: SystemTime,
+}
+
+#[derive(Debug, Message, PartialEq)]
+pub struct Note {
+    #[key = 0]
+    pub text: String,
+    #[flatten]
+    pub revision: Revision,
+    /// Not sent over the wire.
+    #[skip]
+    pub dirty: bool,
+}
+
+/// Replaces the text of the note, as edited by `editor`, a minute later.
+pub async fn edit(note: Note, editor: String, text: String) -> Note {
+    Note {
+        text,
+        revision: Revision {
+            edited_by: editor,
+            edited_at: note.revision.edited_at + Duration::from_secs(60),
+        },
+        dirty: !note.dirty,
+    }
+}
+
+#[derive(Debug, Message, PartialEq)]
+pub struct Score {
+    #[key = 0]
+    pub player: String,
+    #[key = 1]
+    pub points: u32,
+    #[key = 2]
+    pub online: bool,
+    #[key = 3]
+    pub team: Option<String>,
+}
+
+/// Sorts the scores, highest points first.
+pub async fn rank(mut scores: Columns<Score>) -> Columns<Score> {
+    scores.sort_by_key(|score| Reverse(score.points));
+    scores
+}
+
+/// Mirrors each point, same as [mirror].
+pub async fn mirror_all(mut points: Columns<Point>) -> Columns<Point> {
+    for point in points.iter_mut() {
+        *point = Point(-point.0, -point.1);
+    }
+    points
+}
+
+/// Responds after `millis` milliseconds.
+pub async fn delay(millis: u64) -> u64 {
+    nio::sleep(Duration::from_millis(millis)).await;
+    millis
+}
+
 pub async fn find_in_string(str: String, pat: String) -> Option<u32> {
     str.find(&pat).map(|idx| idx as u32)
 }
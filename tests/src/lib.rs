@@ -12,13 +12,25 @@ pub use stateful::*;
 pub use stream::*;
 
 setu::export! {
+    #![reflection]
     as TestSuite;
 
     fn say_hello(input) = 1;
 
+    /// Returns `a + b`.
     fn add(a, b) = 2;
     fn find_in_string(input, pat) = 3;
     fn print(msg) = 4;
+    fn divide(a, b) = 10;
+    fn parse_int(input) = 13;
+    fn mirror(point) = 14;
+    fn swap(pair) = 15;
+    fn scale(shape, factor) = 16;
+    fn edit(note, editor, text) = 17;
+    fn rank(scores) = 18;
+    fn mirror_all(points) = 19;
+    fn echo_wide(unsigned, signed) = 20;
+    fn delay(millis) = 21;
 
     fn store(msg) = 5;
     fn load() = 6;
@@ -27,6 +39,8 @@ setu::export! {
     // stream
     fn fetch_user_ids(count) = 8;
     fn process_msg() = 9;
+    fn sum_numbers() = 11;
+    fn chat(prefix) = 12;
 
     // ----------
     fn random_data() = 101;
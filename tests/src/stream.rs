@@ -24,3 +24,26 @@ pub async fn process_msg(mut s: Stream<String, u8>) {
         }
     }
 }
+
+/// Client stream, returns sum of all numbers, including the final one.
+pub async fn sum_numbers(mut s: Stream<i32, i32>) -> i32 {
+    let mut sum = 0;
+    loop {
+        match s.next().await.unwrap() {
+            ControlFlow::Continue(num) => sum += num,
+            ControlFlow::Break(num) => return sum + num,
+        }
+    }
+}
+
+/// Bidirectional stream, echoes every message with `prefix`, and returns the count of messages.
+pub fn chat(prefix: String, mut s: Stream<String, ()>) -> impl Output {
+    sse! {
+        let mut count: u32 = 0;
+        while let ControlFlow::Continue(msg) = s.next().await.unwrap() {
+            count += 1;
+            yield format!("{prefix}{msg}");
+        }
+        return count;
+    }
+}
@@ -0,0 +1,585 @@
+use futures::channel::oneshot;
+use setu::{
+    Application, DecodeLimits, RpcError, Status,
+    client::Client,
+    interceptor::{Interceptor, Outcome},
+    transport::{HttpServer, http::HttpContext},
+};
+use setu_codegen::type_info::TypeInfo;
+use std::{
+    net::SocketAddr,
+    ops::ControlFlow,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use test_suite::TestSuite;
+
+#[rustfmt::skip]
+#[allow(dead_code)]
+#[path = "../build/rust/mod.rs"]
+mod api;
+
+struct Server {
+    shutdown: oneshot::Sender<()>,
+    handle: nio::JoinHandle<setu::Result<()>>,
+}
+
+impl Server {
+    async fn stop(self) {
+        self.shutdown.send(()).unwrap();
+        self.handle.await.unwrap().unwrap();
+    }
+}
+
+async fn connect() -> (Server, Client) {
+    connect_to(HttpServer::new()).await
+}
+
+async fn connect_to(server: HttpServer) -> (Server, Client) {
+    connect_with(server, Duration::from_secs(1)).await
+}
+
+async fn connect_with(server: HttpServer, grace_period: Duration) -> (Server, Client) {
+    let addr: SocketAddr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let (shutdown, signal) = oneshot::channel();
+    let handle = nio::spawn_local(
+        server
+            .h2c()
+            .addr(addr)
+            .graceful_shutdown(async { signal.await.unwrap() })
+            .grace_period(grace_period)
+            .run(|mut ctx: HttpContext| match ctx.req.get_rpc_key() {
+                Some(id) => TestSuite::execute(id, ctx),
+                None => ctx.res.write_unbound("Hello, World").unwrap(),
+            }),
+    );
+    let server = Server { shutdown, handle };
+
+    for _ in 0..50 {
+        if let Ok(client) = Client::connect(addr).await {
+            return (server, client);
+        }
+        nio::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("failed to connect: {addr}");
+}
+
+#[nio::test]
+async fn unary() {
+    let (server, client) = connect().await;
+
+    let sum: i32 = client.call(2, (40, 2)).await.unwrap();
+    assert_eq!(sum, 42);
+
+    let idx: Option<u32> = client.call(3, ("Hello, World", "World")).await.unwrap();
+    assert_eq!(idx, Some(7));
+
+    let idx: Option<u32> = client.call(3, ("Hello", "World")).await.unwrap();
+    assert_eq!(idx, None);
+
+    let err = client.call::<_, ()>(42, ()).await.unwrap_err();
+    assert!(err.to_string().contains("501"), "{err}");
+
+    drop(client);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn graceful_shutdown() {
+    let (Server { shutdown, handle }, client) =
+        connect_with(HttpServer::new(), Duration::from_millis(300)).await;
+    let api = api::TestSuite::new(client);
+    let started = Instant::now();
+
+    let stop = async {
+        // Both calls are in flight, before the server is draining.
+        nio::sleep(Duration::from_millis(50)).await;
+        shutdown.send(()).unwrap();
+        nio::sleep(Duration::from_millis(50)).await;
+
+        // New streams are refused with `GOAWAY`.
+        let err = api.delay(0).await.unwrap_err();
+        let err = err.downcast::<h2::Error>().unwrap();
+        assert!(err.is_go_away());
+
+        handle.await.unwrap().unwrap();
+    };
+    let (fast, slow, ()) = futures::join!(api.delay(200), api.delay(10_000), stop);
+
+    // Finished during the grace period.
+    assert_eq!(fast.unwrap(), 200);
+
+    // Still running after the grace period.
+    let err = slow.unwrap_err().downcast::<RpcError>().unwrap();
+    assert_eq!(err.status, Status::Unavailable);
+    assert_eq!(err.message.as_deref(), Some("server is shutting down"));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[nio::test]
+async fn server_stream() {
+    let (server, client) = connect().await;
+
+    let mut ids = client.sse::<_, u8, String>(8, (2u8,)).await.unwrap();
+    assert!(matches!(
+        ids.next().await.unwrap(),
+        ControlFlow::Continue(1)
+    ));
+    assert!(matches!(
+        ids.next().await.unwrap(),
+        ControlFlow::Continue(2)
+    ));
+    match ids.next().await.unwrap() {
+        ControlFlow::Break(msg) => assert_eq!(msg, "Bye!"),
+        ControlFlow::Continue(id) => panic!("unexpected id: {id}"),
+    }
+
+    drop(client);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn client_stream() {
+    let (server, client) = connect().await;
+
+    let (mut tx, res) = client
+        .client_stream::<_, &str, u8, ()>(9, ())
+        .await
+        .unwrap();
+
+    tx.send("Hello").await.unwrap();
+    tx.send("World").await.unwrap();
+    tx.end(0).unwrap();
+
+    res.output().await.unwrap();
+
+    drop(client);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn generated_client() {
+    let (server, client) = connect().await;
+    let api = api::TestSuite::new(client);
+
+    let reply = api
+        .say_hello(api::HelloRequest {
+            name: "Setu".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(reply.message, "Hello Setu!");
+
+    assert_eq!(api.add(1, 2).await.unwrap(), 3);
+    assert_eq!(api.load().await.unwrap(), None);
+
+    let value = api::JsValue::Array(vec![
+        api::JsValue::Null,
+        api::JsValue::Number(4.2),
+        api::JsValue::String("Hello".into()),
+    ]);
+    assert_eq!(api.echo_js_value(value.clone()).await.unwrap(), value);
+    assert!(api.compare_js_value(value.clone(), value).await.unwrap());
+
+    let (mut tx, res) = api.process_msg().await.unwrap();
+    tx.send("Hello".into()).await.unwrap();
+    tx.end(0).unwrap();
+    res.output().await.unwrap();
+
+    drop(api);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn compression() {
+    let (server, client) = connect().await;
+
+    for &encoding in setu::Encoding::ALL {
+        let api = api::TestSuite::new(client.clone().compression(encoding));
+
+        let value = api::JsValue::Array(vec![api::JsValue::String("Setu ".repeat(1024)); 4]);
+        assert_eq!(api.echo_js_value(value.clone()).await.unwrap(), value);
+
+        let idx = api
+            .find_in_string("Setu ".repeat(1024) + "RPC", "RPC".into())
+            .await
+            .unwrap();
+        assert_eq!(idx, Some(5 * 1024));
+
+        let (mut tx, res) = api.process_msg().await.unwrap();
+        tx.send("Hello ".repeat(1024)).await.unwrap();
+        tx.end(0).unwrap();
+        res.output().await.unwrap();
+    }
+
+    drop(client);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn chunked_frames() {
+    let (server, client) = connect().await;
+    let api = api::TestSuite::new(client);
+
+    // Larger than an HTTP/2 data frame, so they are received in multiple chunks.
+    let text = "Setu ".repeat(64 * 1024);
+
+    let idx = api
+        .find_in_string(text.clone() + "RPC", "RPC".into())
+        .await
+        .unwrap();
+    assert_eq!(idx, Some(5 * 64 * 1024));
+
+    let value = api::JsValue::Array(vec![api::JsValue::String(text.clone()); 2]);
+    assert_eq!(api.echo_js_value(value.clone()).await.unwrap(), value);
+
+    let (mut tx, mut rx) = api.chat("> ".into()).await.unwrap();
+    tx.send(text.clone()).await.unwrap();
+    let ControlFlow::Continue(reply) = rx.next().await.unwrap() else {
+        panic!("expected message");
+    };
+    assert_eq!(reply, format!("> {text}"));
+    tx.end(()).unwrap();
+    assert!(matches!(rx.next().await.unwrap(), ControlFlow::Break(1)));
+
+    drop(api);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn frame_size_limit() {
+    let (server, client) = connect_to(HttpServer::new().max_frame_size(1024)).await;
+
+    let idx: Option<u32> = client.call(3, ("Hello, World", "World")).await.unwrap();
+    assert_eq!(idx, Some(7));
+
+    // Rejected by the server.
+    let err = client
+        .call::<_, Option<u32>>(3, ("Setu ".repeat(1024), "RPC"))
+        .await
+        .unwrap_err();
+    let err = err.downcast::<RpcError>().unwrap();
+    assert_eq!(err.status, Status::ResourceExhausted);
+
+    // Rejected by the client, before sending.
+    let err = client
+        .clone()
+        .max_frame_size(1024)
+        .call::<_, Option<u32>>(3, ("Setu ".repeat(1024), "RPC"))
+        .await
+        .unwrap_err();
+    let err = err.downcast::<RpcError>().unwrap();
+    assert_eq!(err.status, Status::ResourceExhausted);
+
+    // Compressed frame is smaller than the limit, but not once it's decompressed.
+    for &encoding in setu::Encoding::ALL {
+        let err = client
+            .clone()
+            .compression(encoding)
+            .call::<_, Option<u32>>(3, ("Setu ".repeat(1024), "RPC"))
+            .await
+            .unwrap_err();
+        let err = err.downcast::<RpcError>().unwrap();
+        assert_eq!(err.status, Status::ResourceExhausted);
+    }
+
+    drop(client);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn decode_limits() {
+    let limits = DecodeLimits {
+        max_depth: 16,
+        ..DecodeLimits::default()
+    };
+    let (server, client) = connect_to(HttpServer::new().decode_limits(limits)).await;
+    let api = api::TestSuite::new(client);
+
+    let nested = |depth| {
+        (0..depth).fold(api::JsValue::Null, |value, _| {
+            api::JsValue::Array(vec![value])
+        })
+    };
+    assert_eq!(api.echo_js_value(nested(4)).await.unwrap(), nested(4));
+
+    let err = api.echo_js_value(nested(32)).await.unwrap_err();
+    assert!(err.to_string().contains("400"), "{err}");
+
+    drop(api);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn rpc_error() {
+    let (server, client) = connect().await;
+    let api = api::TestSuite::new(client);
+
+    assert_eq!(api.divide(42, 2).await.unwrap(), 21);
+
+    let err = api.divide(42, 0).await.unwrap_err();
+    let err = err.downcast::<RpcError>().unwrap();
+    assert_eq!(err.status, Status::InvalidArgument);
+    assert_eq!(err.message.as_deref(), Some("division by zero"));
+    assert_eq!(err.decode_details::<i32>().unwrap().unwrap(), 42);
+
+    drop(api);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn result_output() {
+    let (server, client) = connect().await;
+    let api = api::TestSuite::new(client);
+
+    assert_eq!(api.parse_int("-7".into()).await.unwrap(), Ok(-7));
+    assert_eq!(
+        api.parse_int("".into()).await.unwrap(),
+        Err(api::ParseError::Empty)
+    );
+    assert_eq!(
+        api.parse_int("seven".into()).await.unwrap(),
+        Err(api::ParseError::Invalid("seven".into()))
+    );
+
+    drop(api);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn tuple_and_char() {
+    let (server, client) = connect().await;
+    let api = api::TestSuite::new(client);
+
+    assert_eq!(
+        api.mirror(api::Point(3, -4)).await.unwrap(),
+        api::Point(-3, 4)
+    );
+    assert_eq!(
+        api.swap(('老', "tiger".into())).await.unwrap(),
+        ("tiger".into(), '老')
+    );
+
+    drop(api);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn wide_integers() {
+    let (server, client) = connect().await;
+    let api = api::TestSuite::new(client);
+
+    for (unsigned, signed) in [(0, 0), (u128::MAX, i128::MAX), (u128::MAX, i128::MIN)] {
+        let echoed = api.echo_wide(unsigned, signed).await.unwrap();
+        assert_eq!(echoed, (unsigned, signed));
+    }
+
+    drop(api);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn struct_and_tuple_variants() {
+    let (server, client) = connect().await;
+    let api = api::TestSuite::new(client);
+
+    assert_eq!(
+        api.scale(api::Shape::Dot, 2).await.unwrap(),
+        api::Shape::Dot
+    );
+
+    let circle = api::Shape::Circle {
+        center: api::Point(1, -2),
+        radius: 1.5,
+        fill: Some(0xff0000),
+    };
+    assert_eq!(
+        api.scale(circle, 2).await.unwrap(),
+        api::Shape::Circle {
+            center: api::Point(2, -4),
+            radius: 3.0,
+            fill: Some(0xff0000),
+        }
+    );
+
+    let line = api::Shape::Line(api::Point(0, 1), api::Point(2, 3));
+    assert_eq!(
+        api.scale(line, -1).await.unwrap(),
+        api::Shape::Line(api::Point(0, -1), api::Point(-2, -3))
+    );
+
+    drop(api);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn field_attributes() {
+    let (server, client) = connect().await;
+    let api = api::TestSuite::new(client);
+
+    let note = api::Note {
+        text: "draft".into(),
+        author: "alice".into(),
+        edited_at: 1_700_000_000,
+    };
+    assert_eq!(
+        api.edit(note, "bob".into(), "final".into()).await.unwrap(),
+        api::Note {
+            text: "final".into(),
+            author: "bob".into(),
+            edited_at: 1_700_000_060,
+        }
+    );
+
+    drop(api);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn columns() {
+    let (server, client) = connect().await;
+    let api = api::TestSuite::new(client);
+
+    let score = |player: &str, points, online, team: Option<&str>| api::Score {
+        player: player.into(),
+        points,
+        online,
+        team: team.map(Into::into),
+    };
+    let scores = setu::Columns(vec![
+        score("alice", 10, true, None),
+        score("bob", 30, false, Some("red")),
+        score("carol", 20, true, Some("blue")),
+    ]);
+    assert_eq!(
+        api.rank(scores).await.unwrap(),
+        setu::Columns(vec![
+            score("bob", 30, false, Some("red")),
+            score("carol", 20, true, Some("blue")),
+            score("alice", 10, true, None),
+        ])
+    );
+    assert!(api.rank(setu::Columns(vec![])).await.unwrap().is_empty());
+
+    // Rows of tuple structs.
+    let points = setu::Columns(vec![api::Point(1, -2), api::Point(0, 3)]);
+    assert_eq!(
+        api.mirror_all(points).await.unwrap(),
+        setu::Columns(vec![api::Point(-1, 2), api::Point(0, -3)])
+    );
+
+    drop(api);
+    server.stop().await;
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<(u32, Outcome)>>>);
+
+impl Interceptor for Recorder {
+    fn complete(&self, id: u32, outcome: &Outcome) {
+        self.0.lock().unwrap().push((id, *outcome));
+    }
+}
+
+struct Deny(u32);
+
+impl Interceptor for Deny {
+    fn intercept(&self, id: u32, _: &mut HttpContext) -> Result<(), RpcError> {
+        if id == self.0 {
+            return Err(RpcError::new(Status::PermissionDenied, "denied"));
+        }
+        Ok(())
+    }
+}
+
+#[nio::test]
+async fn interceptor() {
+    let recorder = Recorder::default();
+    let server = HttpServer::new()
+        .interceptor(recorder.clone())
+        .interceptor(Deny(3));
+
+    let (server, client) = connect_to(server).await;
+
+    let sum: i32 = client.call(2, (40, 2)).await.unwrap();
+    assert_eq!(sum, 42);
+
+    let err = client
+        .call::<_, Option<u32>>(3, ("Hello, World", "World"))
+        .await
+        .unwrap_err();
+    let err = err.downcast::<RpcError>().unwrap();
+    assert_eq!(err.status, Status::PermissionDenied);
+
+    let err = client.call::<_, ()>(42, ()).await.unwrap_err();
+    assert!(err.to_string().contains("501"), "{err}");
+
+    drop(client);
+    server.stop().await;
+
+    let mut outcomes = recorder.0.lock().unwrap().clone();
+    outcomes.sort_by_key(|(id, _)| *id);
+
+    let [(2, add), (3, denied), (42, unknown)] = outcomes[..] else {
+        panic!("unexpected outcomes: {outcomes:?}");
+    };
+    assert_eq!(add.status, Status::Ok);
+    assert!(add.received > 0 && add.sent > 0);
+    assert_eq!(denied.status, Status::PermissionDenied);
+    assert_eq!(denied.received, 0);
+    assert_eq!(unknown.status, Status::Unimplemented);
+}
+
+#[nio::test]
+async fn bidi_stream() {
+    let (server, client) = connect().await;
+    let api = api::TestSuite::new(client);
+
+    let (mut tx, res) = api.sum_numbers().await.unwrap();
+    for num in 1..=4 {
+        tx.send(num).await.unwrap();
+    }
+    tx.end(5).unwrap();
+    assert_eq!(res.output().await.unwrap(), 15);
+
+    let (mut tx, mut rx) = api.chat("> ".into()).await.unwrap();
+    for msg in ["Hello", "World"] {
+        tx.send(msg.into()).await.unwrap();
+        let ControlFlow::Continue(reply) = rx.next().await.unwrap() else {
+            panic!("expected message");
+        };
+        assert_eq!(reply, format!("> {msg}"));
+    }
+    tx.end(()).unwrap();
+    assert!(matches!(rx.next().await.unwrap(), ControlFlow::Break(2)));
+
+    drop(api);
+    server.stop().await;
+}
+
+#[nio::test]
+async fn reflection() {
+    let (server, client) = connect().await;
+
+    let info = client.reflect().await.unwrap();
+    let expected = TypeInfo::from::<TestSuite>();
+    assert_eq!(info.to_schema(), expected.to_schema());
+    assert_eq!(info.fingerprint(), expected.fingerprint());
+
+    let add = info.fns.iter().find(|f| &*f.meta.ident == "add").unwrap();
+    assert_eq!(add.meta.docs, "Returns `a + b`.");
+    assert_eq!(&*add.meta.args, ["a".into(), "b".into()]);
+
+    let request = &info.registry["test_suite::greeting::HelloRequest"];
+    assert_eq!(
+        request.attrs.docs,
+        "The request message containing the user's name."
+    );
+
+    drop(client);
+    server.stop().await;
+}
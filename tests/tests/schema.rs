@@ -0,0 +1,21 @@
+use setu_codegen::type_info::{TypeInfo, diff};
+use test_suite::TestSuite;
+
+/// The snapshot is updated by `codegen`, changes since then must be wire-compatible.
+#[test]
+fn compatible_with_snapshot() {
+    let snapshot = TypeInfo::from_schema(include_str!("../build/test_suite.schema")).unwrap();
+    let info = TypeInfo::from::<TestSuite>();
+
+    let breaking: Vec<_> = diff(&snapshot, &info)
+        .into_iter()
+        .filter(|change| change.breaking)
+        .map(|change| change.to_string())
+        .collect();
+    assert!(breaking.is_empty(), "{breaking:#?}");
+
+    let schema = info.to_schema();
+    let parsed = TypeInfo::from_schema(&schema).unwrap();
+    assert_eq!(parsed.to_schema(), schema);
+    assert_eq!(parsed.fingerprint(), info.fingerprint());
+}
@@ -11,6 +11,48 @@ assertEquals(await api.add({ a: 1, b: 2 }), 3);
 assertEquals(await api.find_in_string({ input: "Löwe 老虎 Léopard Gepardi", pat: "é" }), 14);
 assertEquals(await api.find_in_string({ input: "321", pat: "12" }), undefined);
 
+assertEquals(await api.parse_int("-7"), { ok: true, value: -7 });
+assertEquals(await api.parse_int(""), { ok: false, error: { type: "Empty" } });
+assertEquals(await api.parse_int("seven"), { ok: false, error: { type: "Invalid", value: "seven" } });
+assertEquals(await api.mirror([3, -4]), [-3, 4]);
+assertEquals(await api.swap(["老", "tiger"]), ["tiger", "老"]);
+
+const U128_MAX = (1n << 128n) - 1n;
+const I128_MAX = (1n << 127n) - 1n;
+for (const [unsigned, signed] of [[0n, 0n], [U128_MAX, I128_MAX], [U128_MAX, -I128_MAX - 1n]]) {
+    assertEquals(await api.echo_wide({ unsigned, signed }), [unsigned, signed]);
+}
+
+assertEquals(await api.scale({ shape: { type: "Dot" }, factor: 2 }), { type: "Dot" });
+assertEquals(
+    await api.scale({ shape: { type: "Circle", center: [1, -2], radius: 1.5 }, factor: 2 }),
+    { type: "Circle", center: [2, -4], radius: 3 },
+);
+assertEquals(
+    await api.scale({ shape: { type: "Line", value: [[0, 1], [2, 3]] }, factor: -1 }),
+    { type: "Line", value: [[0, -1], [-2, -3]] },
+);
+assertEquals(
+    await api.edit({
+        note: { text: "draft", author: "alice", edited_at: 1700000000n },
+        editor: "bob",
+        text: "final",
+    }),
+    { text: "final", author: "bob", edited_at: 1700000060n },
+);
+
+assertEquals(
+    await api.rank([
+        { player: "alice", points: 10, online: true },
+        { player: "bob", points: 30, online: false, team: "red" },
+    ]),
+    [
+        { player: "bob", points: 30, online: false, team: "red" },
+        { player: "alice", points: 10, online: true },
+    ],
+);
+assertEquals(await api.mirror_all([[1, -2], [0, 3]]), [[-1, 2], [0, -3]]);
+
 await api.print("Hello, World!");
 
 // stateful
@@ -37,6 +79,23 @@ for (let _ of Range(0, 3)) {
 
 let a = api.process_msg();
 
+let sum = await api.sum_numbers();
+for (let num of [1, 2, 3, 4]) {
+    await sum.send(num);
+}
+await sum.sendFinal(5);
+assertEquals(await sum.output(), 15);
+
+// -------------------------- Bidi --------------------------
+
+let chat = await api.chat("> ");
+for (let msg of ["Hello", "World"]) {
+    await chat.send(msg);
+    assertEquals(await chat.next(), { done: false, value: `> ${msg}` });
+}
+await chat.sendFinal();
+assertEquals(await chat.next(), { done: true, value: undefined });
+assertEquals(await chat.output(), 2);
 
 // -------------------------- SSE ---------------------------
 